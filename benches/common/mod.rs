@@ -0,0 +1,15 @@
+/* Made up batting files for the benchmarks, the same every time so
+ * that runs can be compared with each other. They live in a directory
+ * of their own so cargo does not take them for a benchmark. */
+use getting_started::generate::Generator;
+use getting_started::rng::Rng;
+
+/* The sizes every benchmark is run at: a club's worth of players and
+ * a whole country's history */
+pub const SIZES: [usize; 2] = [10_000, 1_000_000];
+
+/* n lines made by batsmen generate --seed 42, like
+ * "KT Ventalmar, 1843, 36.14, 1, 1994, 2003" */
+pub fn lines(n: usize) -> Vec<String> {
+  Generator::new(Rng::new(42)).take(n).collect()
+}
@@ -0,0 +1,48 @@
+/* Times the ways the commands narrow a batting file down. Run it with
+ *
+ *   cargo bench --bench filter
+ *
+ * Each is timed at each of the common::SIZES. */
+#[macro_use]
+extern crate criterion;
+extern crate getting_started;
+
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion};
+
+use getting_started::batsman::Batsman;
+use getting_started::expr::Expr;
+
+mod common;
+
+fn filtering(c: &mut Criterion) {
+  let score = "0.6*average + 0.4*runs/100".parse::<Expr>().expect("the benchmark's expression parses");
+  let mut group = c.benchmark_group("filter");
+  group.sample_size(10);
+
+  for &n in &common::SIZES {
+    let lines = common::lines(n);
+    let batsmen = lines.iter().map(|l| Batsman::from_line(l)).collect::<Vec<Batsman>>();
+
+    /* What the leaderboard does to every file */
+    group.bench_with_input(BenchmarkId::new("surname starts with P", n), &batsmen, |b, batsmen| {
+      b.iter(|| black_box(batsmen.iter().filter(|b| b.surname.starts_with('P')).count()))
+    });
+    /* What --player and the server's /players/<name> do */
+    group.bench_with_input(BenchmarkId::new("matches_name", n), &batsmen, |b, batsmen| {
+      b.iter(|| black_box(batsmen.iter().filter(|b| b.matches_name("Player99")).count()))
+    });
+    group.bench_with_input(BenchmarkId::new("runs over 10000", n), &batsmen, |b, batsmen| {
+      b.iter(|| black_box(batsmen.iter().filter(|b| b.runs > 10_000).count()))
+    });
+    /* --score, worked out for everyone */
+    group.bench_with_input(BenchmarkId::new("score expression", n), &batsmen, |b, batsmen| {
+      b.iter(|| black_box(batsmen.iter().filter_map(|b| score.eval(b).ok()).filter(|&s| s > 50.0).count()))
+    });
+  }
+  group.finish();
+}
+
+criterion_group!(benches, filtering);
+criterion_main!(benches);
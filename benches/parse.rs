@@ -0,0 +1,79 @@
+/* Times reading numbers and whole lines out of batting files of each
+ * of the common::SIZES. Run it with
+ *
+ *   cargo bench --bench parse
+ *   cargo bench --bench parse --features fast-parse
+ *
+ * The "numbers" group always compares str::parse with the parsers in
+ * numeric.rs directly. The "lines" group parses whole Batsmen, so it
+ * uses whichever one the feature picked, and comparing the two runs
+ * shows what the feature is worth end to end. */
+#[macro_use]
+extern crate criterion;
+extern crate getting_started;
+
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion};
+
+use getting_started::batsman::Batsman;
+use getting_started::numeric::Number;
+
+mod common;
+
+fn numbers(c: &mut Criterion) {
+  let lines = common::lines(common::SIZES[1]);
+  let items = lines.iter().map(|l| {
+    let v = l.split(',').map(|x| x.trim()).collect::<Vec<&str>>();
+    (v[1], v[2])
+  }).collect::<Vec<(&str, &str)>>();
+
+  /* The fast parsers are only any use if they agree with str::parse */
+  for &(runs, average) in &items {
+    assert_eq!(u32::parse_fast(runs), runs.parse::<u32>());
+    assert_eq!(f32::parse_fast(average), average.parse::<f32>());
+  }
+
+  let mut group = c.benchmark_group("numbers");
+  group.sample_size(10);
+  group.bench_function("u32 str::parse", |b| b.iter(|| {
+    for &(runs, _) in &items {
+      black_box(runs.parse::<u32>().ok());
+    }
+  }));
+  group.bench_function("u32 parse_fast", |b| b.iter(|| {
+    for &(runs, _) in &items {
+      black_box(u32::parse_fast(runs).ok());
+    }
+  }));
+  group.bench_function("f32 str::parse", |b| b.iter(|| {
+    for &(_, average) in &items {
+      black_box(average.parse::<f32>().ok());
+    }
+  }));
+  group.bench_function("f32 parse_fast", |b| b.iter(|| {
+    for &(_, average) in &items {
+      black_box(f32::parse_fast(average).ok());
+    }
+  }));
+  group.finish();
+}
+
+fn batsmen(c: &mut Criterion) {
+  let name = if cfg!(feature = "fast-parse") { "Batsman::parse (fast-parse)" } else { "Batsman::parse" };
+
+  let mut group = c.benchmark_group("lines");
+  group.sample_size(10);
+  for &n in &common::SIZES {
+    let lines = common::lines(n);
+    group.bench_with_input(BenchmarkId::new(name, n), &lines, |b, lines| b.iter(|| {
+      for l in lines {
+        black_box(Batsman::parse(l).ok());
+      }
+    }));
+  }
+  group.finish();
+}
+
+criterion_group!(benches, numbers, batsmen);
+criterion_main!(benches);
@@ -0,0 +1,131 @@
+/* Compares the ways of sorting a batting file, and of picking the top
+ * of one without sorting all of it. Run it with
+ *
+ *   cargo bench --bench sort
+ *
+ * Every strategy is timed on the same made up batsmen at each of the
+ * common::SIZES, so the numbers are only worth comparing with each
+ * other on one machine.
+ *
+ *   cargo bench --bench sort --features parallel
+ *
+ * adds a "parallel" group timing sorted against rayon's par_sort_by
+ * over a wider spread of sizes, to see where splitting the work up
+ * starts to pay (which is what sort::PARALLEL_THRESHOLD is set from). */
+#[macro_use]
+extern crate criterion;
+extern crate getting_started;
+
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion};
+
+use getting_started::batsman::Batsman;
+use getting_started::sort::{Field, SortSpec, sort_in_place, sorted, sorted_iter, sorted_unstable, top_by_field};
+
+#[cfg(feature = "parallel")]
+extern crate rayon;
+#[cfg(feature = "parallel")]
+use rayon::slice::ParallelSliceMut;
+
+mod common;
+
+/* How many batsmen the top-N benchmarks ask for */
+const TOP: usize = 10;
+
+/* The sizes the parallel group runs at, closer together around where
+ * the two sorts cross over */
+#[cfg(feature = "parallel")]
+const CROSSOVER_SIZES: [usize; 6] = [1_000, 5_000, 20_000, 50_000, 200_000, 1_000_000];
+
+fn sorting(c: &mut Criterion) {
+  let spec = SortSpec::by(Field::Runs).desc();
+  let mut group = c.benchmark_group("sort");
+  group.sample_size(10);
+
+  for &n in &common::SIZES {
+    let lines = common::lines(n);
+    let batsmen = lines.iter().map(|l| Batsman::from_line(l)).collect::<Vec<Batsman>>();
+
+    /* The clone is timed as well, since the vector has to come from
+     * somewhere and cloning it is the cost sorted used to pay */
+    group.bench_with_input(BenchmarkId::new("clone", n), &batsmen, |b, batsmen| {
+      b.iter(|| black_box(batsmen.clone()))
+    });
+    group.bench_with_input(BenchmarkId::new("clone + sorted", n), &batsmen, |b, batsmen| {
+      b.iter(|| black_box(sorted(batsmen.clone(), spec.comparator())))
+    });
+    group.bench_with_input(BenchmarkId::new("clone + sorted_unstable", n), &batsmen, |b, batsmen| {
+      b.iter(|| black_box(sorted_unstable(batsmen.clone(), spec.comparator())))
+    });
+    group.bench_with_input(BenchmarkId::new("clone + sort_in_place", n), &batsmen, |b, batsmen| {
+      b.iter(|| {
+        let mut copy = batsmen.clone();
+        sort_in_place(&mut copy, false, spec.comparator());
+        black_box(copy)
+      })
+    });
+    group.bench_with_input(BenchmarkId::new("sorted_iter, all", n), &batsmen, |b, batsmen| {
+      b.iter(|| black_box(sorted_iter(batsmen, spec.comparator()).count()))
+    });
+  }
+  group.finish();
+}
+
+fn top(c: &mut Criterion) {
+  let spec = SortSpec::by(Field::Runs).desc();
+  let mut group = c.benchmark_group("top 10");
+  group.sample_size(10);
+
+  for &n in &common::SIZES {
+    let lines = common::lines(n);
+    let batsmen = lines.iter().map(|l| Batsman::from_line(l)).collect::<Vec<Batsman>>();
+
+    group.bench_with_input(BenchmarkId::new("clone + sorted + truncate", n), &batsmen, |b, batsmen| {
+      b.iter(|| {
+        let mut all = sorted(batsmen.clone(), spec.comparator());
+        all.truncate(TOP);
+        black_box(all)
+      })
+    });
+    group.bench_with_input(BenchmarkId::new("sorted_iter", n), &batsmen, |b, batsmen| {
+      b.iter(|| black_box(sorted_iter(batsmen, spec.comparator()).take(TOP).count()))
+    });
+    group.bench_with_input(BenchmarkId::new("top_by_field", n), &batsmen, |b, batsmen| {
+      b.iter(|| black_box(top_by_field(batsmen, TOP, "runs", true)))
+    });
+  }
+  group.finish();
+}
+
+/* par_sort_by is called directly rather than through par_sorted, which
+ * would fall back to sorted below the threshold being measured */
+#[cfg(feature = "parallel")]
+fn parallel(c: &mut Criterion) {
+  let spec = SortSpec::by(Field::Runs).desc();
+  let mut group = c.benchmark_group("parallel");
+  group.sample_size(10);
+
+  for &n in &CROSSOVER_SIZES {
+    let lines = common::lines(n);
+    let batsmen = lines.iter().map(|l| Batsman::from_line(l)).collect::<Vec<Batsman>>();
+
+    group.bench_with_input(BenchmarkId::new("clone + sorted", n), &batsmen, |b, batsmen| {
+      b.iter(|| black_box(sorted(batsmen.clone(), spec.comparator())))
+    });
+    group.bench_with_input(BenchmarkId::new("clone + par_sort_by", n), &batsmen, |b, batsmen| {
+      b.iter(|| {
+        let mut copy = batsmen.clone();
+        copy.par_sort_by(spec.comparator());
+        black_box(copy)
+      })
+    });
+  }
+  group.finish();
+}
+
+#[cfg(feature = "parallel")]
+criterion_group!(benches, sorting, top, parallel);
+#[cfg(not(feature = "parallel"))]
+criterion_group!(benches, sorting, top);
+criterion_main!(benches);
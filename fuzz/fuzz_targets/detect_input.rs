@@ -0,0 +1,11 @@
+/* Format detection looks at raw bytes before anything has checked
+ * they are text, so it gets raw bytes here too */
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use getting_started::input::InputFormat;
+
+fuzz_target!(|data: &[u8]| {
+  let _ = InputFormat::detect(data);
+});
@@ -0,0 +1,13 @@
+/* --score and --derive expressions come straight from the command
+ * line, so the expression parser should cope with anything */
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use getting_started::expr::Expr;
+
+fuzz_target!(|data: &[u8]| {
+  if let Ok(text) = std::str::from_utf8(data) {
+    let _ = text.parse::<Expr>();
+  }
+});
@@ -0,0 +1,24 @@
+/* Every line parser, and a whole file's worth through ParsedDataset,
+ * fed whatever text libFuzzer comes up with. They should return an
+ * error for anything they do not understand, never panic. */
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use getting_started::batsman::Batsman;
+use getting_started::bowler::Bowler;
+use getting_started::dataset::ParsedDataset;
+use getting_started::innings::Innings;
+use getting_started::scorecard::ScorecardEntry;
+
+fuzz_target!(|data: &[u8]| {
+  if let Ok(text) = std::str::from_utf8(data) {
+    for line in text.lines() {
+      let _ = Batsman::parse(line);
+      let _ = Bowler::parse(line);
+      let _ = Innings::parse(line);
+      let _ = ScorecardEntry::parse(line);
+    }
+    let _ = ParsedDataset::parse(text.to_string());
+  }
+});
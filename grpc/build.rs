@@ -0,0 +1,13 @@
+/* Generates the message types and service trait from the .proto file.
+ * protox compiles the schema in Rust, so there is no need to have
+ * protoc installed. */
+fn main() {
+  println!("cargo:rerun-if-changed=../proto/records.proto");
+
+  let descriptors = protox::compile(["../proto/records.proto"], ["../proto"])
+    .expect("records.proto should compile");
+  tonic_prost_build::configure()
+    .build_client(false)
+    .compile_fds(descriptors)
+    .expect("code generation should succeed");
+}
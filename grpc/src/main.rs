@@ -0,0 +1,176 @@
+/* A gRPC server for the batting and bowling files, so that programs
+ * written in other languages can query them using the schema in
+ * proto/records.proto.
+ *
+ * This lives in its own crate rather than the main one because tonic
+ * is built on async/await, which needs a newer edition of Rust than
+ * the rest of the tutorial is written in. */
+use std::env;
+use std::fs;
+use std::pin::Pin;
+use std::process::ExitCode;
+use std::sync::Arc;
+
+use tonic::codegen::tokio_stream::{self, Stream};
+use tonic::transport::Server;
+use tonic::{Request, Response, Status};
+
+use getting_started::batsman::Batsman;
+use getting_started::bowler::Bowler;
+use getting_started::error::ParseError;
+use getting_started::numeric::average_f32;
+use getting_started::record::Record;
+use getting_started::sort::sorted;
+
+/* The code generated from records.proto by build.rs */
+mod proto {
+  tonic::include_proto!("batsmen");
+}
+
+use proto::records_server::{Records, RecordsServer};
+use proto::{BatsmenQuery, BatsmenReply, ExportRequest};
+
+type RecordStream<T> = Pin<Box<dyn Stream<Item = Result<T, Status>> + Send>>;
+
+impl<'a> From<&'a Batsman<'a>> for proto::Batsman {
+  fn from(b: &Batsman) -> proto::Batsman {
+    proto::Batsman {
+      initials: b.initials.to_string(),
+      surname: b.surname.to_string(),
+      runs: b.runs,
+      average: average_f32(b.average)
+    }
+  }
+}
+
+impl<'a> From<&'a Bowler<'a>> for proto::Bowler {
+  fn from(b: &Bowler) -> proto::Bowler {
+    proto::Bowler {
+      initials: b.initials.to_string(),
+      surname: b.surname.to_string(),
+      wickets: b.wickets,
+      average: b.average
+    }
+  }
+}
+
+/* Parses every line, turning the first bad one into an error that
+ * says which line it was */
+fn parse<'a, T, F>(contents: &'a str, parse: F) -> Result<Vec<T>, Status>
+  where F: Fn(&'a str) -> Result<T, ParseError>
+{
+  contents.lines().enumerate().map(|(i, l)| {
+    parse(l).map_err(|e| Status::internal(format!("Line {}: {}", i + 1, e)))
+  }).collect()
+}
+
+/* The files being served, read once at startup. Like the HTTP server
+ * in the batsmen binary, only the text is kept and each request parses
+ * it again. */
+struct Service {
+  batting: Arc<String>,
+  bowling: Option<Arc<String>>
+}
+
+#[tonic::async_trait]
+impl Records for Service {
+  async fn query_batsmen(&self, request: Request<BatsmenQuery>) -> Result<Response<BatsmenReply>, Status> {
+    let query = request.into_inner();
+    let mut batsmen = parse(&self.batting, Batsman::parse)?;
+
+    if !query.name.is_empty() {
+      batsmen.retain(|b| b.matches_name(&query.name));
+    }
+    if !query.sort_by.is_empty() {
+      if batsmen.first().is_some_and(|b| b.field(&query.sort_by).is_none()) {
+        return Err(Status::invalid_argument(format!("Unknown field {:?}", query.sort_by)));
+      }
+      batsmen = sorted(batsmen, |lhs, rhs| lhs.cmp_by(rhs, &query.sort_by));
+    }
+    if query.descending {
+      batsmen.reverse();
+    }
+    if query.limit > 0 {
+      batsmen.truncate(query.limit as usize);
+    }
+
+    Ok(Response::new(BatsmenReply {
+      batsmen: batsmen.iter().map(proto::Batsman::from).collect()
+    }))
+  }
+
+  type ExportBatsmenStream = RecordStream<proto::Batsman>;
+
+  async fn export_batsmen(&self, _: Request<ExportRequest>) -> Result<Response<Self::ExportBatsmenStream>, Status> {
+    let batsmen = parse(&self.batting, Batsman::parse)?
+      .iter()
+      .map(|b| Ok(proto::Batsman::from(b)))
+      .collect::<Vec<_>>();
+    Ok(Response::new(Box::pin(tokio_stream::iter(batsmen))))
+  }
+
+  type ExportBowlersStream = RecordStream<proto::Bowler>;
+
+  async fn export_bowlers(&self, _: Request<ExportRequest>) -> Result<Response<Self::ExportBowlersStream>, Status> {
+    let contents = self.bowling.as_ref()
+      .ok_or_else(|| Status::failed_precondition("The server was started without a bowling file"))?;
+    let bowlers = parse(contents, Bowler::parse)?
+      .iter()
+      .map(|b| Ok(proto::Bowler::from(b)))
+      .collect::<Vec<_>>();
+    Ok(Response::new(Box::pin(tokio_stream::iter(bowlers))))
+  }
+}
+
+fn read(path: &str) -> Result<Arc<String>, String> {
+  fs::read_to_string(path).map(Arc::new).map_err(|e| format!("{}: {}", path, e))
+}
+
+/* batsmen-grpc <batting file> [bowling file] [--port 50051] */
+#[tokio::main]
+async fn main() -> ExitCode {
+  let mut args = env::args().skip(1);
+  let mut files = Vec::new();
+  let mut port = 50051;
+  while let Some(arg) = args.next() {
+    if arg == "--port" {
+      match args.next().map(|p| p.parse::<u16>()) {
+        Some(Ok(p)) => port = p,
+        _ => {
+          eprintln!("Expected --port <number>");
+          return ExitCode::from(1);
+        }
+      }
+    } else {
+      files.push(arg);
+    }
+  }
+
+  let service = match files.as_slice() {
+    [batting] => read(batting).map(|batting| Service { batting, bowling: None }),
+    [batting, bowling] => read(batting).and_then(|batting| {
+      read(bowling).map(|bowling| Service { batting, bowling: Some(bowling) })
+    }),
+    _ => {
+      eprintln!("Usage: batsmen-grpc <batting file> [bowling file] [--port 50051]");
+      return ExitCode::from(1);
+    }
+  };
+  let service = match service {
+    Ok(s) => s,
+    Err(e) => {
+      eprintln!("{}", e);
+      return ExitCode::from(2);
+    }
+  };
+
+  let address = ([127, 0, 0, 1], port).into();
+  eprintln!("Serving gRPC on {}", address);
+  match Server::builder().add_service(RecordsServer::new(service)).serve(address).await {
+    Ok(()) => ExitCode::SUCCESS,
+    Err(e) => {
+      eprintln!("{}", e);
+      ExitCode::from(2)
+    }
+  }
+}
@@ -0,0 +1,42 @@
+#ifndef BATSMEN_H
+#define BATSMEN_H
+
+/* Generated from src/ffi.rs by cbindgen, do not edit by hand. See the
+ * top of that file for who owns what. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+typedef struct BatsmenList BatsmenList;
+
+typedef struct CBatsman {
+  const char *initials;
+  const char *surname;
+  uint32_t runs;
+  float average;
+} CBatsman;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+struct BatsmenList *batsmen_parse(const char *text, char **error);
+
+size_t batsmen_len(const struct BatsmenList *list);
+
+struct CBatsman batsmen_get(const struct BatsmenList *list, size_t index);
+
+int32_t batsmen_sort(struct BatsmenList *list, const char *field, bool descending);
+
+void batsmen_free(struct BatsmenList *list);
+
+void batsmen_free_string(char *s);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* BATSMEN_H */
@@ -0,0 +1,42 @@
+use std::cmp::Ordering;
+
+use batsman::Batsman;
+use bowler::Bowler;
+use join::{inner_join, normalize_name};
+use numeric::average_f32;
+use sort::sorted;
+
+/* A player who shows up in both the batting and the bowling data */
+#[derive(Debug, Clone)]
+pub struct AllRounder<'a> {
+  pub batting: Batsman<'a>,
+  pub bowling: Bowler<'a>,
+  pub score: f32
+}
+
+/* The classic rule of thumb for an all-rounder: their batting average
+ * should be higher than their bowling average, so the difference
+ * between the two is a reasonable single number to rank on. */
+pub fn score(batting: &Batsman, bowling: &Bowler) -> f32 {
+  average_f32(batting.average) - bowling.average
+}
+
+/* Joins the two datasets on the player's normalized name and ranks
+ * everyone who appears in both, best first */
+pub fn allrounders<'a>(batting: &[Batsman<'a>],
+                       bowling: &[Bowler<'a>]) -> Vec<AllRounder<'a>> {
+  let joined = inner_join(batting,
+                          bowling,
+                          |b| normalize_name(&format!("{} {}", b.initials, b.surname)),
+                          |b| normalize_name(&format!("{} {}", b.initials, b.surname)));
+
+  sorted(joined.into_iter().map(|(bat, bowl)| {
+    AllRounder {
+      batting: bat.clone(),
+      bowling: bowl.clone(),
+      score: score(bat, bowl)
+    }
+  }).collect::<Vec<AllRounder>>(), |lhs, rhs| {
+    rhs.score.partial_cmp(&lhs.score).unwrap_or(Ordering::Equal)
+  })
+}
@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+
+use batsman::Batsman;
+use join::normalize_name;
+use rng::Rng;
+
+/* Syllables that pseudonymous surnames are built out of. Three of them
+ * give 32,768 possible surnames, plenty for any one dataset. */
+const SYLLABLES: &[&str] = &[
+  "bar", "ben", "cal", "cor", "dal", "den", "fal", "fen",
+  "gar", "gil", "hal", "har", "kel", "lan", "lor", "mar",
+  "mel", "nor", "pen", "ral", "ren", "sal", "sel", "tal",
+  "ten", "tor", "val", "ven", "wal", "wen", "yar", "zel"
+];
+
+/* FNV-1a: a simple hash which, unlike the standard library's hasher,
+ * is guaranteed to give the same answer on every machine and every
+ * version of Rust */
+fn fnv1a(bytes: &[u8]) -> u64 {
+  bytes.iter().fold(0xcbf2_9ce4_8422_2325, |h, &b| {
+    (h ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3)
+  })
+}
+
+/* A made up name like "KT Ventalmar" standing in for a real one. The
+ * same name and salt always give the same pseudonym, and names which
+ * normalize_name considers the same player do too, so a player keeps
+ * their pseudonym across files.
+ *
+ * Without a salt, anybody could work out who is who by trying every
+ * real player's name, so pass a secret salt when the data is going to
+ * be shared. */
+pub fn pseudonym(name: &str, salt: &str) -> String {
+  let key = format!("{}\u{0}{}", salt, normalize_name(name));
+  made_up_name(&mut Rng::new(fnv1a(key.as_bytes())))
+}
+
+/* Two initials and three syllables, picked with rng. pseudonym seeds
+ * it from the real name, and generate.rs from its own seed. */
+pub fn made_up_name(rng: &mut Rng) -> String {
+  let initials = (0..2).map(|_| (b'A' + rng.below(26) as u8) as char).collect::<String>();
+  let surname = (0..3).map(|_| SYLLABLES[rng.below(SYLLABLES.len())]).collect::<String>();
+  let mut chars = surname.chars();
+  let surname = match chars.next() {
+    Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+    None => surname
+  };
+
+  format!("{} {}", initials, surname)
+}
+
+/* Replaces the name at the start of a comma separated line with its
+ * pseudonym, leaving every other field exactly as it was */
+pub fn anonymize_line(line: &str, salt: &str) -> String {
+  match line.find(',') {
+    Some(i) => format!("{}{}", pseudonym(&line[..i], salt), &line[i..]),
+    None if line.trim().is_empty() => line.to_string(),
+    None => pseudonym(line, salt)
+  }
+}
+
+/* The pseudonym of every player in some text, worked out up front so
+ * that records, which borrow their names, can borrow these instead.
+ * Anything which picks players by name, like the leaderboard's
+ * surnames starting with C, does so by their real names first, and
+ * only what is printed gets the pseudonyms:
+ *
+ *   let names = Pseudonyms::of(&contents, "salt");
+ *   let shown = names.batsman(&batsmen[0]);      // KT Ventalmar
+ *
+ * Without any (Pseudonyms::default()) every name stays as it is. */
+#[derive(Debug, Clone, Default)]
+pub struct Pseudonyms {
+  /* Keyed by the name as normalize_name has it, as pseudonym is */
+  names: HashMap<String, String>
+}
+
+impl Pseudonyms {
+  /* For the name at the start of each line of text */
+  pub fn of(text: &str, salt: &str) -> Pseudonyms {
+    let mut names = HashMap::new();
+    for line in text.lines().filter(|l| !l.trim().is_empty()) {
+      let name = line.split(',').next().unwrap_or(line);
+      names.entry(normalize_name(name)).or_insert_with(|| pseudonym(name, salt));
+    }
+    Pseudonyms { names }
+  }
+
+  /* Adds the pseudonyms of more text, for commands which read more
+   * than one file */
+  pub fn extend(&mut self, other: &Pseudonyms) {
+    for (name, pseudonym) in &other.names {
+      self.names.entry(name.clone()).or_insert_with(|| pseudonym.clone());
+    }
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.names.is_empty()
+  }
+
+  /* The pseudonym of a player given as initials and surname, split up
+   * the same way, or the name as it is if they have none */
+  pub fn name<'a>(&'a self, initials: &'a str, surname: &'a str) -> (&'a str, &'a str) {
+    match self.names.get(&normalize_name(&format!("{} {}", initials, surname))) {
+      Some(pseudonym) => pseudonym.split_once(' ').unwrap_or(("", pseudonym)),
+      None => (initials, surname)
+    }
+  }
+
+  /* A batsman as they should be printed */
+  pub fn batsman<'a>(&'a self, b: &Batsman<'a>) -> Batsman<'a> {
+    let (initials, surname) = self.name(b.initials, b.surname);
+    Batsman { initials, surname, ..b.clone() }
+  }
+
+  /* A line with the name at its start swapped for its pseudonym, and
+   * every other field left exactly as it was */
+  pub fn line(&self, line: &str) -> String {
+    let (name, rest) = line.split_at(line.find(',').unwrap_or(line.len()));
+    match self.names.get(&normalize_name(name)) {
+      Some(pseudonym) => format!("{}{}", pseudonym, rest),
+      None => line.to_string()
+    }
+  }
+}
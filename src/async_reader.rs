@@ -0,0 +1,161 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::mem;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+use tokio::io::AsyncBufRead;
+
+use dataset::ParsedDataset;
+use error::ParseError;
+
+/* How many lines go in each dataset the reader hands back, unless
+ * chunk_size says otherwise */
+const DEFAULT_CHUNK: usize = 1024;
+
+/* What can go wrong reading batsmen from a stream: the stream itself
+ * failing (or not being UTF-8), or a line of it not parsing */
+#[derive(Debug)]
+pub enum StreamError {
+  Io(io::Error),
+  Parse {
+    line: usize,
+    error: ParseError
+  }
+}
+
+impl fmt::Display for StreamError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      StreamError::Io(ref e) => write!(f, "{}", e),
+      StreamError::Parse { line, ref error } => write!(f, "Line {}: {}", line, error)
+    }
+  }
+}
+
+impl Error for StreamError {
+}
+
+/* Reads batsmen from anything tokio can read lines from (a file, a
+ * socket, the body of an HTTP response) without blocking the thread
+ * while it waits for more. It is a Stream of ParsedDatasets, each
+ * holding the next chunk_size lines, so
+ *
+ *   let mut reader = AsyncBatsmenReader::new(BufReader::new(file));
+ *   while let Some(chunk) = reader.next().await {
+ *     for b in chunk?.iter() { ... }
+ *   }
+ *
+ * works through a file of any size a chunk at a time. The chunks own
+ * their text, which is what lets them be handed out at all: a Batsman
+ * borrows its names from its line, and the reader moves on from each
+ * line as soon as it has read it.
+ *
+ * A bad line ends its chunk with a StreamError::Parse numbered from the
+ * start of the stream, and the next chunk carries on after it. A BOM
+ * at the start is skipped and CRLF line endings are read as plain ones,
+ * as the blocking readers do. */
+pub struct AsyncBatsmenReader<R> {
+  inner: R,
+  chunk_size: usize,
+  /* The part of a line read so far */
+  line: Vec<u8>,
+  /* The lines of the chunk being put together, and how many */
+  text: String,
+  lines: usize,
+  /* The number of the first line of that chunk, counting from 1 */
+  first: usize,
+  done: bool
+}
+
+impl<R: AsyncBufRead + Unpin> AsyncBatsmenReader<R> {
+  pub fn new(inner: R) -> AsyncBatsmenReader<R> {
+    AsyncBatsmenReader {
+      inner,
+      chunk_size: DEFAULT_CHUNK,
+      line: Vec::new(),
+      text: String::new(),
+      lines: 0,
+      first: 1,
+      done: false
+    }
+  }
+
+  /* Hands the batsmen back n lines at a time. Smaller chunks come out
+   * sooner, bigger ones cost less per line. */
+  pub fn chunk_size(mut self, n: usize) -> AsyncBatsmenReader<R> {
+    self.chunk_size = n.max(1);
+    self
+  }
+
+  /* Adds the line read so far to the chunk */
+  fn end_line(&mut self) -> io::Result<()> {
+    let bytes = mem::take(&mut self.line);
+    let mut line = String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    if line.ends_with('\r') {
+      line.pop();
+    }
+    if self.first == 1 && self.lines == 0 && line.starts_with('\u{feff}') {
+      line.remove(0);
+    }
+    self.text.push_str(&line);
+    self.text.push('\n');
+    self.lines += 1;
+    Ok(())
+  }
+
+  /* Parses the chunk put together so far and starts the next one */
+  fn take_chunk(&mut self) -> Result<ParsedDataset, StreamError> {
+    let first = self.first;
+    self.first += self.lines;
+    self.lines = 0;
+    ParsedDataset::parse(mem::take(&mut self.text))
+      .map_err(|(i, error)| StreamError::Parse { line: first + i - 1, error })
+  }
+}
+
+impl<R: AsyncBufRead + Unpin> Stream for AsyncBatsmenReader<R> {
+  type Item = Result<ParsedDataset, StreamError>;
+
+  fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+    let this = self.get_mut();
+    loop {
+      if this.lines == this.chunk_size || (this.done && this.lines > 0) {
+        return Poll::Ready(Some(this.take_chunk()));
+      }
+      if this.done {
+        return Poll::Ready(None);
+      }
+
+      /* Whatever is buffered is copied up to the end of the line, if
+       * that is in there, and then the buffer is told how much was used */
+      let (used, ended) = match Pin::new(&mut this.inner).poll_fill_buf(cx) {
+        Poll::Pending => return Poll::Pending,
+        Poll::Ready(Err(e)) => return Poll::Ready(Some(Err(StreamError::Io(e)))),
+        Poll::Ready(Ok([])) => {
+          this.done = true;
+          if !this.line.is_empty() {
+            this.end_line().map_err(StreamError::Io)?;
+          }
+          continue;
+        },
+        Poll::Ready(Ok(buf)) => match buf.iter().position(|&b| b == b'\n') {
+          Some(i) => {
+            this.line.extend_from_slice(&buf[..i]);
+            (i + 1, true)
+          },
+          None => {
+            this.line.extend_from_slice(buf);
+            (buf.len(), false)
+          }
+        }
+      };
+      Pin::new(&mut this.inner).consume(used);
+      if ended {
+        this.end_line().map_err(StreamError::Io)?;
+      }
+    }
+  }
+}
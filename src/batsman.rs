@@ -0,0 +1,289 @@
+use alloc::string::ToString;
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use core::fmt;
+
+use error::{ParseError, ValidationError};
+use line;
+use name::matches_name;
+use numeric::{Average, DisplayAverage, average_eq, average_f32};
+use record::{Record, Value};
+
+/* #[derive] is a handy little macro for introspecting our
+ * type and automatically implementing certain type traits
+ * with sensible default implementations. In Rust, there's a
+ * strong mantra that you "don't pay for what you don't use".
+ *
+ * Unlike other languages where you can just print() an object
+ * and it will probably print something sensible because it
+ * generates the code to do so via runtime introspection, Rust
+ * has no runtime and it will not generate that code for you
+ * ahead of time unless you explicitly ask it to. That's what
+ * the Debug trait does.
+ *
+ * Same thing with Clone - this trait signals that we support
+ * making a complete copy of the object and since the implementation
+ * of how that might work would vary between different objects,
+ * the code for that is not generated for you and you have
+ * to either opt-in to using the default implementation
+ * or implement it yourself.
+ */
+#[derive(Debug, Clone)]
+/* <'a> Here is what is called a lifetime parameter. The two
+ * str elements of Batsman are read only, so we can tell the
+ * compiler that we either want them to sit in the .data
+ * section of the binary during the lifetime of the program,
+ * or we can tell the compiler that they should be allocated
+ * on the stack alongside Batsman. This is what what the
+ * default lifetime parameter does if we just create a
+ * new Batsman without specifying where we want it to go. */
+pub struct Batsman<'a> {
+  pub initials: &'a str,
+  pub surname: &'a str,
+  pub runs: u32,
+  pub average: Average
+}
+
+impl<'a> Batsman<'a> {
+  /* Parses a line like "AN Cook, 11629, 46.33, 1". The returned
+   * Batsman borrows its names straight out of the line, which is why
+   * the line has to outlive it. */
+  pub fn parse(l: &'a str) -> Result<Batsman<'a>, ParseError> {
+    let v = line::split(l);
+    /* Initials are optional, as they are for the builder, so Display
+     * writes a batsman without them as just a surname */
+    let (initials, surname) = line::name_or_surname(v[0])?;
+
+    /* Each ? returns early from parse on the first field which is
+     * wrong. Once every field has been read, the builder checks that
+     * they make sense together. */
+    let builder = match initials {
+      Some(initials) => Batsman::builder().initials(initials),
+      None => Batsman::builder()
+    };
+    builder
+      .surname(surname)
+      .runs(line::number(&v, 1, "runs", "u32")?)
+      .average(line::number(&v, 2, "average", AVERAGE_TYPE)?)
+      .build()
+      .map_err(ParseError::Invalid)
+  }
+
+  /* Starts building a Batsman in code, for example
+   *
+   *   Batsman::builder().surname("Cook").runs(12472).average(45.35).build()
+   *
+   * Initials are optional, but everything else has to be given. */
+  pub fn builder() -> BatsmanBuilder<'a> {
+    BatsmanBuilder::default()
+  }
+
+  /* The same as parse, but panics on a bad line. Handy for quick
+   * programs that are happy to fall over on bad input. */
+  pub fn from_line(l: &'a str) -> Batsman<'a> {
+    match Batsman::parse(l) {
+      Ok(b) => b,
+      Err(e) => panic!("{}", e)
+    }
+  }
+
+  /* Whether a name typed in by a user refers to this batsman, so
+   * "Cook", "A Cook" and "AN Cook" all find AN Cook */
+  pub fn matches_name(&self, query: &str) -> bool {
+    matches_name(query, self.initials, self.surname)
+  }
+}
+
+/* Batting files can give the years of a player's first and last
+ * matches after the usual four columns, as in
+ * "AN Cook, 11629, 46.33, 1, 2006, 2018". They are not part of a
+ * Batsman, since most files do not have them, so this reads them
+ * straight from the line. None means the line has no years. */
+pub fn career_span(l: &str) -> Result<Option<(u32, u32)>, ParseError> {
+  let v = line::split(l);
+  if v.len() < 6 {
+    return Ok(None);
+  }
+  Ok(Some((line::number(&v, 4, "debut", "u32")?, line::number(&v, 5, "last", "u32")?)))
+}
+
+/* The canonical way of writing a Batsman as text, "AN Cook, 12472,
+ * 45.35", which parse reads back in as the same Batsman. */
+impl<'a> fmt::Display for Batsman<'a> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    if self.initials.is_empty() {
+      write!(f, "{}, {}, {}", self.surname, self.runs, DisplayAverage(self.average))
+    } else {
+      write!(f, "{} {}, {}, {}", self.initials, self.surname, self.runs, DisplayAverage(self.average))
+    }
+  }
+}
+
+/* You might expect FromStr here, so that "...".parse::<Batsman>()
+ * works. It can't: FromStr::from_str gets a &str with no lifetime we
+ * can name, and Batsman borrows its names from the text it was parsed
+ * from, so there would be nothing for those names to borrow from.
+ * TryFrom lets us tie the lifetimes together instead, so
+ * Batsman::try_from(line) is the equivalent. */
+impl<'a> TryFrom<&'a str> for Batsman<'a> {
+  type Error = ParseError;
+
+  fn try_from(l: &'a str) -> Result<Batsman<'a>, ParseError> {
+    Batsman::parse(l)
+  }
+}
+
+/* Collects the fields of a Batsman one method call at a time. Each
+ * method takes the builder by value and hands it back, which is what
+ * lets the calls be chained together. Nothing is checked until build,
+ * so the fields can be given in any order. */
+#[derive(Debug, Clone, Default)]
+pub struct BatsmanBuilder<'a> {
+  initials: Option<&'a str>,
+  surname: Option<&'a str>,
+  runs: Option<u32>,
+  average: Option<Average>
+}
+
+/* A name has to be something we could write out as part of a line and
+ * read back in as the same name */
+fn check_name(field: &'static str, value: &str) -> Result<(), ValidationError> {
+  if value.is_empty() {
+    Err(ValidationError::EmptyName(field))
+  } else if value.contains(|c: char| c.is_whitespace() || c == ',') {
+    Err(ValidationError::BadName { field, value: value.to_string() })
+  } else {
+    Ok(())
+  }
+}
+
+impl<'a> BatsmanBuilder<'a> {
+  pub fn initials(mut self, initials: &'a str) -> BatsmanBuilder<'a> {
+    self.initials = Some(initials);
+    self
+  }
+
+  pub fn surname(mut self, surname: &'a str) -> BatsmanBuilder<'a> {
+    self.surname = Some(surname);
+    self
+  }
+
+  pub fn runs(mut self, runs: u32) -> BatsmanBuilder<'a> {
+    self.runs = Some(runs);
+    self
+  }
+
+  pub fn average(mut self, average: Average) -> BatsmanBuilder<'a> {
+    self.average = Some(average);
+    self
+  }
+
+  /* Checks the fields and hands back the Batsman. Runs cannot be
+   * negative since they are unsigned, but the average is a float (or
+   * a signed Decimal), so that has to be checked by hand. */
+  pub fn build(self) -> Result<Batsman<'a>, ValidationError> {
+    let surname = self.surname.ok_or(ValidationError::MissingField("surname"))?;
+    let runs = self.runs.ok_or(ValidationError::MissingField("runs"))?;
+    let average = self.average.ok_or(ValidationError::MissingField("average"))?;
+
+    check_name("surname", surname)?;
+    if let Some(initials) = self.initials {
+      check_name("initials", initials)?;
+    }
+    let value = average_f32(average);
+    if !value.is_finite() || value < 0.0 {
+      return Err(ValidationError::BadNumber { field: "average", value });
+    }
+
+    Ok(Batsman {
+      initials: self.initials.unwrap_or(""),
+      surname,
+      runs,
+      average
+    })
+  }
+}
+
+/* This is an implementation for a trait. If a struct supports
+ * certain traits as indicated by whether it implements that
+ * trait, then certain operations will be supported on the type.
+ *
+ * For instance, this type supports the PartialEq trait, standing
+ * for "partial equality". This means that for some of its
+ * attributes we can guarantee that the following properties:
+ *
+ * (1) a == a (reflexive),
+ * (2) b == a -> a == b and vice-versa (symmetric)
+ * (3) b == a, a == c -> a == c (transitive)
+ *
+ * Note that we can't compare equality by just deriving the trait
+ * since Rust doesn't like to directly compare equality between
+ * floating point numbers as there is no "right way to do it". Instead
+ * it is up to you. We are using the within-epsilon method (see
+ * numeric::relative_eq), unless averages are Decimals, which can be
+ * compared exactly.
+ */
+impl<'a> PartialEq for Batsman<'a> {
+  fn eq(&self, other: &Batsman) -> bool {
+    self.initials == other.initials &&
+    self.surname == other.surname &&
+    self.runs == other.runs &&
+    average_eq(self.average, other.average)
+  }
+}
+
+/* Empty Eq means we do not support full equavilence */
+impl<'a> Eq for Batsman<'a> {
+}
+
+/* Batsman supports Partial Ordering. Which means that on one axis
+ * the following properties are satisfied:
+ *
+ * (1) a == a (reflexive),
+ * (2) b >= a and a <= b -> a == b and vice-versa (antisymmetric)
+ * (3) b > a, a > c -> b > c (transitive)
+ *
+ * We are partially ordered if we are ordred, so we can always
+ * return a value with an ordering. */
+impl<'a> PartialOrd for Batsman<'a> {
+  fn partial_cmp(&self, other: &Batsman) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+/* Batsman supports Total Ordering. Which means that on one axis
+ * the following properties are satisfied:
+ *
+ * (1) a == a (reflexive),
+ * (2) b >= a and a <= b -> a == b and vice-versa (antisymmetric)
+ * (3) b >= a or a <= b (transitive)
+ */
+impl<'a> Ord for Batsman<'a> {
+  fn cmp(&self, other: &Batsman) -> Ordering {
+    self.runs.cmp(&other.runs)
+  }
+}
+
+/* What parse errors call the type of the average */
+#[cfg(not(feature = "decimal"))]
+const AVERAGE_TYPE: &str = "f32";
+#[cfg(feature = "decimal")]
+const AVERAGE_TYPE: &str = "decimal";
+
+const BATSMAN_FIELDS: &[&str] = &["initials", "surname", "runs", "average"];
+
+impl<'a> Record for Batsman<'a> {
+  fn field_names(&self) -> &'static [&'static str] {
+    BATSMAN_FIELDS
+  }
+
+  fn field(&self, name: &str) -> Option<Value<'_>> {
+    match name {
+      "initials" => Some(Value::Text(self.initials)),
+      "surname" => Some(Value::Text(self.surname)),
+      "runs" => Some(Value::Integer(self.runs)),
+      "average" => Some(Value::average(self.average)),
+      _ => None
+    }
+  }
+}
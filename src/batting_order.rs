@@ -0,0 +1,130 @@
+use batsman::Batsman;
+use error::OrderError;
+use record::Record;
+
+/* How many players there are in a side */
+pub const XI: usize = 11;
+
+/* The order a side bats in. Positions are numbered from 1 (the first
+ * opener) up to 11, as they are on a scorecard, and every way of
+ * changing the order keeps it valid: there are never more than eleven
+ * players and nobody bats twice. */
+#[derive(Debug, Clone, PartialEq)]
+pub struct BattingOrder<'a> {
+  players: Vec<Batsman<'a>>
+}
+
+fn same_player(lhs: &Batsman, rhs: &Batsman) -> bool {
+  lhs.initials == rhs.initials && lhs.surname == rhs.surname
+}
+
+impl<'a> BattingOrder<'a> {
+  pub fn new() -> BattingOrder<'a> {
+    BattingOrder { players: Vec::with_capacity(XI) }
+  }
+
+  /* An order with these players batting in this order */
+  pub fn from_players(players: Vec<Batsman<'a>>) -> Result<BattingOrder<'a>, OrderError> {
+    let mut order = BattingOrder::new();
+    for p in players {
+      order.push(p)?;
+    }
+    Ok(order)
+  }
+
+  /* Adds a player at the bottom of the order, returning the position
+   * they will bat at */
+  pub fn push(&mut self, player: Batsman<'a>) -> Result<usize, OrderError> {
+    if self.players.len() == XI {
+      return Err(OrderError::Full);
+    }
+    if self.players.iter().any(|p| same_player(p, &player)) {
+      return Err(OrderError::Duplicate(format!("{} {}", player.initials, player.surname)));
+    }
+    self.players.push(player);
+    Ok(self.players.len())
+  }
+
+  /* Turns a position into an index, if someone bats there */
+  fn index(&self, position: usize) -> Result<usize, OrderError> {
+    if position >= 1 && position <= self.players.len() {
+      Ok(position - 1)
+    } else {
+      Err(OrderError::BadPosition(position))
+    }
+  }
+
+  pub fn get(&self, position: usize) -> Option<&Batsman<'a>> {
+    self.index(position).ok().map(|i| &self.players[i])
+  }
+
+  /* The player batting at a position swaps places with another */
+  pub fn swap(&mut self, a: usize, b: usize) -> Result<(), OrderError> {
+    let (a, b) = (self.index(a)?, self.index(b)?);
+    self.players.swap(a, b);
+    Ok(())
+  }
+
+  /* Moves the player at position up the order to bat at to, with
+   * everyone from to onwards dropping down a place to make room.
+   * Moving someone down the order works the same way. */
+  pub fn promote(&mut self, position: usize, to: usize) -> Result<(), OrderError> {
+    let (from, to) = (self.index(position)?, self.index(to)?);
+    let player = self.players.remove(from);
+    self.players.insert(to, player);
+    Ok(())
+  }
+
+  /* Takes the player at position out, and everyone below moves up */
+  pub fn remove(&mut self, position: usize) -> Result<Batsman<'a>, OrderError> {
+    let i = self.index(position)?;
+    Ok(self.players.remove(i))
+  }
+
+  pub fn len(&self) -> usize {
+    self.players.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.players.is_empty()
+  }
+
+  /* Whether there is a full side */
+  pub fn is_complete(&self) -> bool {
+    self.players.len() == XI
+  }
+
+  /* Each player with the position they bat at */
+  pub fn iter(&self) -> impl Iterator<Item = (usize, &Batsman<'a>)> {
+    self.players.iter().enumerate().map(|(i, p)| (i + 1, p))
+  }
+
+  pub fn players(&self) -> &[Batsman<'a>] {
+    &self.players
+  }
+}
+
+impl<'a> Default for BattingOrder<'a> {
+  fn default() -> BattingOrder<'a> {
+    BattingOrder::new()
+  }
+}
+
+/* The best side that can be picked from batsmen, going by one field
+ * (highest first), and batting in that order too. A player who is in
+ * the data more than once is only picked the first time. There may be
+ * fewer than eleven if there are not enough players to go round. */
+pub fn best_xi<'a>(batsmen: &[Batsman<'a>], metric: &str) -> BattingOrder<'a> {
+  let mut candidates = batsmen.to_vec();
+  candidates.sort_by(|lhs, rhs| rhs.cmp_by(lhs, metric));
+
+  let mut order = BattingOrder::new();
+  for b in candidates {
+    if order.is_complete() {
+      break;
+    }
+    /* Duplicates are skipped rather than stopping the pick */
+    let _ = order.push(b);
+  }
+  order
+}
@@ -0,0 +1,31 @@
+use getting_started::allrounder::allrounders;
+use getting_started::bowler::Bowler;
+
+use crate::cli::{Args, parse_batsmen, print_output, read_data};
+use crate::error::CliResult;
+
+/* batsmen allrounders --batting bat.csv --bowling bowl.csv */
+pub fn run(args: &Args) -> CliResult<()> {
+  let batting_path = args.required("--batting")?;
+  let bowling_path = args.required("--bowling")?;
+
+  let batting_input = read_data(batting_path, args)?;
+  let bowling_input = read_data(bowling_path, args)?;
+
+  let batting = parse_batsmen(&batting_input)?;
+  let bowling = bowling_input.parse(Bowler::parse)?;
+
+  let mut output = String::new();
+  for (i, a) in allrounders(&batting, &bowling).iter().enumerate() {
+    let (initials, surname) = batting_input.names.name(a.batting.initials, a.batting.surname);
+    output.push_str(&format!("{:>2}. {} {:<14} bat {:>6.2}  bowl {:>6.2}  diff {:>7.2}\n",
+                             i + 1,
+                             initials,
+                             surname,
+                             a.batting.average,
+                             a.bowling.average,
+                             a.score));
+  }
+
+  print_output(args, &output)
+}
@@ -0,0 +1,185 @@
+/* Everything the getting-started, batsmen and borrowck binaries share.
+ * Each of them is a couple of lines which set SUBCOMMAND and include
+ * this file, so there is one set of modules, one way of reading the
+ * command line, logging, translating messages and reporting errors:
+ *
+ *   getting-started stats ...   the batsmen statistics (batsmen ...)
+ *   getting-started learn ...   the borrow checker lessons (borrowck ...)
+ *
+ * The types and helpers used here live in the library half of the
+ * crate (src/lib.rs), so we need to pull it in */
+extern crate getting_started;
+#[cfg(feature = "clipboard")]
+extern crate arboard;
+extern crate indicatif;
+extern crate notify;
+#[cfg(feature = "tui")]
+extern crate ratatui;
+#[cfg(feature = "parallel")]
+extern crate rayon;
+extern crate rustyline;
+extern crate serde;
+extern crate serde_json;
+extern crate sha2;
+extern crate signal_hook;
+extern crate tera;
+extern crate tiny_http;
+extern crate toml;
+#[cfg(feature = "xlsx")]
+extern crate calamine;
+#[cfg(feature = "scripting")]
+extern crate rhai;
+#[macro_use]
+extern crate tracing;
+extern crate tracing_subscriber;
+#[cfg(feature = "http")]
+extern crate ureq;
+#[cfg(feature = "archive")]
+extern crate zip;
+
+/* Each subcommand lives in its own module next to this file, and the
+ * lessons in learn.rs and learn/ */
+mod allrounders;
+#[cfg(feature = "archive")]
+mod archive;
+mod batch;
+mod best;
+mod chart;
+mod cli;
+#[cfg(feature = "clipboard")]
+mod clipboard;
+mod compare;
+mod convert;
+mod correlate;
+mod db;
+mod error;
+mod follow;
+mod form;
+mod generate;
+#[cfg(feature = "http")]
+mod http;
+mod i18n;
+mod leaderboard;
+mod learn;
+mod logging;
+mod manifest;
+mod outliers;
+mod partition;
+mod partnerships;
+mod plugins;
+mod ratings;
+mod repl;
+mod report;
+mod schema;
+mod script;
+mod scorecard;
+mod selfcheck;
+mod serve;
+mod show;
+mod simulate;
+mod sort;
+mod template;
+#[cfg(feature = "tui")]
+mod tui;
+mod validate;
+mod watch;
+mod xi;
+#[cfg(feature = "xlsx")]
+mod xlsx;
+
+/* Necessary imports for what we want to do */
+use std::env;
+use std::process::ExitCode;
+
+use cli::{Args, copyable, threads};
+use error::{CliResult, ErrorFormat};
+
+/* The first positional argument picks the subcommand. Anything which
+ * is not a subcommand is the input file for the default leaderboard
+ * listing. */
+fn stats(args: &Args) -> CliResult<()> {
+  match args.positional.first().map(|a| a.as_str()) {
+    Some("allrounders") => allrounders::run(&args.shift()),
+    Some("batch") => batch::run(&args.shift()),
+    Some("best") => best::run(&args.shift(), true),
+    Some("chart") => chart::run(&args.shift()),
+    Some("compare") => compare::run(&args.shift()),
+    Some("correlate") => correlate::run(&args.shift()),
+    Some("db") => db::run(&args.shift()),
+    Some("form") => form::run(&args.shift()),
+    Some("generate") => generate::run(&args.shift()),
+    Some("outliers") => outliers::run(&args.shift()),
+    Some("partnerships") => partnerships::run(&args.shift()),
+    Some("ratings") => ratings::run(&args.shift()),
+    Some("repl") => repl::run(&args.shift()),
+    Some("report") => report::run(&args.shift()),
+    Some("schema") => schema::run(&args.shift()),
+    Some("scorecard") => scorecard::run(&args.shift()),
+    Some("selfcheck") => selfcheck::run(&args.shift()),
+    Some("serve") => serve::run(&args.shift()),
+    Some("show") => show::run(&args.shift()),
+    Some("simulate") => simulate::run(&args.shift()),
+    Some("sort") => sort::run(&args.shift()),
+    #[cfg(feature = "tui")]
+    Some("tui") => tui::run(&args.shift()),
+    #[cfg(not(feature = "tui"))]
+    Some("tui") => Err(error::CliError::Usage("batsmen tui needs batsmen to be built with --features tui".to_string())),
+    Some("validate") => validate::run(&args.shift()),
+    Some("verify") => manifest::run(&args.shift()),
+    Some("worst") => best::run(&args.shift(), false),
+    Some("xi") => xi::run(&args.shift()),
+    _ => leaderboard::run(args)
+  }
+}
+
+/* Runs getting-started stats or learn, with the arguments after it */
+fn command(name: &str, args: &Args) -> CliResult<()> {
+  match name {
+    "stats" => copyable(args).and_then(|()| {
+      if args.has("--follow") {
+        follow::run(args)
+      } else if args.has("--watch") {
+        watch::run(args, stats)
+      } else {
+        stats(args)
+      }
+    }),
+    "learn" => learn::run(args),
+    _ => Err(error::CliError::Usage(format!("Unknown command {:?}, expected stats or learn", name)))
+  }
+}
+
+/* Errors are logged rather than panicked on, and each kind exits with
+ * its own code (see error.rs) so scripts can tell them apart */
+fn main() -> ExitCode {
+  /* Coerces all commandline argumenst to strings */
+  let args = Args::parse(&env::args().skip(1).collect::<Vec<String>>());
+
+  /* Logging flags can go anywhere on the command line, so logging is
+   * set up before working out the subcommand */
+  logging::init(&args);
+
+  /* So is the language messages are shown in, how errors are reported
+   * and how many threads there are */
+  let result = i18n::init(&args)
+    .and_then(|()| args.parsed::<ErrorFormat>("--error-format"))
+    .and_then(|_| threads(&args))
+    .and_then(|()| match SUBCOMMAND {
+      Some(name) => command(name, &args),
+      None => match args.positional.first() {
+        Some(name) => command(name, &args.shift()),
+        None => Err(error::CliError::Usage("Usage: getting-started <stats | learn> [<arguments>]".to_string()))
+      }
+    });
+
+  match result {
+    Ok(()) => ExitCode::SUCCESS,
+    Err(e) => {
+      match args.parsed::<ErrorFormat>("--error-format") {
+        Ok(Some(ErrorFormat::Json)) => eprintln!("{}", e.to_json()),
+        _ => error!("{}", e)
+      }
+      ExitCode::from(e.exit_code())
+    }
+  }
+}
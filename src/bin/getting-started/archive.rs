@@ -0,0 +1,78 @@
+use std::fs::File;
+use std::io::{self, Read};
+
+use zip::ZipArchive;
+
+use getting_started::input::InputFormat;
+use getting_started::line::{name, split};
+use getting_started::progress::ProgressReader;
+
+use crate::cli::progress_bar;
+use crate::error::{CliError, CliResult};
+
+/* Whether an entry of an archive is a folder or something a Mac put
+ * in there, which nobody meant to be read */
+fn junk(name: &str) -> bool {
+  let file = name.rsplit('/').next().unwrap_or(name);
+  name.ends_with('/') || name.starts_with("__MACOSX/") || file.starts_with('.')
+}
+
+/* Whether an entry of an archive could be a batting file, from its
+ * extension: one of the input formats, or .txt as ours are */
+fn batting_file(name: &str) -> bool {
+  InputFormat::from_extension(name).is_some() || name.to_lowercase().ends_with(".txt")
+}
+
+/* Whether the first line of a file turned into comma separated lines
+ * is a record, a player's name and then something else, which is how
+ * every one of our files starts and a README or notes do not */
+pub fn looks_like_records(lines: &str) -> bool {
+  lines.lines().find(|l| !l.trim().is_empty()).is_some_and(|l| {
+    let items = split(l);
+    items.len() > 1 && name(items[0]).is_ok()
+  })
+}
+
+/* Reads every batting file in a zip archive, like a download of one
+ * CSV per season, giving back the name and bytes of each in order of
+ * name. Each entry is read through a ProgressReader like any other
+ * file, and the progress bar counts the bytes of all of them, since
+ * one bar per season would go by too fast to see. */
+pub fn read_entries(path: &str, progress: bool) -> CliResult<Vec<(String, Vec<u8>)>> {
+  let _span = debug_span!("read", path).entered();
+  let io_error = |error| CliError::Io { path: path.to_string(), error };
+  let zip_error = |e: zip::result::ZipError| io_error(io::Error::other(e));
+
+  let mut archive = ZipArchive::new(File::open(path).map_err(io_error)?).map_err(zip_error)?;
+  let (mut names, others): (Vec<String>, Vec<String>) = archive.file_names()
+    .filter(|n| !junk(n))
+    .map(|n| n.to_string())
+    .partition(|n| batting_file(n));
+  for n in others {
+    warn!("{} in {} is not a batting file, skipping it", n, path);
+  }
+  names.sort();
+  let size = names.iter().filter_map(|n| archive.by_name(n).ok().map(|e| e.size())).sum::<u64>();
+  let bar = progress_bar(size, progress);
+
+  let mut done = 0;
+  let mut entries = Vec::new();
+  for name in names {
+    let entry = archive.by_name(&name).map_err(zip_error)?;
+    let mut entry = ProgressReader::new(entry, |n| {
+      if let Some(ref b) = bar {
+        b.set_position(done + n);
+      }
+    });
+    let mut bytes = Vec::new();
+    entry.read_to_end(&mut bytes).map_err(|error| CliError::Io { path: format!("{}: {}", path, name), error })?;
+    done += bytes.len() as u64;
+    debug!(entry = %name, bytes = bytes.len(), "read archive entry");
+    entries.push((name, bytes));
+  }
+
+  if let Some(ref b) = bar {
+    b.finish_and_clear();
+  }
+  Ok(entries)
+}
@@ -0,0 +1,165 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+
+use serde::Deserialize;
+
+use getting_started::batsman::Batsman;
+use getting_started::expr::Expr;
+use getting_started::output::{Format, Style, Table};
+use getting_started::sort::{SortSpec, sorted};
+
+use crate::cli::{Args, parse_batsmen, read_data};
+use crate::error::{CliError, CliResult};
+use crate::repl::{Comparison, comparison};
+
+/* A job file is a list of these, one [[job]] table each */
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Jobs {
+  job: Vec<Job>
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Job {
+  name: Option<String>,
+  input: String,
+  #[serde(default)]
+  filters: Vec<String>,
+  sort: Option<String>,
+  limit: Option<usize>,
+  format: Option<String>,
+  output: Option<String>
+}
+
+impl Job {
+  fn describe(&self, n: usize) -> String {
+    self.name.clone().unwrap_or_else(|| format!("job {}", n + 1))
+  }
+}
+
+/* Whether a batsman passes every one of a job's filters */
+fn passes(b: &Batsman, filters: &[(Expr, Comparison, Expr)]) -> Result<bool, String> {
+  for &(ref lhs, op, ref rhs) in filters {
+    if !op.holds(lhs.eval(b)?, rhs.eval(b)?) {
+      return Ok(false);
+    }
+  }
+  Ok(true)
+}
+
+/* Runs one job, giving back what it made and where it should go. The
+ * input and output are relative to the job file, wherever batsmen was
+ * run from. */
+fn run_job(job: &Job, dir: &Path, args: &Args) -> CliResult<(String, Option<PathBuf>)> {
+  let usage = CliError::Usage;
+  let input = read_data(&dir.join(&job.input).to_string_lossy(), args)?;
+  let batsmen = parse_batsmen(&input)?;
+
+  let filters = job.filters.iter().map(|f| comparison(f).map_err(|e| usage(format!("Bad filter {:?}: {}", f, e))))
+    .collect::<CliResult<Vec<_>>>()?;
+  let mut kept = Vec::new();
+  for b in batsmen {
+    if passes(&b, &filters).map_err(|e| usage(format!("Bad filter: {}", e)))? {
+      kept.push(b);
+    }
+  }
+
+  let mut kept = match job.sort {
+    Some(ref spec) => {
+      let spec = spec.parse::<SortSpec>().map_err(|e| usage(format!("Bad sort: {}", e)))?;
+      sorted(kept, spec.comparator())
+    },
+    None => kept
+  };
+  if let Some(limit) = job.limit {
+    kept.truncate(limit);
+  }
+
+  let format = match job.format {
+    Some(ref f) => f.parse::<Format>().map_err(|e| usage(format!("Bad format: {}", e)))?,
+    None => Format::Table
+  };
+  let output = job.output.as_ref().filter(|o| o.as_str() != "-").map(|o| dir.join(o));
+  /* Colours only go to a terminal, never into a file */
+  let style = if output.is_some() { Style::plain() } else { args.style()? };
+  let mut table = Table::from_records(&kept);
+  table.anonymize(&input.names);
+  Ok((table.render(format, &style), output))
+}
+
+/* Puts a job's output where it asked for it, making any directories
+ * on the way */
+fn deliver(output: &str, path: Option<&PathBuf>) -> CliResult<()> {
+  match path {
+    Some(path) => {
+      let io_error = |error| CliError::Io { path: path.display().to_string(), error };
+      if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(io_error)?;
+      }
+      fs::write(path, output).map_err(io_error)
+    },
+    None => io::stdout().write_all(output.as_bytes()).map_err(|error| CliError::Io { path: "<stdout>".to_string(), error })
+  }
+}
+
+/* batsmen batch jobs.toml [--parallel]
+ *
+ * Runs every job in a job file in one go, which is handier for a
+ * scheduled task than a line in a crontab per report. Each job is a
+ * [[job]] table like
+ *
+ *   [[job]]
+ *   name = "Weekly top ten"
+ *   input = "batting.txt"
+ *   filters = ["runs > 5000", "average >= 40"]
+ *   sort = "average:desc"
+ *   limit = 10
+ *   format = "markdown"
+ *   output = "reports/top-ten.md"
+ *
+ * Only input is needed. filters are comparisons like the repl's filter
+ * command takes, and a batsman has to pass all of them; sort is a
+ * --sort-by spec, and format one of table, json or markdown. Without
+ * an output (or with "-") the job prints to stdout. Paths are relative
+ * to the job file.
+ *
+ * The jobs run one after another, or all at once with --parallel.
+ * One failing does not stop the others: each failure is logged, and
+ * batsmen exits with the first one's code once they have all run. */
+pub fn run(args: &Args) -> CliResult<()> {
+  let path = args.positional(0, "a job file")?;
+  let text = fs::read_to_string(path).map_err(|error| CliError::Io { path: path.to_string(), error })?;
+  let jobs = toml::from_str::<Jobs>(&text).map_err(|e| CliError::Usage(format!("{}: {}", path, e)))?.job;
+  let dir = Path::new(path).parent().unwrap_or_else(|| Path::new(""));
+
+  /* Outputs are delivered in the order the jobs are listed, so jobs
+   * printing to stdout come out the same way with --parallel */
+  let results = if args.has("--parallel") {
+    thread::scope(|scope| {
+      let handles = jobs.iter().map(|job| scope.spawn(move || run_job(job, dir, args))).collect::<Vec<_>>();
+      handles.into_iter().map(|h| h.join().unwrap_or_else(|_| Err(CliError::Usage("a job panicked".to_string()))))
+        .collect::<Vec<_>>()
+    })
+  } else {
+    jobs.iter().map(|job| run_job(job, dir, args)).collect::<Vec<_>>()
+  };
+
+  let mut first_error = None;
+  for (n, (job, result)) in jobs.iter().zip(results).enumerate() {
+    match result.and_then(|(output, path)| deliver(&output, path.as_ref()).map(|()| path)) {
+      Ok(Some(path)) => info!(job = %job.describe(n), "wrote {}", path.display()),
+      Ok(None) => (),
+      Err(e) => {
+        warn!(job = %job.describe(n), "failed: {}", e);
+        first_error = first_error.or(Some(e));
+      }
+    }
+  }
+  match first_error {
+    Some(e) => Err(e),
+    None => Ok(())
+  }
+}
@@ -0,0 +1,45 @@
+use std::ptr;
+
+use getting_started::batsman::Batsman;
+use getting_started::output::{Format, Table};
+use getting_started::record::Record;
+use getting_started::sort::{max_by_field, min_by_field, nth_by_field, top_by_field};
+
+use crate::cli::{Args, parse_batsmen, print_output, read_input};
+use crate::error::{CliError, CliResult};
+
+/* batsmen best [file] [--by runs] [--limit n | --nth n] [--format table|json|markdown]
+ * batsmen worst [file] ...
+ *
+ * The best (or worst) batsman on a field, or the best --limit of them,
+ * or just the one who comes --nth (counting from 1). None of these
+ * sort the whole file, so they stay quick on big ones. best is run
+ * with highest set and worst without. */
+pub fn run(args: &Args, highest: bool) -> CliResult<()> {
+  let by = args.value("--by").unwrap_or("runs");
+  let format = args.parsed::<Format>("--format")?.unwrap_or(Format::Table);
+
+  let input = read_input(args.positional.first(), args)?;
+  let batsmen = parse_batsmen(&input)?;
+  if batsmen.first().is_some_and(|b| b.field(by).is_none()) {
+    return Err(CliError::Usage(format!("Batsmen have no {} to go by", by)));
+  }
+
+  let picked: Vec<&Batsman> = match (args.parsed::<usize>("--nth")?, args.parsed::<usize>("--limit")?) {
+    (Some(0), _) => return Err(CliError::Usage("--nth counts from 1".to_string())),
+    (Some(n), _) if highest => top_by_field(&batsmen, n, by, true).into_iter().skip(n - 1).collect(),
+    (Some(n), _) => nth_by_field(&batsmen, n - 1, by).into_iter().collect(),
+    (None, Some(n)) => top_by_field(&batsmen, n, by, highest),
+    (None, None) if highest => max_by_field(&batsmen, by).into_iter().collect(),
+    (None, None) => min_by_field(&batsmen, by).into_iter().collect()
+  };
+
+  /* Each of them borrows from batsmen, so where they are in there is
+   * the line of the input they were parsed from */
+  let rows = picked.iter().map(|&p| {
+    batsmen.iter().position(|b| ptr::eq(b, p)).expect("picked from batsmen")
+  }).collect::<Vec<usize>>();
+  let picked = picked.into_iter().cloned().collect::<Vec<Batsman>>();
+  print_output(args, &args.sourced_columns(Table::from_records(&picked), &input, &rows)?.render(format, &args.style()?))?;
+  Ok(())
+}
@@ -0,0 +1,36 @@
+use std::fs;
+
+use getting_started::plot::bar_chart_svg;
+use getting_started::record::{numbers, Record};
+use getting_started::sort::sorted;
+
+use crate::cli::{Args, parse_batsmen, print_output, read_input};
+use crate::error::{CliError, CliResult};
+
+/* batsmen chart [file] [--metric runs] [--top 10] [--output chart.svg]
+ *
+ * A bar chart of the --top batsmen on a numeric field, best first, as
+ * an SVG image that can go straight into documentation or a report.
+ * Without --output the SVG is written to stdout. */
+pub fn run(args: &Args) -> CliResult<()> {
+  let metric = args.value("--metric").unwrap_or("runs");
+  let top = args.parsed::<usize>("--top")?.unwrap_or(10);
+
+  let input = read_input(args.positional.first(), args)?;
+  let batsmen = parse_batsmen(&input)?;
+
+  let mut best = sorted(batsmen, |lhs, rhs| rhs.cmp_by(lhs, metric));
+  best.truncate(top);
+
+  let values = numbers(&best, metric)
+    .ok_or_else(|| CliError::Usage(format!("{:?} is not a numeric field", metric)))?;
+  let labels = best.iter().map(|b| input.names.batsman(b)).map(|b| format!("{} {}", b.initials, b.surname)).collect::<Vec<String>>();
+  let svg = bar_chart_svg(&format!("Top {} by {}", best.len(), metric), &labels, &values);
+
+  match args.value("--output") {
+    Some(path) => fs::write(path, svg).map_err(|error| {
+      CliError::Io { path: path.to_string(), error }
+    }),
+    None => print_output(args, &svg)
+  }
+}
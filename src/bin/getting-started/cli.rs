@@ -0,0 +1,678 @@
+use std::fmt::Display;
+use std::fs::File;
+use std::io;
+use std::io::IsTerminal;
+use std::io::prelude::*;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use indicatif::{ProgressBar, ProgressStyle};
+
+use getting_started::anonymize::Pseudonyms;
+use getting_started::batsman::{Batsman, career_span};
+use getting_started::coerce::Coercions;
+use getting_started::error::ParseError;
+use getting_started::expr::Derivation;
+use getting_started::input::{Encoding, InputFormat};
+use getting_started::invariants::check_finite;
+use getting_started::join::Reference;
+use getting_started::numeric::average_f32;
+use getting_started::output::{ColorChoice, Style, Table};
+use getting_started::progress::ProgressReader;
+use getting_started::record::Value;
+use getting_started::source::Sources;
+use getting_started::validate::conflicting_duplicate;
+
+#[cfg(feature = "archive")]
+use crate::archive;
+#[cfg(feature = "clipboard")]
+use crate::clipboard;
+use crate::convert::to_lines;
+#[cfg(feature = "http")]
+use crate::http;
+use crate::error::{CliError, CliResult, parse_lines};
+#[cfg(feature = "xlsx")]
+use crate::xlsx;
+
+/* Flags which are followed by a value. Anything else starting with
+ * "-" is a plain on/off switch, and everything that does not start
+ * with "-" is a positional argument. A lone "-" is positional too. */
+const VALUED_FLAGS: &[&str] = &[
+  "--as-of",
+  "--batting",
+  "--bowling",
+  "--by",
+  "--cache-dir",
+  "--coerce",
+  "--color",
+  "--confidence",
+  "--corrupt",
+  "--db",
+  "--derive",
+  "--distribution",
+  "--encoding",
+  "--enrich",
+  "--error-format",
+  "--fields",
+  "--format",
+  "--host",
+  "--html",
+  "--innings",
+  "--input-format",
+  "--lang",
+  "--limit",
+  "--memory-budget",
+  "--method",
+  "--metric",
+  "--normalize",
+  "--nth",
+  "--offset",
+  "--on",
+  "--player",
+  "--output",
+  "--output-dir",
+  "--partition-by",
+  "--plugins",
+  "--port",
+  "--rank-method",
+  "--retries",
+  "--rows",
+  "--salt",
+  "--sample",
+  "--sample-rows",
+  "--score",
+  "--script",
+  "--seed",
+  "--sheet",
+  "--sort-by",
+  "--source",
+  "--tail",
+  "--template",
+  "--threads",
+  "--threshold",
+  "--title",
+  "--top",
+  "--window"
+];
+
+/* The command line, split up into positional arguments and flags */
+pub struct Args {
+  pub positional: Vec<String>,
+  flags: Vec<(String, Option<String>)>
+}
+
+impl Args {
+  pub fn parse(raw: &[String]) -> Args {
+    let mut positional = Vec::new();
+    let mut flags = Vec::new();
+    let mut iter = raw.iter();
+
+    while let Some(arg) = iter.next() {
+      if VALUED_FLAGS.contains(&arg.as_str()) {
+        flags.push((arg.clone(), iter.next().cloned()));
+      } else if arg.starts_with('-') && arg.len() > 1 {
+        flags.push((arg.clone(), None));
+      } else {
+        positional.push(arg.clone());
+      }
+    }
+
+    Args {
+      positional,
+      flags
+    }
+  }
+
+  /* The same arguments without the first positional one, which is
+   * how a subcommand's name gets stripped off before it runs */
+  pub fn shift(&self) -> Args {
+    Args {
+      positional: self.positional.iter().skip(1).cloned().collect(),
+      flags: self.flags.clone()
+    }
+  }
+
+  /* The value given to a flag, if the flag was passed. If a flag is
+   * passed more than once, the last one wins. */
+  pub fn value(&self, flag: &str) -> Option<&str> {
+    self.flags.iter()
+      .rev()
+      .find(|&(f, _)| f == flag)
+      .and_then(|(_, v)| v.as_ref())
+      .map(|v| v.as_str())
+  }
+
+  /* The value given to a flag, which has to have been passed */
+  pub fn required(&self, flag: &str) -> CliResult<&str> {
+    self.value(flag).ok_or_else(|| CliError::Usage(format!("Expected {} <value>", flag)))
+  }
+
+  /* The value given to a flag, parsed into whatever type the caller
+   * wants. Anything which fails to parse is a usage error. */
+  pub fn parsed<T>(&self, flag: &str) -> CliResult<Option<T>>
+    where T: FromStr,
+          T::Err: Display
+  {
+    match self.value(flag) {
+      None => Ok(None),
+      Some(v) => v.parse::<T>().map(Some).map_err(|e| {
+        CliError::Usage(format!("Bad value for {}: {}", flag, e))
+      })
+    }
+  }
+
+  /* The positional argument at index, which has to be there */
+  pub fn positional(&self, index: usize, what: &str) -> CliResult<&str> {
+    self.positional.get(index)
+      .map(|p| p.as_str())
+      .ok_or_else(|| CliError::Usage(format!("Expected {}", what)))
+  }
+
+  /* How output should look, from --color always|auto|never */
+  pub fn style(&self) -> CliResult<Style> {
+    Ok(Style::detect(self.parsed::<ColorChoice>("--color")?.unwrap_or(ColorChoice::Auto)))
+  }
+
+  /* Every value given to a flag which can be passed more than once,
+   * in the order they were given */
+  pub fn values(&self, flag: &str) -> Vec<&str> {
+    self.flags.iter()
+      .filter(|&(f, _)| f == flag)
+      .filter_map(|(_, v)| v.as_ref().map(|v| v.as_str()))
+      .collect()
+  }
+
+  /* The columns of a table as they should be printed. --enrich, like
+   * "players.csv", first adds the columns of a reference file, matched
+   * up on the --on columns (surname and initials unless given). Each
+   * --derive, like "dismissals = runs / average", adds a column worked
+   * out from the ones before it, rounded to two places. --fields, like
+   * "surname,average", then picks out which columns are printed and
+   * in what order, and --totals adds a footer with the total and mean
+   * of each numeric column. Rows of an input can also use career_years
+   * in a --derive (see sourced_columns). With --anonymize, names are
+   * swapped for the pseudonyms of the input they came from once
+   * --enrich has matched them up. */
+  pub fn columns<'a>(&self, table: Table<'a>, names: &'a Pseudonyms) -> CliResult<Table<'a>> {
+    if self.has("--with-source") {
+      return Err(CliError::Usage("--with-source is for commands which list rows of their input, like the leaderboard or best".to_string()));
+    }
+    self.added_columns(table, &[], names)
+  }
+
+  /* columns, for a table whose rows are rows of an input, the ones at
+   * these positions in what was parsed from it. --with-source says
+   * which file each row was parsed from and which line of it, so a row
+   * which looks wrong can be traced back to where it came from.
+   * Otherwise rows read from an archive still say which of its files
+   * they came from. A --derive can use career_years, how many seasons
+   * from the first to the last were played, as in "runs_per_year =
+   * runs / career_years". Rows whose lines do not end with those years
+   * get a blank. */
+  pub fn sourced_columns<'a>(&self, mut table: Table<'a>, input: &'a Input, rows: &[usize]) -> CliResult<Table<'a>> {
+    let sources = rows.iter().map(|&row| input.sources.line(row)).collect::<Vec<_>>();
+    let end = table.columns.len();
+    if self.has("--with-source") {
+      table.insert_column(end, "source_file", sources.iter().map(|s| s.map(|(name, _)| Value::Text(name))).collect());
+      table.insert_column(end + 1, "source_line", sources.iter().map(|s| {
+        s.and_then(|(_, line)| u32::try_from(line).ok()).map(Value::Integer)
+      }).collect());
+    } else if input.archive {
+      table.insert_column(end, "source", sources.iter().map(|s| s.map(|(name, _)| Value::Text(name))).collect());
+    }
+    let lines = input.contents.lines().collect::<Vec<&str>>();
+    let careers = rows.iter().map(|&row| {
+      lines.get(row).and_then(|l| career_span(l).ok().flatten())
+    }).collect::<Vec<Option<(u32, u32)>>>();
+    self.added_columns(table, &careers, &input.names)
+  }
+
+  /* careers are the first and last seasons of each row, where known,
+   * and empty for a table whose rows are not rows of an input */
+  fn added_columns<'a>(&self, mut table: Table<'a>, careers: &[Option<(u32, u32)>], names: &'a Pseudonyms) -> CliResult<Table<'a>> {
+    let career = |row: usize, name: &str| match name {
+      "career_years" if !careers.is_empty() => Some(careers.get(row).copied().flatten().map(|(debut, last)| {
+        Value::Integer(last.saturating_sub(debut) + 1)
+      })),
+      _ => None
+    };
+    if let Some(path) = self.value("--enrich") {
+      let on = self.value("--on").unwrap_or("surname,initials")
+        .split(',').map(|f| f.trim()).filter(|f| !f.is_empty()).collect::<Vec<&str>>();
+      table.enrich(reference(path)?, &on).map_err(|e| CliError::Usage(format!("Bad value for --enrich: {}", e)))?;
+    }
+    for d in self.values("--derive") {
+      let derivation = d.parse::<Derivation>().map_err(|e| CliError::Usage(format!("Bad value for --derive: {}", e)))?;
+      table.derive_with(&derivation, career).map_err(|e| CliError::Usage(format!("Bad value for --derive: {}", e)))?;
+      for row in table.rows.iter_mut() {
+        if let Some(Some(Value::Float(ref mut x))) = row.last_mut() {
+          *x = (*x * 100.0).round() / 100.0;
+        }
+      }
+    }
+    table.anonymize(names);
+
+    let mut table = match self.value("--fields") {
+      None => table,
+      Some(fields) => {
+        let names = fields.split(',').map(|f| f.trim()).filter(|f| !f.is_empty()).collect::<Vec<&str>>();
+        if names.is_empty() {
+          return Err(CliError::Usage("Expected at least one field for --fields".to_string()));
+        }
+        table.project(&names).map_err(|e| CliError::Usage(format!("Bad value for --fields: {}", e)))?
+      }
+    };
+
+    if self.has("--totals") {
+      table.add_totals();
+    }
+    Ok(table)
+  }
+
+  /* How many times -v was given, counting -vv as two */
+  pub fn verbosity(&self) -> usize {
+    self.flags.iter().map(|(f, _)| {
+      if f.len() > 1 && f.starts_with('-') && f[1..].chars().all(|c| c == 'v') {
+        f.len() - 1
+      } else {
+        0
+      }
+    }).sum()
+  }
+
+  /* Progress bars are on unless --quiet was passed */
+  pub fn progress(&self) -> bool {
+    !self.has("--quiet")
+  }
+
+  /* Whether a switch (or a valued flag) was passed at all */
+  pub fn has(&self, flag: &str) -> bool {
+    self.flags.iter().any(|(f, _)| f == flag)
+  }
+}
+
+/* Files at least this big get a progress bar while they are read */
+const LARGE_FILE: u64 = 1024 * 1024;
+
+/* The --enrich file, read the first time a table asks for it. It is
+ * kept for the rest of the run, so the tables it is joined onto can
+ * borrow its text as they do the input's. */
+static REFERENCE: OnceLock<Reference> = OnceLock::new();
+
+fn reference(path: &str) -> CliResult<&'static Reference> {
+  if let Some(r) = REFERENCE.get() {
+    return Ok(r);
+  }
+  let reference = Reference::parse(&read_file(path, false, None)?).map_err(|e| {
+    CliError::Usage(format!("{}: {}", path, e))
+  })?;
+  debug!(rows = reference.rows.len(), columns = ?reference.columns, "read the reference file");
+  Ok(REFERENCE.get_or_init(|| reference))
+}
+
+/* A progress bar on stderr, but only if someone is there to see it */
+pub fn progress_bar(size: u64, progress: bool) -> Option<ProgressBar> {
+  if !progress || size < LARGE_FILE || !io::stderr().is_terminal() {
+    return None;
+  }
+
+  let bar = ProgressBar::new(size);
+  if let Ok(style) = ProgressStyle::with_template("{bar:40} {bytes}/{total_bytes} (eta {eta})") {
+    bar.set_style(style);
+  }
+  Some(bar)
+}
+
+/* Reads a whole file as text (see decode), showing a progress bar for
+ * large ones unless progress is false */
+pub fn read_file(path: &str, progress: bool, encoding: Option<Encoding>) -> CliResult<String> {
+  let _span = debug_span!("read", path).entered();
+  let io_error = |error| CliError::Io { path: path.to_string(), error };
+
+  let f = File::open(path).map_err(io_error)?;
+  let size = f.metadata().map(|m| m.len()).unwrap_or(0);
+  let bar = progress_bar(size, progress);
+  let mut f = ProgressReader::new(f, |n| {
+    if let Some(ref b) = bar {
+      b.set_position(n);
+    }
+  });
+
+  let mut bytes = Vec::new();
+  f.read_to_end(&mut bytes).map_err(io_error)?;
+
+  if let Some(ref b) = bar {
+    b.finish_and_clear();
+  }
+
+  let contents = decode(bytes, encoding).map_err(io_error)?;
+  debug!(bytes = contents.len(), "read input");
+  Ok(contents)
+}
+
+/* Turns what was read into text, in the encoding given or failing that
+ * the one it looks like it is in (see Encoding::detect). Text which
+ * is not really in that encoding could not be read, so it counts as an
+ * I/O error, as it did when the file was read straight into a String. */
+fn decode(bytes: Vec<u8>, encoding: Option<Encoding>) -> io::Result<String> {
+  let encoding = encoding.unwrap_or_else(|| Encoding::detect(&bytes));
+  debug!(?encoding, "input encoding");
+  encoding.decode(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/* An input, read in and turned into comma separated lines (see
+ * as_lines), along with a description of how it was read (its format,
+ * delimiter and which columns are which, a line each) for --explain,
+ * and where each of its lines came from. Records are parsed from it a
+ * line each, so a record's position in what was parsed is the line it
+ * was on, which is how a row is traced back to its file and line. */
+pub struct Input {
+  pub contents: String,
+  pub description: String,
+  pub sources: Sources,
+  /* Whether it was a zip archive, whose rows say which of its files
+   * they came from */
+  pub archive: bool,
+  /* With --anonymize, what each player is printed as */
+  pub names: Pseudonyms
+}
+
+impl Input {
+  /* Every record of the input, parsed a line each. A line of an
+   * archive which does not parse is reported by the file in it that it
+   * came from and its line there, rather than where it was once they
+   * were all put together. */
+  pub fn parse<'a, T, F>(&'a self, parse: F) -> CliResult<Vec<T>>
+    where F: Fn(&'a str) -> Result<T, ParseError>
+  {
+    parse_lines(&self.contents, parse).map_err(|e| self.locate(e))
+  }
+
+  /* An error about a line of contents, with the file and line it was
+   * read from filled in. Those can differ from where it is in contents
+   * for more than archives: a JSON record spread over several lines
+   * becomes one line, and a spreadsheet's rows start after its
+   * heading. */
+  pub fn locate(&self, e: CliError) -> CliError {
+    match e {
+      CliError::Parse { file: None, line, column, error } => match self.sources.line(line - 1) {
+        Some((file, line)) => CliError::Parse { file: Some(file.to_string()), line, column, error },
+        None => CliError::Parse { file: None, line, column, error }
+      },
+      e => e
+    }
+  }
+
+  /* --strict-duplicates makes a player listed twice with different
+   * figures an error, naming both lines, where otherwise both would be
+   * kept and counted twice in any totals. The lines are the ones they
+   * were read from, which for an archive can be in different files. */
+  fn check_duplicates(self, args: &Args) -> CliResult<Input> {
+    if args.has("--strict-duplicates") {
+      if let Some(conflict) = conflicting_duplicate(&self.contents) {
+        let source = |line: usize| match self.sources.line(line - 1) {
+          Some((file, line)) => (file.to_string(), line),
+          None => (String::new(), line)
+        };
+        return Err(CliError::Duplicate { name: conflict.name, first: source(conflict.first), second: source(conflict.second) });
+      }
+    }
+    Ok(self)
+  }
+
+  /* --anonymize gives every player a pseudonym to be printed as. The
+   * names in contents stay as they were, so that anything which picks
+   * players by name, like the leaderboard's surnames starting with C,
+   * picks the same ones either way; only the names in the output
+   * change. Diagnostics, like a line which does not parse, still give
+   * the real line, since they are for whoever has the file. */
+  pub fn anonymized(mut self, args: &Args) -> Input {
+    if args.has("--anonymize") {
+      let salt = args.value("--salt").unwrap_or("");
+      if salt.is_empty() {
+        warn!("--anonymize without --salt can be reversed by anyone with a list of player names");
+      }
+      self.description.push_str("\nnames: replaced with pseudonyms (--anonymize)");
+      self.names = Pseudonyms::of(&self.contents, salt);
+    }
+    self
+  }
+}
+
+/* Turns TSV, JSON or NDJSON input into comma separated lines. The
+ * format comes from --input-format if it was given, then the file's
+ * extension, and failing those a look at the contents. The input is
+ * named after its path, or <stdin> without one, and each of its lines
+ * keeps the number of the line it was on in the original. */
+pub fn as_lines(contents: String, path: Option<&str>, args: &Args) -> CliResult<Input> {
+  let (format, why) = match args.parsed::<InputFormat>("--input-format")? {
+    Some(f) => (f, "from --input-format"),
+    None => match path.and_then(InputFormat::from_extension) {
+      Some(f) => (f, "from the file extension"),
+      None => (InputFormat::detect(contents.as_bytes()), "detected from the contents")
+    }
+  };
+  debug!(?format, "input format");
+  let name = path.unwrap_or("<stdin>");
+  let (lines, numbers) = to_lines(contents, format).map_err(|e| match e {
+    CliError::Parse { file: None, line, column, error } => CliError::Parse { file: Some(name.to_string()), line, column, error },
+    e => e
+  })?;
+  let sources = Sources::new(vec![(name.to_string(), numbers)]);
+
+  let (delimiter, mapping) = match format {
+    InputFormat::Csv => ("comma", "item 1 is the name, 2 the runs, 3 the average"),
+    InputFormat::Tsv => ("tab, turned into commas", "item 1 is the name, 2 the runs, 3 the average"),
+    InputFormat::Json | InputFormat::Ndjson =>
+      ("none, each record becomes a comma separated line",
+       "\"name\" (or \"initials\" and \"surname\") is the name, then the other fields in order")
+  };
+  let mut description = format!("{} ({})\ndelimiter: {}\ncolumns: {}",
+                                format!("{:?}", format).to_lowercase(), why, delimiter, mapping);
+
+  /* --coerce cleans up each field before anything parses it, so a file
+   * with "1,843" or "46.33%" in it can be read as it is */
+  let lines = match args.parsed::<Coercions>("--coerce")? {
+    Some(coercions) if !coercions.is_empty() => {
+      description.push_str(&format!("\ncleaning: {}", args.value("--coerce").unwrap_or("").trim()));
+      lines.lines().map(|l| coercions.apply(l) + "\n").collect()
+    },
+    _ => lines
+  };
+
+  Ok(Input { contents: lines, description, sources, archive: false, names: Pseudonyms::default() })
+}
+
+/* Reads a data file. Spreadsheets and other formats are turned into
+ * lines of text on the way in (using --sheet to pick which sheet of a
+ * spreadsheet), so everything after this point works the same
+ * whichever kind of file it was given. An http:// or https:// URL is
+ * fetched (see http.rs). */
+pub fn read_data(path: &str, args: &Args) -> CliResult<Input> {
+  let lower = path.to_lowercase();
+  let input = if lower.ends_with(".zip") {
+    read_archive(path, args)?
+  } else if [".xlsx", ".xlsm", ".xls", ".ods"].iter().any(|ext| lower.ends_with(ext)) {
+    let sheet = args.value("--sheet");
+    let description = format!("spreadsheet, {}", sheet.map(|s| format!("sheet {:?}", s)).unwrap_or_else(|| "first sheet".to_string()));
+    let (contents, rows) = read_spreadsheet(path, sheet)?;
+    Input { contents, description, sources: Sources::new(vec![(path.to_string(), rows)]), archive: false, names: Pseudonyms::default() }
+  } else if path.starts_with("http://") || path.starts_with("https://") {
+    let contents = decode(fetch(path, args)?, args.parsed("--encoding")?).map_err(|error| {
+      CliError::Io { path: path.to_string(), error }
+    })?;
+    as_lines(contents, Some(path), args)?
+  } else {
+    as_lines(read_file(path, args.progress(), args.parsed("--encoding")?)?, Some(path), args)?
+  };
+  Ok(input.check_duplicates(args)?.anonymized(args))
+}
+
+/* Reads each batting file in a zip archive as if it were a file of its
+ * own, so each can be in a different format, and puts them one after
+ * the other, each line keeping the file and line it came from. A .txt
+ * file could as easily be a README or someone's notes as a batting
+ * file, so one which does not start with a record is left out, with a
+ * warning. */
+#[cfg(feature = "archive")]
+fn read_archive(path: &str, args: &Args) -> CliResult<Input> {
+  let mut contents = String::new();
+  let mut descriptions = Vec::new();
+  let mut sources = Sources::default();
+  for (name, bytes) in archive::read_entries(path, args.progress())? {
+    let text = decode(bytes, args.parsed("--encoding")?).map_err(|error| {
+      CliError::Io { path: format!("{}: {}", path, name), error }
+    })?;
+    let mut entry = as_lines(text, Some(&name), args)?;
+    if name.to_lowercase().ends_with(".txt") && !archive::looks_like_records(&entry.contents) {
+      warn!("{} in {} does not look like a batting file, skipping it", name, path);
+      continue;
+    }
+    if !entry.contents.is_empty() && !entry.contents.ends_with('\n') {
+      entry.contents.push('\n');
+    }
+    descriptions.push(format!("{}: {}", name, entry.description.lines().next().unwrap_or("")));
+    contents.push_str(&entry.contents);
+    sources.append(entry.sources);
+  }
+  if sources.is_empty() {
+    return Err(CliError::Usage(format!("{} has no batting files in it", path)));
+  }
+  let description = format!("zip archive of {} files\n{}", sources.len(), descriptions.join("\n"));
+  Ok(Input { contents, description, sources, archive: true, names: Pseudonyms::default() })
+}
+
+#[cfg(not(feature = "archive"))]
+fn read_archive(path: &str, _: &Args) -> CliResult<Input> {
+  Err(CliError::Usage(format!("{} is a zip archive, which needs batsmen to be built with --features archive", path)))
+}
+
+#[cfg(feature = "http")]
+fn fetch(url: &str, args: &Args) -> CliResult<Vec<u8>> {
+  http::fetch(url, args)
+}
+
+#[cfg(not(feature = "http"))]
+fn fetch(url: &str, _: &Args) -> CliResult<Vec<u8>> {
+  Err(CliError::Usage(format!("{} is a URL, which needs batsmen to be built with --features http", url)))
+}
+
+#[cfg(feature = "xlsx")]
+fn read_spreadsheet(path: &str, sheet: Option<&str>) -> CliResult<(String, Vec<usize>)> {
+  let _span = debug_span!("read", path).entered();
+  xlsx::read_sheet(path, sheet)
+}
+
+#[cfg(not(feature = "xlsx"))]
+fn read_spreadsheet(path: &str, _: Option<&str>) -> CliResult<(String, Vec<usize>)> {
+  Err(CliError::Usage(format!("{} is a spreadsheet, which needs batsmen to be built with --features xlsx", path)))
+}
+
+/* Prints what a command worked out. With --copy it goes on the
+ * clipboard as well, ready to paste into a chat or an email, minus any
+ * colours, which would turn into junk there. */
+pub fn print_output(args: &Args, output: &str) -> CliResult<()> {
+  print!("{}", output);
+  if args.has("--copy") {
+    copy(&without_colors(output))?;
+    info!("copied the output to the clipboard");
+  }
+  Ok(())
+}
+
+/* --copy is checked before a command does any work, rather than once
+ * it has printed everything. Commands which write as they go, run until
+ * they are stopped or write files instead of printing have no one
+ * output to copy, so it is an error with them too. args are the ones
+ * after stats, whose first positional is the command. */
+pub fn copyable(args: &Args) -> CliResult<()> {
+  if !args.has("--copy") {
+    return Ok(());
+  }
+  if !cfg!(feature = "clipboard") {
+    return Err(CliError::Usage("--copy needs batsmen to be built with --features clipboard".to_string()));
+  }
+  let command = args.positional.iter().take(2).map(|p| p.as_str()).collect::<Vec<&str>>();
+  if let ["batch", ..] | ["generate", ..] | ["repl", ..] | ["serve", ..] | ["sort", ..] | ["tui", ..] | ["db", "add"] = command.as_slice() {
+    return Err(CliError::Usage(format!("batsmen {} does not print one output for --copy to copy", command.join(" "))));
+  }
+  match ["--follow", "--watch", "--output", "--output-dir", "--html"].iter().find(|f| args.has(f)) {
+    Some(flag) => Err(CliError::Usage(format!("--copy cannot be used with {}, which does not print one output to copy", flag))),
+    None => Ok(())
+  }
+}
+
+/* Drops the escape sequences Style::paint puts around coloured text */
+fn without_colors(s: &str) -> String {
+  let mut out = String::with_capacity(s.len());
+  let mut chars = s.chars();
+  while let Some(c) = chars.next() {
+    if c == '\x1b' {
+      chars.by_ref().find(|c| c.is_ascii_alphabetic());
+    } else {
+      out.push(c);
+    }
+  }
+  out
+}
+
+/* --threads N caps how many threads the parallel sort uses, which is
+ * otherwise one per core. It has to be set before anything is sorted,
+ * so this runs before the command. */
+#[cfg(feature = "parallel")]
+pub fn threads(args: &Args) -> CliResult<()> {
+  if let Some(n) = args.parsed::<usize>("--threads")? {
+    if n == 0 {
+      return Err(CliError::Usage("--threads has to be at least 1".to_string()));
+    }
+    rayon::ThreadPoolBuilder::new().num_threads(n).build_global().map_err(|e| {
+      CliError::Usage(format!("Could not start {} threads: {}", n, e))
+    })?;
+  }
+  Ok(())
+}
+
+#[cfg(not(feature = "parallel"))]
+pub fn threads(args: &Args) -> CliResult<()> {
+  if args.has("--threads") {
+    return Err(CliError::Usage("--threads needs batsmen to be built with --features parallel".to_string()));
+  }
+  Ok(())
+}
+
+#[cfg(feature = "clipboard")]
+fn copy(text: &str) -> CliResult<()> {
+  clipboard::copy(text)
+}
+
+#[cfg(not(feature = "clipboard"))]
+fn copy(_: &str) -> CliResult<()> {
+  Err(CliError::Usage("--copy needs batsmen to be built with --features clipboard".to_string()))
+}
+
+/* Subcommands take their input file as an optional trailing
+ * positional argument. Without one, we read from stdin instead so
+ * that the output of another program can be piped in. */
+pub fn read_input(path: Option<&String>, args: &Args) -> CliResult<Input> {
+  match path {
+    Some(p) => read_data(p, args).map(|input| Input { description: format!("{}: {}", p, input.description), ..input }),
+    None => {
+      let _span = debug_span!("read", path = "<stdin>").entered();
+      let stdin_error = |error| CliError::Io { path: "<stdin>".to_string(), error };
+      let mut bytes = Vec::new();
+      io::stdin().read_to_end(&mut bytes).map_err(stdin_error)?;
+      let contents = decode(bytes, args.parsed("--encoding")?).map_err(stdin_error)?;
+      let input = as_lines(contents, None, args)?.check_duplicates(args)?.anonymized(args);
+      Ok(Input { description: format!("<stdin>: {}", input.description), ..input })
+    }
+  }
+}
+
+/* Parses every line of a batting file */
+pub fn parse_batsmen(input: &Input) -> CliResult<Vec<Batsman<'_>>> {
+  let _span = debug_span!("parse").entered();
+  let batsmen = input.parse(Batsman::parse)?;
+  info!(records = batsmen.len(), "parsed batsmen");
+  debug_assert_eq!(check_finite(&batsmen.iter().map(|b| average_f32(b.average)).collect::<Vec<f32>>()), Ok(()));
+  Ok(batsmen)
+}
@@ -0,0 +1,16 @@
+use std::io;
+
+use arboard::Clipboard;
+
+use crate::error::{CliError, CliResult};
+
+/* Puts text on the system clipboard. On Linux the clipboard belongs to
+ * whichever program copied last, so when we exit arboard hands the
+ * text over to the desktop's clipboard manager; without one running,
+ * it goes when we do. */
+pub fn copy(text: &str) -> CliResult<()> {
+  let clipboard_error = |e: arboard::Error| CliError::Io { path: "<clipboard>".to_string(), error: io::Error::other(e) };
+
+  let mut clipboard = Clipboard::new().map_err(clipboard_error)?;
+  clipboard.set_text(text).map_err(clipboard_error)
+}
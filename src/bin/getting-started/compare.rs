@@ -0,0 +1,102 @@
+use getting_started::batsman::Batsman;
+use getting_started::compare::{compare, FieldComparison, Winner};
+use getting_started::output::{Format, Style, json_string, json_value, markdown_table, render_table};
+
+use crate::cli::{Args, parse_batsmen, print_output, read_input};
+use crate::error::{CliError, CliResult};
+
+/* Looks a player up by name, insisting on exactly one match so that we
+ * never silently compare the wrong person */
+pub fn find_player<'a, 'b>(batsmen: &'b [Batsman<'a>], query: &str) -> CliResult<&'b Batsman<'a>> {
+  let matches = batsmen.iter().filter(|b| b.matches_name(query)).collect::<Vec<&Batsman>>();
+  match matches.len() {
+    0 => Err(CliError::Usage(format!("No player matching {:?}", query))),
+    1 => Ok(matches[0]),
+    _ => Err(CliError::Usage(format!("{:?} matches more than one player: {:?}", query, matches)))
+  }
+}
+
+fn full_name(b: &Batsman) -> String {
+  format!("{} {}", b.initials, b.surname)
+}
+
+fn table(left: &Batsman, right: &Batsman, rows: &[FieldComparison], format: Format, style: &Style) -> String {
+  let header = vec![
+    "field".to_string(),
+    full_name(left),
+    full_name(right),
+    "difference".to_string()
+  ];
+
+  /* The winner of each field gets a * next to their value */
+  let cell = |v: &FieldComparison, side: Winner| {
+    let value = match if side == Winner::Left { v.left } else { v.right } {
+      Some(x) => x.to_string(),
+      None => String::new()
+    };
+    match v.winner {
+      Some(w) if w == side => format!("{} *", value),
+      _ => value
+    }
+  };
+
+  let body = rows.iter().map(|r| {
+    vec![
+      r.field.to_string(),
+      cell(r, Winner::Left),
+      cell(r, Winner::Right),
+      match r.percent_difference {
+        Some(p) => format!("{:+.1}%", p),
+        None => String::new()
+      }
+    ]
+  }).collect::<Vec<Vec<String>>>();
+
+  match format {
+    Format::Markdown => markdown_table(&header, &body, &[false, true, true, true]),
+    _ => render_table(&header, &body, style)
+  }
+}
+
+fn json(left: &Batsman, right: &Batsman, rows: &[FieldComparison]) -> String {
+  let optional = |v: Option<String>| v.unwrap_or_else(|| "null".to_string());
+
+  let fields = rows.iter().map(|r| {
+    format!("{{\"field\": {}, \"values\": [{}, {}], \"winner\": {}, \"percent_difference\": {}}}",
+            json_string(r.field),
+            optional(r.left.map(|v| json_value(&v))),
+            optional(r.right.map(|v| json_value(&v))),
+            optional(r.winner.map(|w| match w {
+              Winner::Left => json_string(&full_name(left)),
+              Winner::Right => json_string(&full_name(right)),
+              Winner::Tie => json_string("tie")
+            })),
+            optional(r.percent_difference.map(|p| p.to_string())))
+  }).collect::<Vec<String>>();
+
+  format!("{{\"players\": [{}, {}], \"fields\": [{}]}}\n",
+          json_string(&full_name(left)),
+          json_string(&full_name(right)),
+          fields.join(", "))
+}
+
+/* batsmen compare "Cook" "Cowdrey" [file] [--format table|json|markdown] */
+pub fn run(args: &Args) -> CliResult<()> {
+  let left_name = args.positional(0, "two players to compare")?;
+  let right_name = args.positional(1, "two players to compare")?;
+  let format = args.parsed::<Format>("--format")?.unwrap_or(Format::Table);
+
+  let input = read_input(args.positional.get(2), args)?;
+  let batsmen = parse_batsmen(&input)?;
+
+  /* Players are found by their real names, and shown as they should
+   * be printed */
+  let left = input.names.batsman(find_player(&batsmen, left_name)?);
+  let right = input.names.batsman(find_player(&batsmen, right_name)?);
+  let rows = compare(&left, &right);
+
+  print_output(args, &match format {
+    Format::Table | Format::Markdown => table(&left, &right, &rows, format, &args.style()?),
+    Format::Json => json(&left, &right, &rows)
+  })
+}
@@ -0,0 +1,116 @@
+use serde_json::{Map, Value};
+
+use getting_started::error::ParseError;
+use getting_started::input::InputFormat;
+
+use crate::error::{CliError, CliResult};
+
+fn malformed(line: usize, message: String) -> CliError {
+  CliError::Parse { file: None, line, column: None, error: ParseError::Malformed(message) }
+}
+
+/* A single JSON value as it would be written in a CSV file */
+fn text(v: &Value) -> String {
+  match *v {
+    Value::String(ref s) => s.clone(),
+    Value::Null => String::new(),
+    ref other => other.to_string()
+  }
+}
+
+/* One record as a comma separated line. Objects give their name first
+ * (either a "name" field, or "initials" and "surname" joined up the way
+ * our own JSON output splits them) and then every other field in the
+ * order it appears. Arrays are taken as the items of the line. */
+fn record_line(line: usize, v: &Value) -> CliResult<String> {
+  let items = match *v {
+    Value::Array(ref items) => items.iter().map(text).collect::<Vec<String>>(),
+    Value::Object(ref fields) => object_items(fields),
+    _ => return Err(malformed(line, format!("Expected a record as an object or an array, got {}", v)))
+  };
+  Ok(items.join(", "))
+}
+
+fn object_items(fields: &Map<String, Value>) -> Vec<String> {
+  let name = match (fields.get("name"), fields.get("initials"), fields.get("surname")) {
+    (Some(name), _, _) => text(name),
+    (None, Some(initials), Some(surname)) => format!("{} {}", text(initials), text(surname)),
+    (None, _, Some(surname)) => text(surname),
+    _ => String::new()
+  };
+
+  Some(name).into_iter().chain(fields.iter().filter(|&(k, _)| {
+    !["name", "initials", "surname"].contains(&k.as_str())
+  }).map(|(_, v)| text(v))).collect()
+}
+
+/* The line each record of a JSON array starts on, found by going
+ * through it a character at a time, keeping track of how deep in
+ * brackets it is and whether it is inside a string */
+fn record_lines(contents: &str) -> Vec<usize> {
+  let mut lines = Vec::new();
+  let (mut line, mut depth) = (1, 0);
+  let (mut string, mut escaped, mut expecting) = (false, false, true);
+  for c in contents.chars() {
+    if c == '\n' {
+      line += 1;
+    }
+    if string {
+      match c {
+        _ if escaped => escaped = false,
+        '\\' => escaped = true,
+        '"' => string = false,
+        _ => ()
+      }
+      continue;
+    }
+    if depth == 1 && expecting && !c.is_whitespace() && c != ']' {
+      lines.push(line);
+      expecting = false;
+    }
+    match c {
+      '"' => string = true,
+      '[' | '{' => depth += 1,
+      ']' | '}' => depth -= 1,
+      ',' if depth == 1 => expecting = true,
+      _ => ()
+    }
+  }
+  lines
+}
+
+/* Rewrites input in any of the InputFormats as the comma separated
+ * lines which every record type knows how to parse, so the rest of the
+ * program never needs to know what the file looked like. Line numbers
+ * in errors are lines of the original input, and along with the lines
+ * comes the number of the line of the original that each one was. */
+pub fn to_lines(contents: String, format: InputFormat) -> CliResult<(String, Vec<usize>)> {
+  match format {
+    InputFormat::Csv => {
+      let numbers = (1..=contents.lines().count()).collect();
+      Ok((contents, numbers))
+    },
+    InputFormat::Tsv => Ok((contents.lines().map(|l| {
+      l.split('\t').map(|x| x.trim()).collect::<Vec<&str>>().join(", ") + "\n"
+    }).collect(), (1..=contents.lines().count()).collect())),
+    InputFormat::Json => {
+      let records = match serde_json::from_str::<Value>(&contents) {
+        Ok(Value::Array(records)) => records,
+        Ok(_) => return Err(malformed(1, "Expected a JSON array of records".to_string())),
+        Err(e) => return Err(malformed(e.line(), e.to_string()))
+      };
+      let lines = record_lines(&contents);
+      records.iter().zip(&lines).map(|(r, &line)| record_line(line, r).map(|l| l + "\n"))
+        .collect::<CliResult<String>>()
+        .map(|text| (text, lines))
+    },
+    InputFormat::Ndjson => {
+      let records = contents.lines().enumerate().filter(|&(_, l)| !l.trim().is_empty()).collect::<Vec<(usize, &str)>>();
+      let text = records.iter().map(|&(i, l)| {
+        let v = serde_json::from_str::<Value>(l).map_err(|e| malformed(i + 1, e.to_string()))?;
+        record_line(i + 1, &v).map(|l| l + "\n")
+      }).collect::<CliResult<String>>()?;
+      Ok((text, records.iter().map(|&(i, _)| i + 1).collect()))
+    }
+  }
+}
@@ -0,0 +1,77 @@
+use getting_started::output::{Format, json_string, markdown_table};
+use getting_started::plot::scatter;
+use getting_started::record::numbers;
+use getting_started::stats::{linear_fit, pearson};
+
+use crate::cli::{Args, parse_batsmen, print_output, read_input};
+use crate::error::{CliError, CliResult};
+
+/* batsmen correlate <x> <y> [file] [--plot] [--format table|json|markdown]
+ *
+ * How strongly two numeric fields move together, and the straight line
+ * that best predicts y from x. */
+pub fn run(args: &Args) -> CliResult<()> {
+  let x_name = args.positional(0, "two fields to correlate")?;
+  let y_name = args.positional(1, "two fields to correlate")?;
+  let format = args.parsed::<Format>("--format")?.unwrap_or(Format::Table);
+
+  let input = read_input(args.positional.get(2), args)?;
+  let batsmen = parse_batsmen(&input)?;
+
+  let column = |name: &str| {
+    numbers(&batsmen, name).ok_or_else(|| CliError::Usage(format!("{:?} is not a numeric field", name)))
+  };
+  let xs = column(x_name)?;
+  let ys = column(y_name)?;
+
+  let r = pearson(&xs, &ys);
+  let fit = linear_fit(&xs, &ys);
+
+  let mut output = String::new();
+  match format {
+    Format::Table => {
+      output.push_str(&format!("n          {}\n", xs.len()));
+      output.push_str(&match r {
+        Some(r) => format!("pearson r  {:.4}\n", r),
+        None => "pearson r  undefined\n".to_string()
+      });
+      output.push_str(&match fit {
+        Some((slope, intercept)) => format!("fit        {} = {:.6} * {} + {:.4}\n", y_name, slope, x_name, intercept),
+        None => "fit        undefined\n".to_string()
+      });
+
+      if args.has("--plot") {
+        output.push('\n');
+        output.push_str(&scatter(&xs, &ys, 60, 20));
+      }
+    },
+    Format::Markdown => {
+      let rows = vec![
+        vec!["n".to_string(), xs.len().to_string()],
+        vec!["pearson r".to_string(), r.map(|r| format!("{:.4}", r)).unwrap_or_else(|| "undefined".to_string())],
+        vec!["fit".to_string(), match fit {
+          Some((slope, intercept)) => format!("{} = {:.6} * {} + {:.4}", y_name, slope, x_name, intercept),
+          None => "undefined".to_string()
+        }]
+      ];
+      output.push_str(&markdown_table(&["statistic".to_string(), "value".to_string()], &rows, &[false, false]));
+
+      /* The plot goes in a code block so it keeps its spacing */
+      if args.has("--plot") {
+        output.push_str(&format!("\n```\n{}```\n", scatter(&xs, &ys, 60, 20)));
+      }
+    },
+    Format::Json => {
+      let optional = |v: Option<f32>| v.map(|x| x.to_string()).unwrap_or_else(|| "null".to_string());
+      output.push_str(&format!("{{\"x\": {}, \"y\": {}, \"n\": {}, \"pearson\": {}, \"slope\": {}, \"intercept\": {}}}\n",
+                               json_string(x_name),
+                               json_string(y_name),
+                               xs.len(),
+                               optional(r),
+                               optional(fit.map(|f| f.0)),
+                               optional(fit.map(|f| f.1))));
+    }
+  }
+
+  print_output(args, &output)
+}
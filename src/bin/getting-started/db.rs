@@ -0,0 +1,211 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use getting_started::anonymize::Pseudonyms;
+use getting_started::intern::Interner;
+use getting_started::join::matches_name;
+use getting_started::numeric::average_f32;
+use getting_started::output::Table;
+use getting_started::record::{Record, Value};
+use getting_started::sort::{Field, SortSpec, sorted};
+
+use crate::cli::{Args, parse_batsmen, print_output, read_input};
+use crate::error::{CliError, CliResult};
+use crate::manifest;
+use crate::plugins;
+
+/* Where the database lives unless --db says otherwise */
+const DEFAULT_DB: &str = "batsmen.db";
+
+/* The file in the database listing every import, see manifest.rs */
+const MANIFEST: &str = "manifest.ndjson";
+
+/* One batsman as stored, with where and when they came from. Names are
+ * owned here, since the file they were parsed from is long gone by the
+ * time anyone queries them. The text is shared through an Interner,
+ * since the same names come up in import after import and every row
+ * of an import has the same source and time. */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Stored {
+  initials: Arc<str>,
+  surname: Arc<str>,
+  runs: u32,
+  average: f32,
+  source: Arc<str>,
+  imported: Arc<str>
+}
+
+impl Stored {
+  /* Swaps each string for the interner's copy, dropping this one */
+  fn interned(self, strings: &mut Interner) -> Stored {
+    Stored {
+      initials: strings.intern(&self.initials),
+      surname: strings.intern(&self.surname),
+      source: strings.intern(&self.source),
+      imported: strings.intern(&self.imported),
+      ..self
+    }
+  }
+}
+
+const STORED_FIELDS: &[&str] = &["initials", "surname", "runs", "average", "source", "imported"];
+
+impl Record for Stored {
+  fn field_names(&self) -> &'static [&'static str] {
+    STORED_FIELDS
+  }
+
+  fn field(&self, name: &str) -> Option<Value<'_>> {
+    match name {
+      "initials" => Some(Value::Text(&self.initials)),
+      "surname" => Some(Value::Text(&self.surname)),
+      "runs" => Some(Value::Integer(self.runs)),
+      "average" => Some(Value::Float(self.average)),
+      "source" => Some(Value::Text(&self.source)),
+      "imported" => Some(Value::Text(&self.imported)),
+      _ => None
+    }
+  }
+}
+
+/* Seconds since 1970 as "2016-01-01T12:00:00Z". Days are turned into
+ * a date with Howard Hinnant's days_from_civil worked backwards, which
+ * saves pulling in a date library for one timestamp. */
+fn iso_time(secs: u64) -> String {
+  let (days, rest) = ((secs / 86400) as i64, secs % 86400);
+  let z = days + 719468;
+  let era = z.div_euclid(146097);
+  let doe = z - era * 146097;
+  let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+  let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+  let mp = (5 * doy + 2) / 153;
+  let day = doy - (153 * mp + 2) / 5 + 1;
+  let month = if mp < 10 { mp + 3 } else { mp - 9 };
+  let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+  format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", year, month, day, rest / 3600, rest / 60 % 60, rest % 60)
+}
+
+fn db_error(path: &Path, error: io::Error) -> CliError {
+  CliError::Io { path: path.display().to_string(), error }
+}
+
+/* The segment files, oldest first. Their names are zero padded
+ * numbers, so sorting the names sorts them by age. */
+fn segments(db: &Path) -> CliResult<Vec<PathBuf>> {
+  if !db.exists() {
+    return Ok(Vec::new());
+  }
+  let mut paths = fs::read_dir(db).map_err(|e| db_error(db, e))?
+    .filter_map(|e| e.ok().map(|e| e.path()))
+    .filter(|p| p.extension().is_some_and(|x| x == "ndjson") && !p.ends_with(MANIFEST))
+    .collect::<Vec<PathBuf>>();
+  paths.sort();
+  Ok(paths)
+}
+
+fn load(db: &Path) -> CliResult<Vec<Stored>> {
+  let mut all = Vec::new();
+  let mut strings = Interner::new();
+  for path in segments(db)? {
+    let contents = fs::read_to_string(&path).map_err(|e| db_error(&path, e))?;
+    for (i, l) in contents.lines().enumerate() {
+      let stored = serde_json::from_str::<Stored>(l).map_err(|e| {
+        CliError::Usage(format!("{} line {} is damaged: {}", path.display(), i + 1, e))
+      })?;
+      all.push(stored.interned(&mut strings));
+    }
+  }
+  debug!(rows = all.len(), strings = strings.len(), "loaded database");
+  Ok(all)
+}
+
+/* batsmen db add <file> [--db dir]
+ *
+ * Each import is written to a segment file of its own, which is never
+ * changed afterwards. It is written under a temporary name and renamed
+ * into place, so an import which fails half way leaves nothing behind.
+ * The file's checksum and row count go in the manifest, for batsmen
+ * verify to check it against later. */
+fn add(args: &Args, db: &Path) -> CliResult<()> {
+  let path = args.positional(0, "a file to add")?;
+  let input = read_input(Some(&path.to_string()), args)?;
+  let batsmen = parse_batsmen(&input)?;
+
+  let source = fs::canonicalize(path).map(|p| p.display().to_string()).unwrap_or_else(|_| path.to_string());
+  let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+  let imported: Arc<str> = Arc::from(iso_time(now));
+  let source: Arc<str> = Arc::from(source);
+  let lines = batsmen.iter().map(|b| input.names.batsman(b)).map(|b| {
+    serde_json::to_string(&Stored {
+      initials: Arc::from(b.initials),
+      surname: Arc::from(b.surname),
+      runs: b.runs,
+      average: average_f32(b.average),
+      source: Arc::clone(&source),
+      imported: Arc::clone(&imported)
+    }).map_err(|e| CliError::Usage(e.to_string()))
+  }).collect::<CliResult<Vec<String>>>()?;
+
+  fs::create_dir_all(db).map_err(|e| db_error(db, e))?;
+  let segment = db.join(format!("{:06}.ndjson", segments(db)?.len() + 1));
+  let partial = segment.with_extension("partial");
+  fs::write(&partial, lines.join("\n") + "\n").map_err(|e| db_error(&partial, e))?;
+  fs::rename(&partial, &segment).map_err(|e| db_error(&segment, e))?;
+
+  /* The manifest lets batsmen verify tell later if the file changes */
+  manifest::append(&db.join(MANIFEST), &manifest::Entry {
+    source: source.to_string(),
+    sha256: manifest::sha256(path)?,
+    rows: batsmen.len(),
+    imported: imported.to_string()
+  })?;
+
+  info!(segment = %segment.display(), "added {} batsmen from {}", batsmen.len(), source);
+  Ok(())
+}
+
+/* batsmen db query [--db dir] [--player name] [--source file]
+ *                  [--sort-by runs:desc] [--limit n] [--format table|json|...]
+ *
+ * Everything ever added, with the file each batsman came from and
+ * when, narrowed down to one player or one source file if asked. */
+fn query(args: &Args, db: &Path) -> CliResult<()> {
+  let spec = args.parsed::<SortSpec>("--sort-by")?.unwrap_or_else(|| SortSpec::by(Field::Runs).desc());
+  let source = args.value("--source").map(|s| {
+    fs::canonicalize(s).map(|p| p.display().to_string()).unwrap_or_else(|_| s.to_string())
+  });
+
+  let matching = load(db)?.into_iter()
+    .filter(|s| args.value("--player").is_none_or(|p| matches_name(p, &s.initials, &s.surname)))
+    .filter(|s| source.as_ref().is_none_or(|src| *s.source == **src))
+    .collect::<Vec<Stored>>();
+  let mut matching = sorted(matching, spec.comparator());
+  if let Some(n) = args.parsed::<usize>("--limit")? {
+    matching.truncate(n);
+  }
+
+  let table = Table::from_records(&matching);
+  print_output(args, &plugins::render(args, args.value("--format").unwrap_or("table"), &args.columns(table, &Pseudonyms::default())?)?)?;
+  Ok(())
+}
+
+/* batsmen db add|query ...
+ *
+ * A local store of every batting file imported into it, so questions
+ * about a club's whole history can be answered without finding and
+ * parsing every old file again. The store is a directory (batsmen.db
+ * unless --db says otherwise) of append-only segments, one per import,
+ * each holding a line of JSON per batsman. */
+pub fn run(args: &Args) -> CliResult<()> {
+  let db = PathBuf::from(args.value("--db").unwrap_or(DEFAULT_DB));
+  match args.positional.first().map(|s| s.as_str()) {
+    Some("add") => add(&args.shift(), &db),
+    Some("query") => query(&args.shift(), &db),
+    _ => Err(CliError::Usage("Expected db add <file> or db query".to_string()))
+  }
+}
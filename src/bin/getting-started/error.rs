@@ -0,0 +1,165 @@
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+use getting_started::error::{ParseError, ValidationError};
+use getting_started::output::json_string;
+
+use crate::i18n;
+
+/* Everything that can make a command fail. Each kind of failure gets
+ * its own exit code so that shell scripts and CI jobs can tell them
+ * apart without having to read the error message:
+ *
+ * 0  success
+ * 1  usage error (bad flags, unknown player, that sort of thing)
+ * 2  I/O error (the input could not be read)
+ * 3  parse error (the input was read, but a line made no sense)
+ * 4  validation failure (the input parsed, but failed a check, like
+ *    --strict-duplicates finding a player listed twice)
+ * 130  interrupted by Ctrl-C, as a shell reports a command killed by it
+ */
+#[derive(Debug)]
+pub enum CliError {
+  Usage(String),
+  Io {
+    path: String,
+    error: io::Error
+  },
+  /* file is the one the line was on. Errors start out without it, and
+   * have it filled in by whatever knows which input the line came from
+   * (see Input::locate) */
+  Parse {
+    file: Option<String>,
+    line: usize,
+    column: Option<usize>,
+    error: ParseError
+  },
+  Validation(usize),
+  /* A player on two lines with different figures, with the file and
+   * line each was read from */
+  Duplicate {
+    name: String,
+    first: (String, usize),
+    second: (String, usize)
+  },
+  Interrupted(usize)
+}
+
+impl CliError {
+  pub fn exit_code(&self) -> u8 {
+    match *self {
+      CliError::Usage(_) => 1,
+      CliError::Io { .. } => 2,
+      CliError::Parse { .. } => 3,
+      CliError::Validation(_) | CliError::Duplicate { .. } => 4,
+      CliError::Interrupted(_) => 130
+    }
+  }
+}
+
+impl fmt::Display for CliError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      CliError::Usage(ref message) => write!(f, "{}", message),
+      CliError::Io { ref path, ref error } => write!(f, "{}: {}", path, error),
+      CliError::Parse { file: Some(ref file), line, ref error, .. } =>
+        write!(f, "{}", i18n::trf("{} line {}: {}", &[file, &line, &i18n::parse_error(error)])),
+      CliError::Parse { file: None, line, ref error, .. } =>
+        write!(f, "{}", i18n::trf("Line {}: {}", &[&line, &i18n::parse_error(error)])),
+      CliError::Validation(count) => write!(f, "{}", i18n::trf("{} validation failure(s)", &[&count])),
+      CliError::Duplicate { ref name, first: (ref file, first), second: (ref other, second) } if file == other =>
+        write!(f, "{}", i18n::trf("{} is on line {} and line {} with different figures", &[name, &first, &second])),
+      CliError::Duplicate { ref name, ref first, ref second } =>
+        write!(f, "{}", i18n::trf("{} is on {} line {} and {} line {} with different figures",
+                                  &[name, &first.0, &first.1, &second.0, &second.1])),
+      CliError::Interrupted(rows) => write!(f, "{}", i18n::trf("Interrupted after {} rows", &[&rows]))
+    }
+  }
+}
+
+/* How errors are reported: as a log line for people, or with
+ * --error-format json as a JSON object for editors and CI jobs to pick
+ * apart */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorFormat {
+  Text,
+  Json
+}
+
+impl FromStr for ErrorFormat {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<ErrorFormat, String> {
+    match s {
+      "text" => Ok(ErrorFormat::Text),
+      "json" => Ok(ErrorFormat::Json),
+      _ => Err(format!("Unknown error format {:?}, expected text or json", s))
+    }
+  }
+}
+
+/* One problem as a JSON object on a line of its own, with the file,
+ * line and column it was found at (null where they do not apply), a
+ * code saying what kind of problem it is and the message. The codes
+ * are the ones batsmen validate uses where they overlap. */
+pub fn json_diagnostic(file: Option<&str>, line: Option<usize>, column: Option<usize>, code: &str, message: &str) -> String {
+  let optional = |n: Option<usize>| n.map(|n| n.to_string()).unwrap_or_else(|| "null".to_string());
+  format!("{{\"file\": {}, \"line\": {}, \"column\": {}, \"code\": {}, \"message\": {}}}",
+          file.map(json_string).unwrap_or_else(|| "null".to_string()),
+          optional(line),
+          optional(column),
+          json_string(code),
+          json_string(message))
+}
+
+impl CliError {
+  pub fn to_json(&self) -> String {
+    match *self {
+      CliError::Usage(ref message) => json_diagnostic(None, None, None, "usage", message),
+      CliError::Io { ref path, ref error } => json_diagnostic(Some(path), None, None, "io", &error.to_string()),
+      CliError::Parse { ref file, line, column, ref error } =>
+        json_diagnostic(file.as_deref(), Some(line), column, "parse", &i18n::parse_error(error)),
+      CliError::Validation(_) => json_diagnostic(None, None, None, "validation", &self.to_string()),
+      CliError::Duplicate { second: (ref file, line), .. } =>
+        json_diagnostic(Some(file), Some(line), None, "duplicate-player", &self.to_string()),
+      CliError::Interrupted(_) => json_diagnostic(None, None, None, "interrupted", &self.to_string())
+    }
+  }
+}
+
+/* Shorthand for the Result every command returns */
+pub type CliResult<T> = Result<T, CliError>;
+
+/* Numbers the lines of a parse so that a bad one can be reported as
+ * CliError::Parse with the line it was on */
+pub fn parse_lines<'a, T, F>(contents: &'a str, parse: F) -> CliResult<Vec<T>>
+  where F: Fn(&'a str) -> Result<T, ParseError>
+{
+  contents.lines().enumerate().map(|(i, l)| {
+    parse(l).map_err(|error| parse_error(i, l, error))
+  }).collect()
+}
+
+/* A parse error on the line at index i (counting from 0), whose text
+ * was l */
+pub fn parse_error(i: usize, l: &str, error: ParseError) -> CliError {
+  CliError::Parse { file: None, line: i + 1, column: column(l, &error), error }
+}
+
+/* Where on its line the text a parse error complains about starts,
+ * counting characters from 1, if we can tell. A missing field would
+ * have been at the end. */
+fn column(l: &str, error: &ParseError) -> Option<usize> {
+  let value = match *error {
+    ParseError::BadName(ref value) |
+    ParseError::BadNumber { ref value, .. } |
+    ParseError::Invalid(ValidationError::BadName { ref value, .. }) => value.as_str(),
+    ParseError::MissingField(_) => return Some(l.chars().count() + 1),
+    _ => return None
+  };
+  if value.is_empty() {
+    return None;
+  }
+  l.find(value).map(|start| l[..start].chars().count() + 1)
+}
@@ -0,0 +1,102 @@
+use std::io;
+use std::io::prelude::*;
+
+use getting_started::batsman::Batsman;
+use getting_started::output::{json_string, json_value};
+use getting_started::record::Record;
+
+use crate::cli::{Args, as_lines};
+use crate::error::{CliError, CliResult, parse_error};
+use crate::script::Script;
+
+/* A batsman as a line of JSON, the way the json format writes each of
+ * its records, with the line of stdin it came from if asked */
+fn json_line(b: &Batsman, source: Option<usize>) -> String {
+  let mut fields = b.field_names().iter().filter_map(|&name| {
+    b.field(name).map(|v| format!("{}: {}", json_string(name), json_value(&v)))
+  }).collect::<Vec<String>>();
+  if let Some(line) = source {
+    fields.push(format!("\"source_file\": \"<stdin>\", \"source_line\": {}", line));
+  }
+  format!("{{{}}}", fields.join(", "))
+}
+
+/* One line of input as the batsman it is, if the filters keep them.
+ * The line is turned into a comma separated one first, as a whole file
+ * would be (so a JSON object, --coerce and --anonymize all work), and
+ * that is what the batsman borrows from. Like the leaderboard, the
+ * filters see the real name and only what is written out gets the
+ * pseudonym. */
+fn follow_line(args: &Args, script: Option<&Script>, number: usize, line: &str, out: &mut impl Write) -> CliResult<()> {
+  /* Each line is converted as an input of its own, so it is always
+   * the first; what matters is which line of stdin it was */
+  let located = |e: CliError| match e {
+    CliError::Parse { column, error, .. } => CliError::Parse { file: Some("<stdin>".to_string()), line: number, column, error },
+    e => e
+  };
+  let converted = as_lines(line.to_string(), None, args).map_err(located)?.anonymized(args);
+  let l = converted.contents.trim_end_matches('\n');
+  let b = Batsman::parse(l).map_err(|error| located(parse_error(0, l, error)))?;
+
+  /* The same filters as the leaderboard */
+  if !b.surname.starts_with('C') {
+    return Ok(());
+  }
+  let b = match script {
+    Some(s) if !s.filter(&b)? => return Ok(()),
+    Some(s) => s.transform(b)?,
+    None => b
+  };
+  let b = converted.names.batsman(&b);
+
+  let source = Some(number).filter(|_| args.has("--with-source"));
+  let text = match (args.value("--format"), source) {
+    (Some("json"), _) => json_line(&b, source),
+    (_, Some(line)) => format!("{}, <stdin>, {}", b, line),
+    (_, None) => b.to_string()
+  };
+  writeln!(out, "{}", text)
+    .and_then(|()| out.flush())
+    .map_err(|error| CliError::Io { path: "<stdout>".to_string(), error })
+}
+
+/* batsmen --follow [--format json] [--script s.rhai] [--with-source]
+ *
+ * Reads batsmen from stdin a line at a time for as long as there are
+ * any, as CSV or NDJSON (or a mix of the two), and writes out each one
+ * the leaderboard's filters keep as soon as it has been read, rather
+ * than waiting for the end of the input. That makes it something to
+ * pipe a live feed through, as in
+ *
+ *   tail -f scores.ndjson | batsmen --follow --format json
+ *
+ * Batsmen come out as batting lines, or as a line of JSON each with
+ * --format json, and --with-source adds <stdin> and the line number
+ * each was read from. A line which does not parse is logged and skipped
+ * rather than stopping everything, since the next one may well be
+ * fine; it only stops at the end of the input. */
+pub fn run(args: &Args) -> CliResult<()> {
+  match args.value("--format") {
+    None | Some("json") => (),
+    Some(other) => return Err(CliError::Usage(format!("--follow writes batting lines or json, not {}", other)))
+  }
+  let script = match args.value("--script") {
+    Some(path) => Some(Script::load(path)?),
+    None => None
+  };
+
+  let stdin = io::stdin().lock();
+  let mut out = io::stdout().lock();
+  for (i, line) in stdin.lines().enumerate() {
+    let line = line.map_err(|error| CliError::Io { path: "<stdin>".to_string(), error })?;
+    if line.trim().is_empty() {
+      continue;
+    }
+    match follow_line(args, script.as_ref(), i + 1, &line, &mut out) {
+      Err(e @ CliError::Io { .. }) => return Err(e),
+      Err(e) => warn!("{}", e),
+      Ok(()) => ()
+    }
+  }
+  Ok(())
+}
@@ -0,0 +1,44 @@
+use getting_started::innings::Innings;
+use getting_started::output::{Format, Table};
+use getting_started::record::Value;
+use getting_started::sort::sorted;
+use getting_started::stats::rolling_mean;
+
+use crate::cli::{Args, print_output, read_input};
+use crate::error::{CliError, CliResult};
+
+/* batsmen form <player> [file] [--window 10] [--format table|json|markdown]
+ *
+ * Reads a per-innings file and prints each of the player's innings in
+ * date order alongside the average of the last --window innings. */
+pub fn run(args: &Args) -> CliResult<()> {
+  let player = args.positional(0, "a player name")?;
+  let window = args.parsed::<usize>("--window")?.unwrap_or(10);
+  let format = args.parsed::<Format>("--format")?.unwrap_or(Format::Table);
+
+  let input = read_input(args.positional.get(1), args)?;
+  let innings = sorted(input.parse(Innings::parse)?
+                         .into_iter()
+                         .filter(|i| i.matches_name(player))
+                         .collect::<Vec<Innings>>(),
+                       |lhs, rhs| lhs.date.cmp(rhs.date));
+
+  if innings.is_empty() {
+    return Err(CliError::Usage(format!("No innings found for {:?}", player)));
+  }
+
+  let runs = innings.iter().map(|i| i.runs as f32).collect::<Vec<f32>>();
+  let averages = rolling_mean(&runs, window);
+
+  let rows = innings.iter().zip(averages).map(|(i, avg)| {
+    vec![
+      Some(Value::Text(i.date)),
+      Some(Value::Integer(i.runs)),
+      avg.map(Value::Float)
+    ]
+  }).collect();
+  let table = Table::new(vec!["date".to_string(), "runs".to_string(), "rolling_average".to_string()], rows);
+
+  print_output(args, &args.columns(table, &input.names)?.render(format, &args.style()?))?;
+  Ok(())
+}
@@ -0,0 +1,43 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+use getting_started::generate::Generator;
+use getting_started::rng::Rng;
+
+use crate::cli::Args;
+use crate::error::{CliError, CliResult};
+
+/* batsmen generate [--rows 1000] [--seed s] [--corrupt 0.01]
+ *                  [--output big.csv]
+ *
+ * Writes a made up batting file (see generate.rs in the library) with
+ * --rows lines, for benchmarks, fuzzing and trying things out on
+ * something bigger than our own files. Passing --seed gives the same
+ * file every time. --corrupt breaks that fraction of the lines, to
+ * see what happens to bad ones. Without --output the lines are
+ * written to stdout. */
+pub fn run(args: &Args) -> CliResult<()> {
+  let rows = args.parsed::<usize>("--rows")?.unwrap_or(1000);
+  let corrupt = args.parsed::<f64>("--corrupt")?.unwrap_or(0.0);
+  if !(0.0..=1.0).contains(&corrupt) {
+    return Err(CliError::Usage("--corrupt has to be between 0 and 1".to_string()));
+  }
+  let rng = match args.parsed::<u64>("--seed")? {
+    Some(seed) => Rng::new(seed),
+    None => Rng::from_time()
+  };
+  let generator = Generator::new(rng).corrupt(corrupt);
+
+  let _span = debug_span!("generate", rows).entered();
+  let (path, out): (&str, Box<dyn Write>) = match args.value("--output") {
+    Some(path) => (path, Box::new(File::create(path).map_err(|error| {
+      CliError::Io { path: path.to_string(), error }
+    })?)),
+    None => ("<stdout>", Box::new(io::stdout().lock()))
+  };
+  let mut out = BufWriter::new(out);
+  generator.take(rows)
+    .try_for_each(|line| writeln!(out, "{}", line))
+    .and_then(|()| out.flush())
+    .map_err(|error| CliError::Io { path: path.to_string(), error })
+}
@@ -0,0 +1,179 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use ureq::Agent;
+
+use crate::cli::Args;
+use crate::error::{CliError, CliResult};
+
+/* How many times a request is tried again after failing, unless
+ * --retries says otherwise */
+const DEFAULT_RETRIES: u32 = 3;
+
+/* The wait before the first retry, which doubles for each one after */
+const FIRST_BACKOFF: Duration = Duration::from_millis(500);
+
+/* A copy of a URL kept from the last time it was fetched, along with
+ * what the server said to identify that version of it */
+struct Cached {
+  body: PathBuf,
+  meta: PathBuf,
+  etag: Option<String>,
+  modified: Option<String>
+}
+
+impl Cached {
+  /* Where the copy of url lives in dir, named after its hash */
+  fn at(dir: &Path, url: &str) -> Cached {
+    let name = Sha256::digest(url.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect::<String>();
+    Cached {
+      body: dir.join(&name),
+      meta: dir.join(name + ".json"),
+      etag: None,
+      modified: None
+    }
+  }
+
+  fn load(dir: &Path, url: &str) -> Option<Cached> {
+    let mut cached = Cached::at(dir, url);
+    let meta = serde_json::from_str::<Value>(&fs::read_to_string(&cached.meta).ok()?).ok()?;
+    if !cached.body.is_file() {
+      return None;
+    }
+    cached.etag = meta["etag"].as_str().map(String::from);
+    cached.modified = meta["last_modified"].as_str().map(String::from);
+    Some(cached)
+  }
+
+  fn save(dir: &Path, url: &str, body: &[u8], etag: Option<&str>, modified: Option<&str>) -> io::Result<()> {
+    let cached = Cached::at(dir, url);
+    fs::create_dir_all(dir)?;
+    fs::write(&cached.body, body)?;
+    fs::write(&cached.meta, json!({ "url": url, "etag": etag, "last_modified": modified }).to_string())
+  }
+}
+
+/* What came of asking for a URL once */
+enum Attempt {
+  Fetched {
+    body: Vec<u8>,
+    etag: Option<String>,
+    modified: Option<String>
+  },
+  NotModified,
+  Failed {
+    message: String,
+    retry: bool
+  }
+}
+
+fn attempt(agent: &Agent, url: &str, cached: Option<&Cached>) -> Attempt {
+  let mut request = agent.get(url);
+  if let Some(c) = cached {
+    if let Some(ref etag) = c.etag {
+      request = request.header("If-None-Match", etag);
+    }
+    if let Some(ref modified) = c.modified {
+      request = request.header("If-Modified-Since", modified);
+    }
+  }
+
+  let mut response = match request.call() {
+    Ok(r) => r,
+    /* The connection failing is usually a blip worth trying again */
+    Err(e) => return Attempt::Failed { message: e.to_string(), retry: true }
+  };
+  let header = |name: &str| response.headers().get(name).and_then(|v| v.to_str().ok()).map(String::from);
+  let (etag, modified) = (header("etag"), header("last-modified"));
+
+  match response.status().as_u16() {
+    304 => Attempt::NotModified,
+    200..=299 => match response.body_mut().with_config().limit(u64::MAX).read_to_vec() {
+      Ok(body) => Attempt::Fetched { body, etag, modified },
+      Err(e) => Attempt::Failed { message: e.to_string(), retry: true }
+    },
+    /* Timeouts, rate limits and server errors might clear up, but the
+     * server telling us the URL is wrong will not */
+    status => Attempt::Failed {
+      message: format!("the server answered {}", status),
+      retry: status == 408 || status == 429 || status >= 500
+    }
+  }
+}
+
+/* Where fetched files are kept: --cache-dir, or the usual place for
+ * caches in the home directory. --no-cache turns it off. */
+fn cache_dir(args: &Args) -> Option<PathBuf> {
+  if args.has("--no-cache") {
+    return None;
+  }
+  if let Some(dir) = args.value("--cache-dir") {
+    return Some(PathBuf::from(dir));
+  }
+  env::var_os("XDG_CACHE_HOME").map(PathBuf::from)
+    .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+    .map(|dir| dir.join("batsmen"))
+}
+
+/* batsmen [command] https://example.com/batting.csv [--retries 3]
+ *                   [--cache-dir dir] [--no-cache]
+ *
+ * Fetches a URL. Failures which might be temporary are tried again,
+ * --retries times, waiting half a second and then twice as long each
+ * time. A copy of what was fetched is kept in the cache directory, and
+ * the next fetch asks the server (with If-None-Match and
+ * If-Modified-Since) to only send the file if it has changed since;
+ * if the server cannot be reached at all, the copy is used instead,
+ * with a warning, so a scheduled job carries on with yesterday's data
+ * rather than failing. */
+pub fn fetch(url: &str, args: &Args) -> CliResult<Vec<u8>> {
+  let _span = debug_span!("fetch", url).entered();
+  let retries = args.parsed::<u32>("--retries")?.unwrap_or(DEFAULT_RETRIES);
+  let dir = cache_dir(args);
+  let cached = dir.as_ref().and_then(|d| Cached::load(d, url));
+  let agent: Agent = Agent::config_builder().http_status_as_error(false).build().into();
+  let read_cached = |c: &Cached| fs::read(&c.body).map_err(|error| CliError::Io { path: c.body.display().to_string(), error });
+
+  let mut backoff = FIRST_BACKOFF;
+  let mut tries = 0;
+  loop {
+    match attempt(&agent, url, cached.as_ref()) {
+      Attempt::Fetched { body, etag, modified } => {
+        info!(bytes = body.len(), "fetched");
+        if let Some(ref d) = dir {
+          if let Err(e) = Cached::save(d, url, &body, etag.as_deref(), modified.as_deref()) {
+            warn!(error = %e, "could not keep a copy in the cache");
+          }
+        }
+        return Ok(body);
+      },
+      Attempt::NotModified => {
+        if let Some(ref c) = cached {
+          info!("not modified since the cached copy");
+          return read_cached(c);
+        }
+        return Err(CliError::Io { path: url.to_string(), error: io::Error::other("the server answered 304 with nothing cached") });
+      },
+      Attempt::Failed { message, retry } => {
+        if retry && tries < retries {
+          warn!(%message, wait = ?backoff, "fetch failed, trying again");
+          thread::sleep(backoff);
+          backoff *= 2;
+          tries += 1;
+          continue;
+        }
+        if let Some(ref c) = cached {
+          warn!(%message, "fetch failed, using the cached copy");
+          return read_cached(c);
+        }
+        return Err(CliError::Io { path: url.to_string(), error: io::Error::other(message) });
+      }
+    }
+  }
+}
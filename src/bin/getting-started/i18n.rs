@@ -0,0 +1,148 @@
+use std::env;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use getting_started::error::{ParseError, ValidationError};
+
+use crate::cli::Args;
+use crate::error::{CliError, CliResult};
+
+/* The languages messages can be shown in. Messages are written in
+ * English in the code, and each other language has a table below
+ * mapping the English to its own. Anything missing from a table is
+ * shown in English, so a message can be added without translating it
+ * straight away. */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Lang {
+  English,
+  French
+}
+
+/* Takes "fr", or a locale like "fr_FR.UTF-8" as found in $LANG */
+impl FromStr for Lang {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Lang, String> {
+    let code = s.split(['_', '-', '.', '@']).next().unwrap_or("").to_lowercase();
+    match code.as_str() {
+      "en" | "c" | "posix" => Ok(Lang::English),
+      "fr" => Ok(Lang::French),
+      _ => Err(format!("Unknown language {:?}, expected en or fr", s))
+    }
+  }
+}
+
+impl Lang {
+  /* The code used for the lang attribute of HTML reports */
+  pub fn code(&self) -> &'static str {
+    match *self {
+      Lang::English => "en",
+      Lang::French => "fr"
+    }
+  }
+
+  fn table(&self) -> &'static [(&'static str, &'static str)] {
+    match *self {
+      Lang::English => &[],
+      Lang::French => FRENCH
+    }
+  }
+}
+
+const FRENCH: &[(&str, &str)] = &[
+  /* Errors */
+  ("Line {}: {}", "Ligne {} : {}"),
+  ("{} line {}: {}", "{} ligne {} : {}"),
+  ("{} validation failure(s)", "{} échec(s) de validation"),
+  ("{} is on line {} and line {} with different figures", "{} figure aux lignes {} et {} avec des chiffres différents"),
+  ("{} is on {} line {} and {} line {} with different figures", "{} figure à {} ligne {} et à {} ligne {} avec des chiffres différents"),
+  ("Interrupted after {} rows", "Interrompu après {} lignes"),
+  ("Expected a {} field", "Champ {} manquant"),
+  ("Expected a name like \"AN Cook\", got {}", "Nom attendu sous la forme \"AN Cook\", reçu {}"),
+  ("Expected {} to be an {}, got {}", "{} devrait être de type {}, reçu {}"),
+  ("No {} was given", "Aucun champ {} n'a été donné"),
+  ("The {} cannot be empty", "Le champ {} ne peut pas être vide"),
+  ("The {} cannot contain spaces or commas, got {}", "Le champ {} ne peut contenir ni espaces ni virgules, reçu {}"),
+  ("Expected {} to be a finite number of at least zero, got {}", "{} devrait être un nombre fini positif ou nul, reçu {}"),
+
+  /* The HTML report */
+  ("Batting report", "Rapport des batteurs"),
+  ("Summary", "Résumé"),
+  ("Players", "Joueurs"),
+  ("Total runs", "Total des courses"),
+  ("Top scorer", "Meilleur marqueur"),
+  ("Mean average", "Moyenne des moyennes"),
+  ("Median average", "Moyenne médiane"),
+  ("Std. dev. of averages", "Écart type des moyennes"),
+  ("Click a column heading to sort by it, and again to reverse.",
+   "Cliquez sur un en-tête de colonne pour trier, et à nouveau pour inverser l'ordre."),
+  ("Initials", "Initiales"),
+  ("Surname", "Nom"),
+  ("Runs", "Courses"),
+  ("Average", "Moyenne")
+];
+
+static LANG: OnceLock<Lang> = OnceLock::new();
+
+/* Picks the language from --lang, or failing that the usual locale
+ * variables. A --lang we do not know is an error, but a locale we have
+ * no translation for just means English. */
+pub fn init(args: &Args) -> CliResult<()> {
+  let lang = match args.value("--lang") {
+    Some(l) => l.parse::<Lang>().map_err(|e| CliError::Usage(format!("Bad value for --lang: {}", e)))?,
+    None => ["LC_ALL", "LC_MESSAGES", "LANG"].iter()
+      .filter_map(|v| env::var(v).ok())
+      .find(|v| !v.is_empty())
+      .and_then(|v| v.parse::<Lang>().ok())
+      .unwrap_or(Lang::English)
+  };
+  let _ = LANG.set(lang);
+  Ok(())
+}
+
+pub fn lang() -> Lang {
+  LANG.get().cloned().unwrap_or(Lang::English)
+}
+
+/* A message in the current language */
+pub fn tr(english: &'static str) -> &'static str {
+  lang().table().iter().find(|&&(en, _)| en == english).map(|&(_, t)| t).unwrap_or(english)
+}
+
+/* A message with {} placeholders, filled in with args in order. The
+ * placeholders can be anywhere in the translation, but have to be in
+ * the same order as in the English. */
+pub fn trf(english: &'static str, args: &[&dyn Display]) -> String {
+  let mut pieces = tr(english).split("{}");
+  let mut out = pieces.next().unwrap_or("").to_string();
+  for (piece, arg) in pieces.zip(args.iter().map(|a| a.to_string()).chain(std::iter::repeat(String::new()))) {
+    out.push_str(&arg);
+    out.push_str(piece);
+  }
+  out
+}
+
+/* The library's error messages are in English, so they are written out
+ * again here from the parts of the error */
+pub fn parse_error(e: &ParseError) -> String {
+  match *e {
+    ParseError::MissingField(field) => trf("Expected a {} field", &[&field]),
+    ParseError::BadName(ref name) => trf("Expected a name like \"AN Cook\", got {}", &[&format!("{:?}", name)]),
+    ParseError::BadNumber { field, expected, ref value } =>
+      trf("Expected {} to be an {}, got {}", &[&field, &expected, &format!("{:?}", value)]),
+    ParseError::Malformed(ref message) => message.clone(),
+    ParseError::Invalid(ref e) => validation_error(e)
+  }
+}
+
+fn validation_error(e: &ValidationError) -> String {
+  match *e {
+    ValidationError::MissingField(field) => trf("No {} was given", &[&field]),
+    ValidationError::EmptyName(field) => trf("The {} cannot be empty", &[&field]),
+    ValidationError::BadName { field, ref value } =>
+      trf("The {} cannot contain spaces or commas, got {}", &[&field, &format!("{:?}", value)]),
+    ValidationError::BadNumber { field, value } =>
+      trf("Expected {} to be a finite number of at least zero, got {}", &[&field, &value])
+  }
+}
@@ -0,0 +1,333 @@
+use serde_json::json;
+
+use getting_started::batsman::{Batsman, career_span};
+use getting_started::expr::{Expr, Scored};
+use getting_started::numeric::{average_f32, average_from_f32};
+use getting_started::output::Table;
+use getting_started::page::paginate;
+use getting_started::rank::{rank, RankMethod};
+use getting_started::record::{Record, Value};
+use getting_started::rng::Rng;
+use getting_started::sort::{Field, SortSpec};
+/* Built with --features parallel, big files are sorted on every core */
+#[cfg(feature = "parallel")]
+use getting_started::sort::par_sorted as sorted;
+#[cfg(not(feature = "parallel"))]
+use getting_started::sort::sorted;
+use getting_started::stats::{normalize, overlap_means};
+use getting_started::validate::average_is_consistent;
+
+use crate::cli::{Args, parse_batsmen, print_output, read_file, read_input};
+use crate::partition::{Partition, write_lines, write_tables};
+use crate::plugins;
+use crate::script::{Derived, Script, apply};
+use crate::error::{CliError, CliResult, parse_error};
+use crate::template::{context, render};
+
+/* --normalize era: each batsman's average scaled by how it compares
+ * with the averages of everyone whose career overlapped theirs, so
+ * that players from high and low scoring eras can be compared. This
+ * needs every line to end with the years of the player's first and
+ * last matches. The whole file counts towards the eras, even the
+ * players who are not listed. */
+fn normalize_era<'a>(contents: &str, batsmen: Vec<Batsman<'a>>) -> CliResult<Vec<Batsman<'a>>> {
+  let spans = contents.lines().enumerate().map(|(i, l)| {
+    match career_span(l) {
+      Ok(Some(span)) => Ok(span),
+      Ok(None) => Err(CliError::Usage(format!("Line {} has no debut and last years, which --normalize era needs", i + 1))),
+      Err(error) => Err(parse_error(i, l, error))
+    }
+  }).collect::<CliResult<Vec<(u32, u32)>>>()?;
+
+  let averages = batsmen.iter().map(|b| average_f32(b.average)).collect::<Vec<f32>>();
+  let normalized = normalize(&averages, &overlap_means(&averages, &spans));
+  Ok(batsmen.into_iter().zip(normalized).map(|(b, average)| Batsman { average: average_from_f32(average).unwrap_or_default(), ..b }).collect())
+}
+
+/* batsmen [--format table|json|markdown|<plugin>] [--plugins dir] [--sort-by runs:desc,surname]
+ *         [--with-rank] [--rank-method m] [--normalize era]
+ *         [--score "0.6*average + 0.4*runs/100"] [--script s.rhai]
+ *         [--enrich players.csv] [--on surname,initials]
+ *         [--derive "name = expression"] [--fields surname,average] [--totals]
+ *         [--sample n] [--shuffle] [--seed s]
+ *         [--offset n] [--limit n] [--tail n]
+ *         [--color always|auto|never] [--template t.txt] [--explain] [--copy]
+ *         [--partition-by year|last|decade --output-dir dir] [--with-source] [file]
+ *
+ * --sample keeps n batsmen picked at random and --shuffle puts them in
+ * a random order. Passing --seed makes both give the same answer
+ * every time. --offset, --limit and --tail then page through
+ * whatever is left, so they always see the final order.
+ *
+ * --template prints them through a Tera template of your own instead,
+ * which gets the same "players" and "summary" as the report command
+ * (and "ranks" with --with-rank, and "sources", each a file and line,
+ * with --with-source), so any kind of text (a newsletter paragraph, a
+ * CSV, ...) can be produced without changing any code.
+ *
+ * --score adds a score column worked out from each batsman's numeric
+ * fields, with + - * / and brackets, and sorts on it (highest first)
+ * unless --sort-by names "score" or other fields itself.
+ *
+ * --script runs a rhai script over the batsmen first, which can drop
+ * some of them, change their numbers and add columns of its own (see
+ * script.rs).
+ *
+ * --sort-by picks the order, as a list of fields each with an optional
+ * :asc or :desc. The default is runs:desc, most runs first, and ranks
+ * treat batsmen as tied when every field in it is the same. Tied
+ * batsmen are still listed in a fixed order, by surname and then
+ * initials, whatever order the file had them in.
+ *
+ * --format can also name a format provided by a plugin, a program
+ * called batsmen-format-<name> in the --plugins directory (or
+ * $BATSMEN_PLUGINS) which is handed the table as JSON on stdin and
+ * prints it however it likes. See plugins.rs.
+ *
+ * --enrich adds the columns of a reference file, like each player's
+ * country or batting hand, matching players up on the --on columns
+ * (surname and initials unless told otherwise). Players the file does
+ * not mention get blanks.
+ *
+ * --derive adds a column worked out just before printing, with the
+ * same arithmetic as --score over any of the other columns, and can be
+ * passed more than once. --fields then picks which columns are printed
+ * and in what order, from the batsmen's own fields and any rank,
+ * score, script, enriched or derived columns. --totals adds a total
+ * and a mean of each numeric column underneath.
+ *
+ * --partition-by writes the batsmen to a file for each year their
+ * careers started (or ended, or each decade) in --output-dir instead
+ * of printing them, as 2014.csv and so on. Each file holds the lines
+ * of the input they were read from, or with --format, the table of
+ * them in that format (a table, in 2014.txt and so on, with any of the
+ * options which make the leaderboard print a table, like --with-source).
+ * The years come from the end of each line, as for --normalize era, and
+ * batsmen without them go in unknown.csv.
+ *
+ * --with-source adds the file and line each batsman was read from, in
+ * whichever format they are printed, for tracing one that looks wrong
+ * back to where it came from.
+ *
+ * --explain does everything up to printing the batsmen, and then
+ * prints each step instead: how the input was read, which filters and
+ * sort order were used and how many rows were left after each, which
+ * is the quickest way to find out why nothing came out.
+ *
+ * Without --format, the batsmen are printed with their Debug
+ * implementation as they always have been. */
+pub fn run(args: &Args) -> CliResult<()> {
+  let input = read_input(args.positional.first(), args)?;
+
+  /* Each phase gets its own span, so -vv shows how long it took */
+  let batsmen = match args.value("--normalize") {
+    None => parse_batsmen(&input)?,
+    Some("era") => normalize_era(&input.contents, parse_batsmen(&input)?).map_err(|e| input.locate(e))?,
+    Some(other) => return Err(CliError::Usage(format!("Bad value for --normalize: {:?}, expected era", other)))
+  };
+  let parsed = batsmen.len();
+  /* Only surnames starting with C. The patterns lesson in the borrowck
+   * binary goes through the ways this could be written. Below, we are
+   * not automatically a vector, so collect the iterable into one.
+   * Each batsman keeps the position they were parsed at from here on,
+   * which is the line of the input they were on, so that they can be
+   * traced back to it whatever order they end up in. */
+  let batsmen = batsmen.into_iter().enumerate().filter(|(_, b)| b.surname.starts_with('C')).collect::<Vec<(usize, Batsman)>>();
+  let filtered = batsmen.len();
+
+  let script = match args.value("--script") {
+    Some(path) => Some(Script::load(path)?),
+    None => None
+  };
+  let batsmen = match script {
+    Some(ref s) => apply(s, batsmen)?,
+    None => batsmen
+  };
+
+  /* --score works a score out for everyone, which is then what they
+   * are sorted on unless --sort-by says otherwise */
+  let score = args.parsed::<Expr>("--score")?;
+  let spec = match args.parsed::<SortSpec>("--sort-by")? {
+    Some(spec) => spec,
+    None if score.is_some() => SortSpec::by(Field::Score).desc(),
+    None => SortSpec::by(Field::Runs).desc()
+  };
+  let batsmen = batsmen.into_iter().map(|(row, b)| {
+    let s = match score {
+      Some(ref e) => Some(e.eval(&b).map_err(|e| CliError::Usage(format!("Bad value for --score: {}", e)))?),
+      None => None
+    };
+    Ok((row, Scored { record: b, score: s }))
+  }).collect::<CliResult<Vec<(usize, Scored<Batsman>)>>>()?;
+  if let Some(field) = spec.fields().into_iter().find(|f| batsmen.first().is_some_and(|(_, b)| b.field(f.name()).is_none())) {
+    return Err(CliError::Usage(format!("Batsmen have no {} to sort by", field.name())));
+  }
+
+  let (rows, batsmen): (Vec<usize>, Vec<Scored<Batsman>>) = {
+    let _span = debug_span!("sort").entered();
+    let cmp = spec.comparator();
+    sorted(batsmen, |lhs, rhs| cmp(&lhs.1, &rhs.1)).into_iter().unzip()
+  };
+
+  /* Ranks are only worked out if someone asked for them */
+  let ranks = if args.has("--with-rank") {
+    let method = args.parsed::<RankMethod>("--rank-method")?.unwrap_or(RankMethod::Standard);
+    Some(rank(&batsmen, method, spec.key_comparator()))
+  } else {
+    None
+  };
+  let (batsmen, scores): (Vec<Batsman>, Vec<Option<f32>>) = batsmen.into_iter().map(|s| (s.record, s.score)).unzip();
+
+  /* Sampling and shuffling work on positions in the sorted list, so
+   * each batsman keeps the rank they had in the full list */
+  let mut rng = match args.parsed::<u64>("--seed")? {
+    Some(seed) => Rng::new(seed),
+    None => Rng::from_time()
+  };
+  let mut picked = (0..batsmen.len()).collect::<Vec<usize>>();
+  if let Some(n) = args.parsed::<usize>("--sample")? {
+    picked = rng.sample(&picked, n);
+  }
+  if args.has("--shuffle") {
+    rng.shuffle(&mut picked);
+  }
+  let picked = paginate(&picked,
+                        args.parsed::<usize>("--offset")?.unwrap_or(0),
+                        args.parsed::<usize>("--limit")?,
+                        args.parsed::<usize>("--tail")?);
+
+  /* The Debug output has nowhere to put a score, the script's fields
+   * or which file (and line) each batsman came from, and always shows
+   * every field, so those get a table unless another format was asked
+   * for */
+  let format = match args.value("--format") {
+    None if score.is_some() || script.is_some() || input.archive || ["--fields", "--derive", "--enrich", "--totals", "--with-source"].iter().any(|f| args.has(f)) => Some("table"),
+    f => f
+  };
+
+  if args.has("--explain") {
+    let mut steps = vec![
+      format!("input:     {}", input.description.replace('\n', "\n           ")),
+      format!("parsed:    {} rows", parsed),
+      format!("normalize: {}", args.value("--normalize").unwrap_or("none")),
+      format!("filter:    surname starts with C, {} rows left", filtered)
+    ];
+    if let Some(path) = args.value("--script") {
+      steps.push(format!("script:    {}, {} rows left", path, batsmen.len()));
+    }
+    if let Some(e) = args.value("--score") {
+      steps.push(format!("score:     {}", e));
+    }
+    steps.push(format!("sort:      {}, then surname and initials for ties", spec));
+    if ranks.is_some() {
+      steps.push(format!("rank:      {}", args.value("--rank-method").unwrap_or("standard")));
+    }
+    steps.push(format!("pick:      {} of {} rows (--sample, --shuffle, --offset, --limit, --tail)", picked.len(), batsmen.len()));
+    if let Some(p) = args.value("--partition-by") {
+      steps.push(format!("partition: by {} into {}", p, args.value("--output-dir").unwrap_or("(no --output-dir)")));
+    }
+    steps.push(format!("output:    {}", match (args.value("--template"), format) {
+      (Some(t), _) => format!("template {}", t),
+      (None, Some(f)) => format!("{} format", f),
+      (None, None) if args.has("--partition-by") => "batting lines".to_string(),
+      (None, None) => "Debug listing".to_string()
+    }));
+    return print_output(args, &(steps.join("\n") + "\n"));
+  }
+
+  /* A template gets the averages before they are rounded for display,
+   * so it can format them however it likes */
+  if let Some(path) = args.value("--template") {
+    let template = read_file(path, false, None)?;
+    let chosen = picked.iter().map(|&i| input.names.batsman(&batsmen[i])).collect::<Vec<Batsman>>();
+    let mut context = context(&chosen);
+    if let Some(ref r) = ranks {
+      context.insert("ranks", &picked.iter().map(|&i| r[i]).collect::<Vec<u32>>());
+    }
+    if score.is_some() {
+      context.insert("scores", &picked.iter().map(|&i| scores[i]).collect::<Vec<Option<f32>>>());
+    }
+    if args.has("--with-source") {
+      context.insert("sources", &picked.iter().map(|&i| {
+        input.sources.line(rows[i]).map(|(file, line)| json!({ "file": file, "line": line }))
+      }).collect::<Vec<_>>());
+    }
+    print_output(args, &render(&template, &context, false)?)?;
+    return Ok(());
+  }
+
+  /* Extra columns from the script, worked out before the averages
+   * are rounded */
+  let derived = match script {
+    Some(ref s) => picked.iter().map(|&i| s.fields(&batsmen[i])).collect::<CliResult<Vec<_>>>()?,
+    None => Vec::new()
+  };
+
+  /* Averages get checked before they are rounded for display, since
+   * rounding would make almost every one of them look wrong. Normalized
+   * averages no longer come from the runs, so there is nothing to
+   * check them against. */
+  let normalized = args.value("--normalize").is_some();
+  let invalid = picked.iter().map(|&i| !normalized && !average_is_consistent(&batsmen[i])).collect::<Vec<bool>>();
+  let batsmen = picked.iter().map(|&i| {
+    /* Struct update syntax: everything except the average is
+     * copied across from the original unchanged. Whole numbers are
+     * exact as either kind of average, so rounding as an f32 rounds
+     * halves away from zero whichever it is. */
+    let average = average_f32(batsmen[i].average).round();
+    Batsman { average: average_from_f32(average).unwrap_or_default(), ..batsmen[i].clone() }
+  }).collect::<Vec<Batsman>>();
+  let ranks = ranks.map(|r| picked.iter().map(|&i| r[i]).collect::<Vec<u32>>());
+  let rows = picked.iter().map(|&i| rows[i]).collect::<Vec<usize>>();
+
+  let _span = debug_span!("output").entered();
+
+  let partition = args.parsed::<Partition>("--partition-by")?;
+  match (format, partition) {
+    (None, Some(p)) => write_lines(args, p, &input, &batsmen, &rows)?,
+    (None, None) => {
+      let shown = batsmen.iter().map(|b| input.names.batsman(b)).collect::<Vec<Batsman>>();
+      print_output(args, &match ranks {
+        Some(r) => format!("{:?}\n", r.iter().zip(shown.iter()).collect::<Vec<(&u32, &Batsman)>>()),
+        None => format!("{:?}\n", shown)
+      })?
+    },
+    (Some(format), partition) => {
+      let mut table = Table::from_records(&batsmen);
+      if let Some(r) = ranks {
+        table.insert_column(0, "rank", r.into_iter().map(|x| Some(Value::Integer(x))).collect());
+      }
+      if score.is_some() {
+        let end = table.columns.len();
+        table.insert_column(end, "score", picked.iter().map(|&i| {
+          scores[i].map(|s| Value::Float((s * 100.0).round() / 100.0))
+        }).collect());
+      }
+      let mut names: Vec<&str> = Vec::new();
+      for (name, _) in derived.iter().flatten() {
+        if !names.contains(&name.as_str()) {
+          names.push(name);
+        }
+      }
+      for name in names {
+        let end = table.columns.len();
+        table.insert_column(end, name, derived.iter().map(|row| {
+          row.iter().find(|f| f.0 == name).map(|f| match f.1 {
+            Derived::Number(n) => Value::Float(n),
+            Derived::Text(ref t) => Value::Text(t)
+          })
+        }).collect());
+      }
+      for (row, &bad) in invalid.iter().enumerate() {
+        if bad {
+          table.flag(row, "average");
+        }
+      }
+      match partition {
+        Some(p) => write_tables(args, p, format, &input, table, &rows)?,
+        None => print_output(args, &plugins::render(args, format, &args.sourced_columns(table, &input, &rows)?)?)?
+      }
+    }
+  }
+  Ok(())
+}
@@ -0,0 +1,135 @@
+/* A tour of what the borrow checker allows and forbids, one lesson at
+ * a time:
+ *
+ *   learn                  runs every lesson in order
+ *   learn list             lists the lessons
+ *   learn run slices       runs one of them
+ *   learn quiz             asks what some snippets do (see quiz.rs)
+ *   learn progress         shows what has been done so far
+ *   learn reset            forgets it all again
+ *   learn export --markdown book/
+ *                          writes the lessons out as an mdBook
+ *   learn try mine.rs      compiles and runs some code (see sandbox.rs)
+ *
+ * With --explain, each lesson first shows its code and says what the
+ * borrow checker makes of it and why, so the output can be read as a
+ * tutorial rather than just a few printed values. With --interactive
+ * it waits between lessons and asks for a guess first (see
+ * interactive.rs). With --measure, each lesson is followed by how long
+ * it took and how much it allocated (see measure.rs). The lessons
+ * themselves live in lessons.rs and their other modules sit in learn/
+ * next to this file. */
+mod export;
+mod interactive;
+mod lessons;
+mod measure;
+mod progress;
+mod quiz;
+mod sandbox;
+
+use crate::cli::Args;
+use crate::error::{CliError, CliResult};
+
+use self::lessons::{LESSONS, Lesson};
+
+/* How wide explanations are wrapped to */
+const WIDTH: usize = 72;
+
+/* Breaks text into lines of at most WIDTH, between words */
+fn wrap(text: &str) -> Vec<String> {
+  let mut lines = Vec::new();
+  let mut line = String::new();
+  for word in text.split_whitespace() {
+    if !line.is_empty() && line.len() + 1 + word.len() > WIDTH {
+      lines.push(line);
+      line = String::new();
+    }
+    if !line.is_empty() {
+      line.push(' ');
+    }
+    line.push_str(word);
+  }
+  if !line.is_empty() {
+    lines.push(line);
+  }
+  lines
+}
+
+/* Prints a lesson's code, indented, and a blank line after */
+fn print_code(lesson: &Lesson) {
+  for line in lesson.source().lines() {
+    if line.is_empty() {
+      println!();
+    } else {
+      println!("    {}", line);
+    }
+  }
+  println!();
+}
+
+fn print_explanation(lesson: &Lesson) {
+  for line in wrap(lesson.explanation) {
+    println!("{}", line);
+  }
+}
+
+/* Runs a lesson, after going through it first, or measuring it, if
+ * asked to */
+fn show(lesson: &Lesson, explain: bool, cost: bool) {
+  if explain {
+    println!("== {} ==\n", lesson.name);
+    print_code(lesson);
+    print_explanation(lesson);
+    println!("\nRunning it prints:");
+  }
+  let ((), spent) = measure::measure(lesson.run);
+  if cost {
+    println!("[{} took {}]", lesson.name, spent);
+  }
+  if explain {
+    println!();
+  }
+  progress::update(|p| p.done(lesson.name));
+}
+
+/* Each failure here is something the user asked for which could not
+ * be done, so they are all usage errors as far as the exit code goes */
+pub fn run(args: &Args) -> CliResult<()> {
+  let explain = args.has("--explain");
+  let interactive = args.has("--interactive");
+  let cost = args.has("--measure");
+  let online = args.has("--online");
+  let positional = args.positional.iter().map(|a| a.as_str()).collect::<Vec<&str>>();
+
+  match positional.as_slice() {
+    [] if interactive => interactive::run(&LESSONS.iter().collect::<Vec<&Lesson>>()),
+    [] => {
+      for lesson in LESSONS {
+        show(lesson, explain, cost);
+      }
+    },
+    ["list"] => {
+      let width = LESSONS.iter().map(|l| l.name.len()).max().unwrap_or(0);
+      for lesson in LESSONS {
+        println!("{:width$}  {}", lesson.name, lesson.description, width = width);
+      }
+    },
+    ["run", name] => match lessons::find(name) {
+      Some(lesson) if interactive => interactive::run(&[lesson]),
+      Some(lesson) => show(lesson, explain, cost),
+      None => {
+        let names = LESSONS.iter().map(|l| l.name).collect::<Vec<&str>>();
+        return Err(CliError::Usage(format!("There is no lesson called {:?}, try one of {}", name, names.join(", "))));
+      }
+    },
+    ["quiz"] | ["quiz", _] => quiz::run(positional.get(1).cloned()).map_err(CliError::Usage)?,
+    ["progress"] => progress::show(&quiz::names()).map_err(CliError::Usage)?,
+    ["reset"] => progress::reset().map_err(CliError::Usage)?,
+    ["export", dir] if args.has("--markdown") => export::run(dir).map_err(CliError::Usage)?,
+    ["try", path] => if !sandbox::run(path, online).map_err(CliError::Usage)? {
+      return Err(CliError::Usage(format!("{} did not compile and run", path)));
+    },
+    _ => return Err(CliError::Usage("Usage: learn [list | run <lesson> | quiz [<topic>] | progress | reset |\n             export --markdown <dir> | try <file.rs> [--online]] [--explain | --interactive] [--measure]".to_string()))
+  }
+  Ok(())
+}
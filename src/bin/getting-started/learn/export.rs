@@ -0,0 +1,47 @@
+use std::fs;
+use std::path::Path;
+
+use super::lessons::{LESSONS, Lesson};
+
+/* A lesson as a chapter: what it shows, its code, and what to make of
+ * it. The code uses getting_started, which mdbook test would not know
+ * about, hence ignore. */
+fn chapter(lesson: &Lesson) -> String {
+  format!("# {}\n\n{}.\n\n```rust,ignore\n{}\n```\n\n{}\n\nRun it with `borrowck run {}`.\n",
+          lesson.name, lesson.description, lesson.source().trim_end(), lesson.explanation, lesson.name)
+}
+
+fn summary() -> String {
+  let mut summary = "# Summary\n\n".to_string();
+  for lesson in LESSONS {
+    summary += &format!("- [{}]({}.md)\n", lesson.name, lesson.name);
+  }
+  summary
+}
+
+const BOOK_TOML: &str = "[book]\ntitle = \"What the borrow checker allows\"\nsrc = \"src\"\n";
+
+/* borrowck export --markdown book/
+ *
+ * Writes every lesson out as a chapter of an mdBook, with its code and
+ * explanation taken from the same lessons the binary runs, so the two
+ * cannot say different things. mdbook build book/ then makes it into a
+ * website. Chapters are overwritten, and book.toml is only written if
+ * there is not one already, so it can be changed. */
+pub fn run(dir: &str) -> Result<(), String> {
+  let dir = Path::new(dir);
+  let src = dir.join("src");
+  let write = |path: &Path, text: &str| fs::write(path, text).map_err(|e| format!("Could not write {}: {}", path.display(), e));
+
+  fs::create_dir_all(&src).map_err(|e| format!("Could not create {}: {}", src.display(), e))?;
+  let toml = dir.join("book.toml");
+  if !toml.exists() {
+    write(&toml, BOOK_TOML)?;
+  }
+  write(&src.join("SUMMARY.md"), &summary())?;
+  for lesson in LESSONS {
+    write(&src.join(format!("{}.md", lesson.name)), &chapter(lesson))?;
+  }
+  println!("Wrote {} chapters to {}", LESSONS.len(), src.display());
+  Ok(())
+}
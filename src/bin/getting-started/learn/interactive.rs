@@ -0,0 +1,75 @@
+use std::io::{self, BufRead, Write};
+
+use super::lessons::Lesson;
+use super::progress;
+
+use super::{print_code, print_explanation};
+
+/* Prints a question and reads the answer, or None if stdin has ended
+ * or the reader wants to stop */
+pub fn ask(question: &str) -> Option<String> {
+  print!("{} ", question);
+  io::stdout().flush().ok()?;
+  let mut answer = String::new();
+  match io::stdin().lock().read_line(&mut answer) {
+    Ok(0) | Err(_) => {
+      println!();
+      None
+    },
+    Ok(_) => match answer.trim() {
+      "q" | "quit" => None,
+      answer => Some(answer.to_string())
+    }
+  }
+}
+
+/* borrowck --interactive
+ *
+ * Goes through the lessons one at a time, waiting for the reader
+ * between them. Each shows its code and asks what the reader thinks
+ * will happen: whether it compiles, and what it prints if it does.
+ * Then it runs, the explanation follows, and the reader says whether
+ * they had it right. A tally of how many they got is kept as they go
+ * and given at the end, along with the lessons worth another look.
+ * Answering q, or ending stdin, stops early. */
+pub fn run(lessons: &[&Lesson]) {
+  let mut right = 0;
+  let mut missed = Vec::new();
+  let mut seen = 0;
+
+  for (n, lesson) in lessons.iter().enumerate() {
+    println!("== Lesson {} of {}: {} ==\n", n + 1, lessons.len(), lesson.name);
+    println!("{}\n", lesson.description);
+    print_code(lesson);
+    if ask("Does this compile, and if so what does it print?\n>").is_none() {
+      break;
+    }
+
+    println!("\nIt compiles, and prints:");
+    (lesson.run)();
+    println!();
+    print_explanation(lesson);
+    println!();
+
+    let answer = match ask("Did you have it right? [y/n]") {
+      Some(answer) => answer,
+      None => break
+    };
+    seen += 1;
+    progress::update(|p| p.done(lesson.name));
+    if answer.starts_with('y') || answer.starts_with('Y') {
+      right += 1;
+    } else {
+      missed.push(lesson.name);
+    }
+    println!("{} of {} right so far\n", right, seen);
+  }
+
+  if seen == 0 {
+    return;
+  }
+  println!("You had {} of {} right.", right, seen);
+  if !missed.is_empty() {
+    println!("Worth another look: {}", missed.join(", "));
+  }
+}
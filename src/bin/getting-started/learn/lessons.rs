@@ -0,0 +1,960 @@
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::cmp::Ordering as CmpOrdering;
+use std::fmt;
+use std::io::{self, BufRead};
+use std::num::ParseIntError;
+use std::ops::Deref;
+use std::rc::{Rc, Weak};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use getting_started::batsman::Batsman;
+
+use super::measure::measure;
+
+fn moving() {
+  let v = vec![1, 2, 3];
+  let v2 = v;
+
+  println!("{:?}", v2);
+}
+
+fn copy() {
+  let mut v = vec![1, 2, 3];
+  let v2 = v.clone();
+
+  v.push(4);
+
+  println!("{:?} {:?}", v, v2);
+
+  /* What each costs: a move copies the Vec's pointer, length and
+   * capacity, where a clone copies every item into new memory */
+  let runs = vec![0u32; 100_000];
+  let (runs, moved) = measure(move || runs);
+  let (_copy, cloned) = measure(|| runs.clone());
+  println!("moving: {} allocations, cloning: {} allocations of {} bytes", moved.allocations, cloned.allocations, cloned.bytes);
+}
+
+fn slice_fn(s: &str) {
+  println!("{:?}", s);
+}
+
+fn slices() {
+  let s = String::from("hello world");
+
+  let hello = &s[0..5];
+  let world = &s[6..11];
+
+  slice_fn(hello);
+  slice_fn(world);
+}
+
+fn refcells() {
+  let x = RefCell::new(3);
+  let w = &x;
+
+  {
+    let mut y = x.borrow_mut();
+    *y += 1;
+  }
+
+  {
+    let mut z = x.borrow_mut();
+    *z += 1;
+  }
+
+  println!("{:?} {:?}", x, w);
+
+  /* Asking first, with try_borrow_mut, gives an Err instead of the
+   * panic borrow_mut would have for the second borrow here */
+  {
+    let _y = x.borrow_mut();
+    println!("second borrow: {:?}", x.try_borrow_mut().map(|_| ()));
+  }
+
+  /* A Cell never lends out a reference at all, only copies of what is
+   * inside, so there is nothing to check */
+  let runs = Cell::new(11629);
+  let r = &runs;
+  r.set(r.get() + 100);
+  println!("{}", runs.get());
+
+  /* A Mutex is RefCell's counterpart for threads: lock waits for the
+   * other threads to finish with it instead of panicking */
+  let total = Mutex::new(0);
+  thread::scope(|scope| {
+    for runs in [11629, 8900] {
+      let total = &total;
+      scope.spawn(move || *total.lock().unwrap() += runs);
+    }
+  });
+  println!("{}", total.lock().unwrap());
+
+  /* And an atomic is Cell's: a number any thread can change, with no
+   * lock, because the processor does each change in one step */
+  let count = AtomicU32::new(0);
+  thread::scope(|scope| {
+    for _ in 0..4 {
+      scope.spawn(|| count.fetch_add(1, Ordering::Relaxed));
+    }
+  });
+  println!("{}", count.load(Ordering::Relaxed));
+}
+
+fn drops() {
+  /* Says when it is dropped */
+  struct Noisy(&'static str);
+
+  impl Drop for Noisy {
+    fn drop(&mut self) {
+      println!("dropped {}", self.0);
+    }
+  }
+
+  /* Dropped at the end of their scope, the last made going first */
+  {
+    let _first = Noisy("first");
+    let _second = Noisy("second");
+    println!("end of the block");
+  }
+
+  /* drop takes ownership, so the value goes there and then */
+  let early = Noisy("early");
+  drop(early);
+  println!("after drop");
+
+  /* A struct's fields go in the order they are declared, after it */
+  struct Pair {
+    _a: Noisy,
+    _b: Noisy
+  }
+  {
+    let _pair = Pair { _a: Noisy("a"), _b: Noisy("b") };
+  }
+
+  /* A RefCell's borrow_mut hands out a guard, and the borrow lasts
+   * until the guard is dropped, which is why refcells puts each one
+   * in a block of its own */
+  let x = RefCell::new(3);
+  let y = x.borrow_mut();
+  println!("borrowed: {}", x.try_borrow().is_err());
+  drop(y);
+  println!("borrowed: {}", x.try_borrow().is_err());
+
+  /* A value nobody keeps is dropped at the end of its statement */
+  let _ = Noisy("unnamed");
+  println!("end of the lesson");
+}
+
+fn cycles() {
+  /* Each node counts itself in drops when it is dropped */
+  struct Node {
+    next: RefCell<Option<Rc<Node>>>,
+    back: RefCell<Weak<Node>>,
+    drops: Rc<Cell<u32>>
+  }
+
+  impl Drop for Node {
+    fn drop(&mut self) {
+      self.drops.set(self.drops.get() + 1);
+    }
+  }
+
+  let drops = Rc::new(Cell::new(0));
+  let node = || Rc::new(Node { next: RefCell::new(None), back: RefCell::new(Weak::new()), drops: Rc::clone(&drops) });
+
+  /* a and b own each other, so neither count ever gets to 0 */
+  {
+    let a = node();
+    let b = node();
+    *a.next.borrow_mut() = Some(Rc::clone(&b));
+    *b.next.borrow_mut() = Some(Rc::clone(&a));
+    println!("a has {} owners, b has {}", Rc::strong_count(&a), Rc::strong_count(&b));
+  }
+  println!("{} of 2 dropped", drops.get());
+
+  /* b only has a Weak pointer back to a, which does not own it */
+  drops.set(0);
+  {
+    let a = node();
+    let b = node();
+    *a.next.borrow_mut() = Some(Rc::clone(&b));
+    *b.back.borrow_mut() = Rc::downgrade(&a);
+    println!("a has {} owners, b has {}", Rc::strong_count(&a), Rc::strong_count(&b));
+    println!("b can still reach a: {}", b.back.borrow().upgrade().is_some());
+  }
+  println!("{} of 2 dropped", drops.get());
+}
+
+fn pointers() {
+  /* A list of innings, each holding the rest. Without the Box an
+   * Innings would have to contain a whole Innings, and so on forever */
+  enum Innings {
+    Then(u32, Box<Innings>),
+    End
+  }
+
+  fn total(innings: &Innings) -> u32 {
+    match *innings {
+      Innings::Then(runs, ref rest) => runs + total(rest),
+      Innings::End => 0
+    }
+  }
+
+  let innings = Innings::Then(294, Box::new(Innings::Then(12, Box::new(Innings::End))));
+  println!("{}", total(&innings));
+
+  /* Cloning a Box clones what is in it; cloning an Rc only counts one
+   * more owner of the same thing */
+  let boxed = Box::new([0u32; 1000]);
+  let shared = Rc::new([0u32; 1000]);
+  let (_b, box_clone) = measure(|| boxed.clone());
+  let (_r, rc_clone) = measure(|| Rc::clone(&shared));
+  println!("Box clone: {} bytes, Rc clone: {} bytes", box_clone.bytes, rc_clone.bytes);
+
+  /* Borrows the name when it is already tidy, and only makes a new
+   * String when it has to change it */
+  fn tidy(surname: &str) -> Cow<'_, str> {
+    if surname.chars().all(|c| c.is_alphabetic()) {
+      Cow::Borrowed(surname)
+    } else {
+      Cow::Owned(surname.chars().filter(|c| c.is_alphabetic()).collect())
+    }
+  }
+
+  for surname in ["Cook", "Gooch*"] {
+    match tidy(surname) {
+      Cow::Borrowed(s) => println!("{} was borrowed", s),
+      Cow::Owned(s) => println!("{} was made", s)
+    }
+  }
+
+  /* A line of a file, which can be used wherever a str can */
+  struct Line(String);
+
+  impl Deref for Line {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+      &self.0
+    }
+  }
+
+  fn surname(line: &str) -> &str {
+    Batsman::parse(line).map(|b| b.surname).unwrap_or("?")
+  }
+
+  let owned = String::from("AN Cook, 11629, 46.33");
+  let boxed = Box::new(String::from("GA Gooch, 8900, 42.58"));
+  let line = Line(String::from("DI Gower, 8231, 44.25"));
+  println!("{} {} {}", surname(&owned), surname(&boxed), surname(&line));
+}
+
+fn traits() {
+  /* Anything which can write a batsman out as a line */
+  trait Formatter {
+    fn format(&self, b: &Batsman) -> String;
+  }
+
+  struct Plain;
+  struct Csv;
+
+  impl Formatter for Plain {
+    fn format(&self, b: &Batsman) -> String {
+      format!("{} {} made {} runs", b.initials, b.surname, b.runs)
+    }
+  }
+
+  impl Formatter for Csv {
+    fn format(&self, b: &Batsman) -> String {
+      format!("{},{},{},{}", b.initials, b.surname, b.runs, b.average)
+    }
+  }
+
+  let cook = Batsman::parse("AN Cook, 11629, 46.33").unwrap();
+
+  /* Generic: a copy of this is compiled for each F it is used with,
+   * and each calls its own format directly */
+  fn write_generic<F: Formatter>(f: &F, b: &Batsman) {
+    println!("{}", f.format(b));
+  }
+  write_generic(&Plain, &cook);
+  write_generic(&Csv, &cook);
+
+  /* dyn: one copy, which looks up format in the formatter's vtable
+   * each time it is called. Which formatter it is can be decided
+   * while the program runs, and different ones kept together. */
+  fn write_dyn(f: &dyn Formatter, b: &Batsman) {
+    println!("{}", f.format(b));
+  }
+  let formatters: Vec<Box<dyn Formatter>> = vec![Box::new(Plain), Box::new(Csv)];
+  for f in &formatters {
+    write_dyn(f.as_ref(), &cook);
+  }
+}
+
+fn generics() {
+  let file = "AN Cook, 11629, 46.33\nGA Gooch, 8900, 42.58\nDI Gower, 8231, 44.25\n";
+  let batsmen = || file.lines().map(|l| Batsman::parse(l).unwrap()).collect::<Vec<Batsman>>();
+  let surnames = |x: &[Batsman]| x.iter().map(|b| b.surname).collect::<Vec<&str>>().join(" ");
+
+  /* 1. Works, but only for batsmen, and only sorts by runs */
+  fn sorted_by_runs(mut x: Vec<Batsman>) -> Vec<Batsman> {
+    x.sort_by_key(|b| std::cmp::Reverse(b.runs));
+    x
+  }
+  println!("{}", surnames(&sorted_by_runs(batsmen())));
+
+  /* 2. Any T, in any order the caller says. The first sorted looked
+   * like this: it copied x, so needed T: Clone. */
+  fn sorted_copy<T: Clone, F: FnMut(&T, &T) -> CmpOrdering>(x: &[T], cmp: F) -> Vec<T> {
+    let mut y = x.to_vec();
+    y.sort_by(cmp);
+    y
+  }
+  let mine = batsmen();
+  println!("{}", surnames(&sorted_copy(&mine, |l, r| l.initials.cmp(r.initials))));
+
+  /* 3. sorted as it is now: taking the Vec means there is nothing to
+   * copy, so the Clone bound goes, and the bounds move to a where
+   * clause where they are easier to read */
+  fn sorted<T, F>(x: Vec<T>, cmp: F) -> Vec<T>
+    where F: FnMut(&T, &T) -> CmpOrdering
+  {
+    let mut y = x;
+    y.sort_by(cmp);
+    y
+  }
+  println!("{}", surnames(&sorted(batsmen(), |l, r| l.average.partial_cmp(&r.average).unwrap())));
+
+  /* 4. impl Trait in an argument is a generic without a name, handy
+   * when nothing else needs to mention it */
+  fn sorted_by_key<T, K: Ord>(x: Vec<T>, mut key: impl FnMut(&T) -> K) -> Vec<T> {
+    sorted(x, |l, r| key(l).cmp(&key(r)))
+  }
+  println!("{}", surnames(&sorted_by_key(batsmen(), |b| b.runs)));
+
+  /* 5. And in a return type it hides which iterator comes back, here
+   * one borrowing from x, so no T is copied */
+  fn best<T>(x: &[T], n: usize, key: impl Fn(&T) -> u32) -> impl Iterator<Item = &T> {
+    let mut refs = x.iter().collect::<Vec<&T>>();
+    refs.sort_by_key(|t| std::cmp::Reverse(key(t)));
+    refs.into_iter().take(n)
+  }
+  println!("{}", best(&mine, 2, |b| b.runs).map(|b| b.surname).collect::<Vec<&str>>().join(" "));
+}
+
+fn patterns() {
+  let file = "AN Cook, 11629, 46.33\nGA Gooch, 8900, 42.58\nbad line\nMC Cowdrey, 7624, 44.06\n";
+
+  /* The match has an arm for every way parse can turn out */
+  let mut batsmen = Vec::new();
+  for line in file.lines() {
+    match Batsman::parse(line) {
+      Ok(b) if b.runs >= 10000 => {
+        println!("{} passed 10000", b.surname);
+        batsmen.push(b);
+      },
+      Ok(b) => batsmen.push(b),
+      Err(e) => println!("skipped {:?}: {}", line, e)
+    }
+  }
+
+  /* What batsmen used to filter with: chars().next() gives an Option,
+   * and matches! asks whether it fits one pattern */
+  let cs = batsmen.iter().filter(|b| matches!(b.surname.chars().next(), Some('C'))).count();
+  println!("{} start with C", cs);
+
+  /* The same Option matched out in full, naming what is inside */
+  for b in &batsmen {
+    match b.surname.chars().next() {
+      Some('C') => println!("{}: C", b.surname),
+      Some(c @ 'A'..='M') => println!("{}: {} is in the first half", b.surname, c),
+      Some(c) => println!("{}: {}", b.surname, c),
+      None => println!("no surname")
+    }
+  }
+
+  /* if let when only one pattern matters. Matching through a reference
+   * binds by reference too, so first is a &Batsman and nothing moves */
+  if let Some(first) = &batsmen.first() {
+    println!("first is {}", first.surname);
+  }
+
+  /* while let goes round for as long as its pattern matches, which
+   * here is until pop finds the Vec empty */
+  let mut surnames = batsmen.iter().map(|b| b.surname).collect::<Vec<&str>>();
+  while let Some(surname) = surnames.pop() {
+    print!("{} ", surname);
+  }
+  println!();
+
+  /* An enum's variants can hold different things, and a match has to
+   * cover them all */
+  enum Dismissal {
+    Bowled,
+    Caught { fielder: &'static str },
+    NotOut
+  }
+
+  for out in [Dismissal::Bowled, Dismissal::Caught { fielder: "Knott" }, Dismissal::NotOut] {
+    let said = match out {
+      Dismissal::Bowled => "bowled".to_string(),
+      Dismissal::Caught { fielder } => format!("caught {}", fielder),
+      Dismissal::NotOut => "not out".to_string()
+    };
+    println!("{}", said);
+  }
+}
+
+fn lifetimes() {
+  /* One reference in and one out, so the compiler can tell without
+   * being told that the answer borrows from s */
+  fn first_word(s: &str) -> &str {
+    s.split(' ').next().unwrap_or("")
+  }
+
+  /* Two references in, so it has to be told: the answer could be
+   * either, so it lives only as long as both do */
+  fn longer<'a>(a: &'a str, b: &'a str) -> &'a str {
+    if a.len() >= b.len() { a } else { b }
+  }
+
+  let line = String::from("AN Cook, 11629, 46.33");
+  let cook = Batsman::parse(&line).unwrap();
+  println!("{} {} was read from {:?}", cook.initials, cook.surname, first_word(&line));
+
+  let gooch: &'static str = "Gooch";
+  println!("{}", longer(cook.surname, gooch));
+}
+
+fn concurrency() {
+  /* move gives the line to the new thread, which might outlive main */
+  let line = String::from("AN Cook, 11629, 46.33");
+  let runs = thread::spawn(move || Batsman::parse(&line).unwrap().runs).join().unwrap();
+  println!("{}", runs);
+
+  /* Arc lets each thread own a share of the total, and the Mutex lets
+   * only one of them change it at a time */
+  let total = Arc::new(Mutex::new(0));
+  let handles = [11629, 8900, 8231].iter().map(|&runs| {
+    let total = Arc::clone(&total);
+    thread::spawn(move || *total.lock().unwrap() += runs)
+  }).collect::<Vec<_>>();
+  for handle in handles {
+    handle.join().unwrap();
+  }
+  println!("{}", total.lock().unwrap());
+
+  /* Scoped threads are all joined before scope returns, so they can
+   * borrow from main without move or Arc */
+  let runs = [11629, 8900, 8231];
+  let (first, rest) = runs.split_at(1);
+  let (a, b) = thread::scope(|scope| {
+    let a = scope.spawn(|| first.iter().sum::<u32>());
+    let b = scope.spawn(|| rest.iter().sum::<u32>());
+    (a.join().unwrap(), b.join().unwrap())
+  });
+  println!("{} + {}", a, b);
+}
+
+fn errors() {
+  /* The first version: any problem with the file panics */
+  fn total_runs_or_panic(file: &[u8]) -> u32 {
+    let mut total = 0;
+    for line in file.lines() {
+      let line = line.expect("could not read the file");
+      let runs = line.split(", ").nth(1).expect("no runs on a line");
+      total += runs.parse::<u32>().expect("runs is not a number");
+    }
+    total
+  }
+
+  /* Everything which can go wrong, for the caller to decide about */
+  #[derive(Debug)]
+  enum ReadError {
+    Io(io::Error),
+    MissingRuns(usize),
+    BadRuns(ParseIntError)
+  }
+
+  impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+      match *self {
+        ReadError::Io(ref e) => write!(f, "could not read the file: {}", e),
+        ReadError::MissingRuns(n) => write!(f, "no runs on line {}", n),
+        ReadError::BadRuns(ref e) => write!(f, "runs is not a number: {}", e)
+      }
+    }
+  }
+
+  /* These let ? turn the library's errors into ours by itself */
+  impl From<io::Error> for ReadError {
+    fn from(e: io::Error) -> ReadError {
+      ReadError::Io(e)
+    }
+  }
+
+  impl From<ParseIntError> for ReadError {
+    fn from(e: ParseIntError) -> ReadError {
+      ReadError::BadRuns(e)
+    }
+  }
+
+  /* The same thing again, with every expect a ? */
+  fn total_runs(file: &[u8]) -> Result<u32, ReadError> {
+    let mut total = 0;
+    for (n, line) in file.lines().enumerate() {
+      let line = line?;
+      let runs = line.split(", ").nth(1).ok_or(ReadError::MissingRuns(n + 1))?;
+      total += runs.parse::<u32>()?;
+    }
+    Ok(total)
+  }
+
+  let good = b"AN Cook, 11629, 46.33\nGA Gooch, 8900, 42.58\n";
+  println!("{}", total_runs_or_panic(good));
+  println!("{:?}", total_runs(good));
+
+  for bad in [&b"AN Cook, lots, 46.33\n"[..], b"AN Cook\n", b"\xff\n"] {
+    match total_runs(bad) {
+      Ok(total) => println!("{}", total),
+      Err(e) => println!("{}", e)
+    }
+  }
+}
+
+fn iterators() {
+  let file = "AN Cook, 11629, 46.33\nGA Gooch, 8900, 42.58\nDI Gower, 8231, 44.25\n";
+
+  /* How batsmen reads a file: each step wraps the one before, and
+   * collect into a Result stops at the first line which fails */
+  let batsmen = file.lines().enumerate()
+    .map(|(i, l)| Batsman::parse(l).map_err(|e| format!("line {}: {}", i + 1, e)))
+    .collect::<Result<Vec<Batsman>, String>>()
+    .unwrap();
+
+  /* filter looks at each batsman through a reference, map picks out
+   * the surname, and collect gathers what comes out */
+  let heavy = batsmen.iter().filter(|b| b.runs > 8500).map(|b| b.surname).collect::<Vec<&str>>();
+  println!("{:?}", heavy);
+
+  /* Nothing runs until something asks for an item: next only wants
+   * the first, so only the first line is parsed */
+  let mut parsed = 0;
+  let first = file.lines().map(|l| {
+    parsed += 1;
+    Batsman::parse(l)
+  }).next();
+  println!("{} parsed to find {}", parsed, first.unwrap().unwrap().surname);
+
+  /* A closure which only reads what it uses borrows it, and is Fn */
+  let least = 8500;
+  let enough = |b: &&Batsman| b.runs >= least;
+  println!("{}", batsmen.iter().filter(enough).count());
+
+  /* One which changes what it uses borrows it mutably, and is FnMut */
+  let mut total = 0;
+  batsmen.iter().for_each(|b| total += b.runs);
+  println!("{}", total);
+
+  /* move takes what it uses, and one which gives that away again can
+   * only be called once, so is FnOnce */
+  let surnames = batsmen.iter().map(|b| b.surname.to_string()).collect::<Vec<String>>();
+  let hand_over = move || surnames;
+  println!("{}", hand_over().join(", "));
+}
+
+#[cfg(feature = "async")]
+fn futures() {
+  use tokio::sync::Mutex as AsyncMutex;
+  use tokio::task;
+
+  let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+  runtime.block_on(async {
+    /* block_on waits for this future to finish, so it can borrow line,
+     * even across the .await where it stops and lets others run */
+    let line = String::from("AN Cook, 11629, 46.33");
+    let cook = Batsman::parse(&line).unwrap();
+    task::yield_now().await;
+    println!("{}", cook.surname);
+
+    /* A spawned task might run for longer than this function, so like
+     * a thread it has to own what it uses: async move takes line */
+    let line = String::from("GA Gooch, 8900, 42.58");
+    let runs = tokio::spawn(async move {
+      task::yield_now().await;
+      Batsman::parse(&line).unwrap().runs
+    }).await.unwrap();
+    println!("{}", runs);
+
+    /* Tasks can move between threads at each .await, so anything held
+     * across one has to be Send. tokio's Mutex guard is; std's is not. */
+    let total = Arc::new(AsyncMutex::new(0));
+    let handles = [11629, 8900].iter().map(|&runs| {
+      let total = Arc::clone(&total);
+      tokio::spawn(async move {
+        let mut total = total.lock().await;
+        task::yield_now().await;
+        *total += runs;
+      })
+    }).collect::<Vec<_>>();
+    for handle in handles {
+      handle.await.unwrap();
+    }
+    println!("{}", total.lock().await);
+  });
+}
+
+#[cfg(not(feature = "async"))]
+fn futures() {
+  println!("This lesson needs borrowck to be built with --features async");
+}
+
+fn unsafety() {
+  /* The standard library's split_at_mut, written out. The borrow
+   * checker cannot see that the two halves never overlap, so the
+   * function has to promise that itself. */
+  fn split_at_mut<T>(values: &mut [T], mid: usize) -> (&mut [T], &mut [T]) {
+    let len = values.len();
+    let start = values.as_mut_ptr();
+
+    /* The one check everything below depends on */
+    assert!(mid <= len, "mid is past the end");
+
+    /* SAFETY: start..start + mid and start + mid..start + len are both
+     * inside values, and do not overlap, so each half is the only way
+     * to reach the items in it for as long as values is borrowed */
+    unsafe {
+      (std::slice::from_raw_parts_mut(start, mid),
+       std::slice::from_raw_parts_mut(start.add(mid), len - mid))
+    }
+  }
+
+  let mut runs = [11629, 8900, 8231, 8181];
+  let (england, rest) = split_at_mut(&mut runs, 2);
+  england[0] += 1;
+  rest[0] += 1;
+  println!("{:?}", runs);
+
+  /* Dereferencing a raw pointer is unsafe too: the compiler does not
+   * know it points at anything, so we have to */
+  let average = 46.33_f32;
+  let pointer = &average as *const f32;
+  println!("{}", unsafe { *pointer });
+}
+
+/* This file, so a lesson can show its own code with --explain */
+const SOURCE: &str = include_str!("lessons.rs");
+
+/* One lesson: a name to pick it by, a line saying what it shows, what
+ * --explain says about it, and the function which shows it */
+pub struct Lesson {
+  pub name: &'static str,
+  pub description: &'static str,
+  pub explanation: &'static str,
+  pub run: fn()
+}
+
+impl Lesson {
+  /* The lesson's function as it is written above, from its fn line to
+   * the closing brace at the start of a line. Taking it from the file
+   * itself means it can never say something the code does not. */
+  pub fn source(&self) -> &'static str {
+    let start = SOURCE.find(&format!("\nfn {}()", self.name)).map(|i| i + 1).unwrap_or(0);
+    let end = SOURCE[start..].find("\n}\n").map(|i| start + i + 2).unwrap_or(SOURCE.len());
+    &SOURCE[start..end]
+  }
+}
+
+/* Every lesson, in the order they are best read in. A new lesson only
+ * has to be added here to show up in borrowck list and be runnable
+ * with borrowck run. */
+pub const LESSONS: &[Lesson] = &[
+  Lesson {
+    name: "moving",
+    description: "Assigning a Vec moves it, so the old name can no longer be used",
+    explanation: "A Vec owns the memory its items are in, and only one name can own it \
+                  at a time. `let v2 = v;` moves the Vec to v2 rather than copying it, so \
+                  from then on v is no longer usable and only v2 can be printed. Printing \
+                  v instead (tests/compile_fail/move_not_ok.rs) is refused with \"borrow of \
+                  moved value: `v`\": if both names could use the Vec, both would try to \
+                  free it.",
+    run: moving
+  },
+  Lesson {
+    name: "copy",
+    description: "Cloning makes a second Vec which can change on its own",
+    explanation: "When two names really do need their own Vec, clone makes a second one \
+                  with copies of the items. v and v2 then own different memory, so v \
+                  can be pushed to without v2 changing, and both can be printed. Cloning \
+                  costs a copy of every item, which is why Rust never does it for you: \
+                  measured, moving 100,000 runs allocates nothing, and cloning them \
+                  allocates 400,000 bytes.",
+    run: copy
+  },
+  Lesson {
+    name: "slices",
+    description: "Slices borrow part of a String without copying it",
+    explanation: "`&s[0..5]` borrows part of s without copying it: hello and world are \
+                  references into the String's own memory. Any number of these shared \
+                  borrows can exist at once, because none of them can change s, and the \
+                  borrow checker makes sure s outlives them all. Changing s while they \
+                  were still in use, say with s.clear(), would be refused \
+                  (tests/compile_fail/slices_bad.rs), since it could leave them \
+                  pointing at freed memory.",
+    run: slices
+  },
+  Lesson {
+    name: "refcells",
+    description: "Cell, RefCell, Mutex and atomics change things behind a shared reference",
+    explanation: "A RefCell moves the borrow check from compile time to run time. x is \
+                  shared with w, yet borrow_mut can still change what is inside it, \
+                  as long as only one mutable borrow is alive at a time; otherwise the \
+                  program panics. Each borrow here ends at the end of its own block, \
+                  so the second one is fine. Taking both in the same block would \
+                  compile but panic with \"already borrowed\" when run, unless the \
+                  second asks with try_borrow_mut, which hands back an Err instead. \
+                  A Cell does the same job for small Copy values like a number, with \
+                  get and set and no borrows to check, so it costs nothing; use a \
+                  RefCell when what is inside has to be borrowed, like a Vec to push \
+                  to. Neither can be shared between threads. There a Mutex takes the \
+                  place of a RefCell, making threads wait their turn rather than \
+                  panicking, and an atomic like AtomicU32 takes the place of a Cell, \
+                  for a lone number which needs no lock at all.",
+    run: refcells
+  },
+  Lesson {
+    name: "drops",
+    description: "When values are dropped, and how to drop them sooner",
+    explanation: "Rust frees things at predictable moments rather than when a \
+                  garbage collector gets to them: a value is dropped when its owner \
+                  goes out of scope, running its Drop impl if it has one. Variables \
+                  go in the reverse of the order they were made in, so second goes \
+                  before first, and nothing made later can be left holding a \
+                  reference to something gone. A struct is dropped before its \
+                  fields, which then go in the order they are declared. std's drop \
+                  is an empty function which takes ownership, so calling it ends a \
+                  value there and then. This is RAII: tying something like a lock \
+                  or a borrow to a value, so it is released when the value is \
+                  dropped. borrow_mut's guard is one, and the RefCell counts as \
+                  borrowed until it is dropped, which is why the refcells lesson \
+                  gives each borrow its own block. let _ = drops a value straight \
+                  away, a common surprise with lock guards; let _name = keeps it to \
+                  the end of the scope.",
+    run: drops
+  },
+  Lesson {
+    name: "cycles",
+    description: "Rc pointing both ways leaks, and Weak is how to point back",
+    explanation: "Rc is for values with more than one owner: it counts them, and \
+                  drops the value when the last one goes. Put Rcs in RefCells, as \
+                  the refcells lesson does with a number, and two values can end up \
+                  owning each other. Then each keeps the other's count above 0, so \
+                  when a and b go out of scope neither Node is ever dropped, and \
+                  their memory is leaked for as long as the program runs. The borrow \
+                  checker cannot catch this, since nothing is used after it is \
+                  freed; nothing is freed at all. The fix is for one direction to \
+                  be a Weak pointer, which can reach the value (upgrade gives an Rc \
+                  back while it still exists) without counting as an owner, so \
+                  parents own their children and children only point back.",
+    run: cycles
+  },
+  Lesson {
+    name: "pointers",
+    description: "Box, Cow and Deref: owning, maybe owning, and looking through",
+    explanation: "A Box owns a value kept on the heap, and is only a pointer's size \
+                  itself. That is what lets Innings hold another Innings: the \
+                  compiler has to know how big every type is, and a type containing \
+                  itself directly would be infinitely big. Cloning a Box clones \
+                  what is inside, so a second 4,000 byte array, where cloning an Rc \
+                  allocates nothing and only adds one to its count. A Cow, clone on write, \
+                  holds either a borrowed value or an owned one. tidy borrows the \
+                  surname when it is fine as it is and only makes a String when it \
+                  has to change it, so the usual case costs nothing; a Batsman \
+                  holding its names as Cows could borrow from its line or own them. \
+                  Deref is what makes a type usable as a reference to another. \
+                  String derefs to str and Box<T> to T, so surname, which wants a \
+                  &str, takes a &String or a &Box<String> as it is, with the \
+                  compiler adding the derefs. Line does the same by implementing \
+                  Deref itself.",
+    run: pointers
+  },
+  Lesson {
+    name: "traits",
+    description: "Generics and dyn Trait: two ways to write code for many types",
+    explanation: "write_generic and write_dyn do the same thing, differently. The \
+                  generic one is monomorphized: the compiler makes a copy of it for \
+                  Plain and another for Csv, each calling its format directly, where \
+                  it can be inlined. That is as fast as it gets, but the type has to \
+                  be known when compiling, and every use adds code. A &dyn Formatter \
+                  or Box<dyn Formatter> is a trait object: a pointer to the value \
+                  and to a table of its methods, looked up on each call. One copy of \
+                  write_dyn serves every formatter, and a Vec can hold a mix of them \
+                  chosen at run time, like from a --format flag. Only dyn compatible \
+                  (once called object safe) traits can be trait objects: a method \
+                  which is generic, or returns Self, \
+                  cannot go in the table, since each type would need a different \
+                  entry (tests/compile_fail/not_dyn_compatible.rs). Prefer generics \
+                  by default, and dyn for mixed collections, plugins, or keeping \
+                  compile times and binary size down. When the set of formats is \
+                  fixed, an enum with a match, like batsmen's Format, is a third way.",
+    run: traits
+  },
+  Lesson {
+    name: "generics",
+    description: "Growing sorted() from one type into a generic API",
+    explanation: "The steps are the ones getting_started's own sorted went through. \
+                  sorted_by_runs works, but only for one type and one order. \
+                  Making the type a parameter, T, and the order a closure, F, makes \
+                  it work for anything; the bounds say what the function needs of \
+                  them, and nothing else may be assumed. The first version cloned \
+                  the slice it was given, so it needed T: Clone and could not sort \
+                  a type which could not be copied. Taking the Vec by value instead \
+                  hands it over to be sorted where it is, so that bound could go. \
+                  Moving bounds into a where clause changes nothing but how easy \
+                  the signature is to read once there are several. impl Trait \
+                  gives a generic without naming it: in an argument it is just \
+                  shorter, and in a return type it lets best hand back an iterator \
+                  without spelling out its type, which for a chain of adapters can \
+                  be long, or for a closure impossible. The &T items it gives out \
+                  borrow from x, which needs no 'a written out since x is the only \
+                  reference coming in.",
+    run: generics
+  },
+  Lesson {
+    name: "patterns",
+    description: "match, if let and while let take values apart",
+    explanation: "A match tries its patterns in order and runs the first arm which \
+                  fits, and the compiler insists that some arm fits every possible \
+                  value. Leave Dismissal::NotOut's arm out and it will not compile \
+                  (tests/compile_fail/non_exhaustive.rs), which is what makes adding \
+                  a variant safe: every match which needs updating is pointed out. \
+                  Patterns name the parts they take apart, like b in Ok(b) or \
+                  fielder in Dismissal::Caught, can add a guard with if, and can match a \
+                  range while keeping the value with @. matches! is a match with one \
+                  pattern, giving a bool; batsmen used to filter surnames with it, \
+                  though starts_with says the same thing more plainly. if let is a \
+                  match with one arm you care about, and while let loops until its \
+                  pattern stops matching, here when surnames is empty. Matching through \
+                  a reference binds by reference, so first borrows the Batsman \
+                  rather than moving it out of the Vec.",
+    run: patterns
+  },
+  Lesson {
+    name: "lifetimes",
+    description: "A reference can never outlive what it borrows from",
+    explanation: "A Batsman does not copy its names: initials and surname are &str \
+                  borrowed straight out of the line it was parsed from. That is what \
+                  the 'a in Batsman<'a> says, and it lets the compiler refuse to let \
+                  cook outlive line (tests/compile_fail/batsman_outlives_line.rs). \
+                  Without it the struct does not compile at all \
+                  (struct_needs_lifetime.rs), and a function cannot hand back a \
+                  reference to something it is about to drop \
+                  (dangling_reference.rs). Most functions need no lifetimes written \
+                  out: with one reference in, like first_word, the one out is taken \
+                  to borrow from it. longer takes two, so it has to say which the \
+                  answer borrows from (longer_needs_lifetime.rs). 'static is the \
+                  lifetime of the whole program, which string literals like \
+                  \"Gooch\" have because they are part of the binary.",
+    run: lifetimes
+  },
+  Lesson {
+    name: "concurrency",
+    description: "Threads own or share what they use, and the compiler checks which",
+    explanation: "A spawned thread might run for longer than the function which \
+                  started it, so thread::spawn only takes closures which own \
+                  everything they use: move hands line over to the thread, and \
+                  borrowing it instead is refused \
+                  (tests/compile_fail/spawn_borrows.rs). To share one value between \
+                  threads, each gets a clone of an Arc, a reference count which is \
+                  safe to change from several threads at once, and a Mutex makes \
+                  them take turns changing what is inside. Rc is the same idea \
+                  without the thread safety, so it is not Send and the compiler will \
+                  not let it cross to another thread (rc_across_threads.rs). \
+                  thread::scope joins every thread it starts before it returns, so \
+                  those threads can borrow from the function after all.",
+    run: concurrency
+  },
+  Lesson {
+    name: "errors",
+    description: "Results and ? hand problems back instead of panicking",
+    explanation: "total_runs_or_panic is how a first version often looks: every \
+                  step which can fail has an expect, and any bad line brings the \
+                  whole program down with a panic. total_runs hands the problem back \
+                  instead, as the Err side of a Result, and leaves it to its caller \
+                  to decide what to do: here, print it and carry on. ReadError lists \
+                  everything which can go wrong in one enum, so a caller can match \
+                  on it, and Display says it in words. Each ? returns early with the \
+                  error if there is one, after turning it into a ReadError with the \
+                  From impls, which is why the second version is barely longer than \
+                  the first. The batsmen binary's CliError is the same idea, though \
+                  it converts with map_err instead, because a From impl could not \
+                  know which file the error came from.",
+    run: errors
+  },
+  Lesson {
+    name: "iterators",
+    description: "Iterator chains, closures and what they capture",
+    explanation: "The first chain is the one batsmen itself reads files with. Each \
+                  adapter, like map, wraps the iterator before it and does nothing \
+                  by itself; the work only happens when something at the end asks \
+                  for items, which is why next parses one line of three. Collecting \
+                  into a Result<Vec<_>, _> stops at the first Err. Closures capture \
+                  what they use as lightly as they can. enough only reads least, so \
+                  it borrows it and can be called any number of times, even at once: \
+                  it is Fn. The for_each closure adds to total, so it borrows it \
+                  mutably, which makes it FnMut: callable again and again, but one \
+                  call at a time, and total cannot be read until it is done with. \
+                  hand_over is a move closure which gives surnames away when called, \
+                  so it is FnOnce, and calling it a second time is refused \
+                  (tests/compile_fail/fn_once_twice.rs).",
+    run: iterators
+  },
+  Lesson {
+    name: "futures",
+    description: "What async code can borrow, and what it has to own",
+    explanation: "An async block is turned into a future: a value holding \
+                  everything the code needs to carry on from each .await, \
+                  including any references. Awaiting it right away, or handing it \
+                  to block_on, which waits for it, means those references cannot \
+                  outlive what they point at, so the first future can borrow line \
+                  across its .await just like ordinary code. tokio::spawn instead \
+                  runs the future alongside this one, maybe for longer, so the same \
+                  rule as for thread::spawn applies: the future has to be 'static, \
+                  owning everything, which async move arranges. And since a \
+                  multi-threaded runtime can move a task between threads at any \
+                  .await, whatever it holds across one must be Send. std's \
+                  MutexGuard is not, so holding it across an .await in a spawned \
+                  task is refused (tests/compile_fail/guard_across_await.rs); \
+                  tokio's Mutex, whose lock is itself awaited, has a guard which \
+                  is. This lesson needs borrowck built with --features async.",
+    run: futures
+  },
+  Lesson {
+    name: "unsafety",
+    description: "unsafe, raw pointers, and the promises they need",
+    explanation: "Splitting one &mut slice into two is fine as long as the halves \
+                  do not overlap, but the borrow checker cannot tell that: \
+                  borrowing values mutably twice is all it sees \
+                  (tests/compile_fail/split_twice.rs). An unsafe block lets us do \
+                  it with raw pointers, which are not checked at all, in exchange \
+                  for us making sure of what the compiler would have. Those \
+                  invariants are what the SAFETY comment sets down: both halves are \
+                  inside the slice, thanks to the assert, and they do not overlap. \
+                  Together they mean the function is sound, safe to call with any \
+                  arguments, so the unsafe stays inside it. Getting one wrong is \
+                  undefined behaviour, which need not crash or even show, and so \
+                  cannot be caught by tests alone. Miri, an interpreter which checks \
+                  every pointer as it goes, can: cargo +nightly miri test --test \
+                  miri runs this lesson under it, and with -- --ignored also a \
+                  broken split whose halves overlap, which Miri reports.",
+    run: unsafety
+  }
+];
+
+pub fn find(name: &str) -> Option<&'static Lesson> {
+  LESSONS.iter().find(|l| l.name == name)
+}
@@ -0,0 +1,69 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/* Every allocation the program makes, and how many bytes they asked
+ * for, counted as they happen */
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static BYTES: AtomicUsize = AtomicUsize::new(0);
+
+/* The system allocator, counting. Growing an allocation with realloc
+ * counts as another one, as it may well have to move. */
+struct Counting;
+
+unsafe impl GlobalAlloc for Counting {
+  unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+    unsafe { System.alloc(layout) }
+  }
+
+  unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+    unsafe { System.alloc_zeroed(layout) }
+  }
+
+  unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    BYTES.fetch_add(new_size.saturating_sub(layout.size()), Ordering::Relaxed);
+    unsafe { System.realloc(ptr, layout, new_size) }
+  }
+
+  unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+    unsafe { System.dealloc(ptr, layout) }
+  }
+}
+
+#[global_allocator]
+static COUNTING: Counting = Counting;
+
+/* What running something cost */
+pub struct Cost {
+  pub time: Duration,
+  pub allocations: usize,
+  pub bytes: usize
+}
+
+impl fmt::Display for Cost {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{:?}, {} allocations ({} bytes)", self.time, self.allocations, self.bytes)
+  }
+}
+
+/* Runs f, and says how long it took and how much it allocated. Other
+ * threads' allocations are counted too, so this is only exact when
+ * nothing else is running. */
+pub fn measure<T, F: FnOnce() -> T>(f: F) -> (T, Cost) {
+  let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+  let bytes = BYTES.load(Ordering::Relaxed);
+  let start = Instant::now();
+  let value = f();
+  let cost = Cost {
+    time: start.elapsed(),
+    allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+    bytes: BYTES.load(Ordering::Relaxed) - bytes
+  };
+  (value, cost)
+}
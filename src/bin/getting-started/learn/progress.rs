@@ -0,0 +1,110 @@
+use std::collections::BTreeSet;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use super::lessons::LESSONS;
+
+/* How many past quiz scores are kept */
+const SCORES_KEPT: usize = 10;
+
+/* What has been done so far: the lessons run, the quiz questions
+ * answered right at least once, and the latest quiz scores as (right,
+ * asked), oldest first */
+#[derive(Default, Serialize, Deserialize)]
+pub struct Progress {
+  #[serde(default)]
+  lessons: BTreeSet<String>,
+  #[serde(default)]
+  questions: BTreeSet<String>,
+  #[serde(default)]
+  scores: Vec<(usize, usize)>
+}
+
+impl Progress {
+  pub fn done(&mut self, lesson: &str) {
+    self.lessons.insert(lesson.to_string());
+  }
+
+  pub fn answered(&mut self, question: &str) {
+    self.questions.insert(question.to_string());
+  }
+
+  pub fn scored(&mut self, right: usize, asked: usize) {
+    self.scores.push((right, asked));
+    if self.scores.len() > SCORES_KEPT {
+      self.scores.remove(0);
+    }
+  }
+}
+
+/* Where progress is kept: the usual place for an application's data
+ * in the home directory */
+fn path() -> Option<PathBuf> {
+  env::var_os("XDG_DATA_HOME").map(PathBuf::from)
+    .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share")))
+    .map(|dir| dir.join("borrowck").join("progress.json"))
+}
+
+/* The progress so far, or none if there is no file yet or it cannot
+ * be read */
+fn load() -> Progress {
+  path().and_then(|p| fs::read_to_string(p).ok())
+    .and_then(|text| serde_json::from_str(&text).ok())
+    .unwrap_or_default()
+}
+
+/* Changes the saved progress. It is read and written each time, so
+ * nothing is lost if the tutorial is stopped part way through, and
+ * failing to save only gets a warning: the lesson still ran. */
+pub fn update<F: FnOnce(&mut Progress)>(change: F) {
+  let path = match path() {
+    Some(path) => path,
+    None => return
+  };
+  let mut progress = load();
+  change(&mut progress);
+  let saved = path.parent().map_or(Ok(()), fs::create_dir_all)
+    .and_then(|()| fs::write(&path, serde_json::to_string_pretty(&progress).unwrap_or_default()));
+  if let Err(e) = saved {
+    eprintln!("Could not save progress to {}: {}", path.display(), e);
+  }
+}
+
+/* learn progress
+ *
+ * Lists the lessons with the ones already run ticked off, how many of
+ * the quiz questions have been answered right, and the latest quiz
+ * scores. */
+pub fn show(questions: &[&str]) -> Result<(), String> {
+  let progress = load();
+
+  let done = LESSONS.iter().filter(|l| progress.lessons.contains(l.name)).count();
+  println!("Lessons: {} of {} done", done, LESSONS.len());
+  for lesson in LESSONS {
+    let tick = if progress.lessons.contains(lesson.name) { "x" } else { " " };
+    println!("  [{}] {}", tick, lesson.name);
+  }
+
+  let answered = questions.iter().filter(|q| progress.questions.contains(**q)).count();
+  println!("Quiz: {} of {} questions answered right", answered, questions.len());
+  if !progress.scores.is_empty() {
+    let scores = progress.scores.iter().map(|&(right, asked)| format!("{}/{}", right, asked)).collect::<Vec<String>>();
+    println!("Latest scores: {}", scores.join(", "));
+  }
+  Ok(())
+}
+
+/* learn reset
+ *
+ * Forgets everything, for starting the tutorial again */
+pub fn reset() -> Result<(), String> {
+  match path() {
+    Some(path) if path.exists() => fs::remove_file(&path).map_err(|e| format!("Could not remove {}: {}", path.display(), e))?,
+    _ => ()
+  }
+  println!("Progress reset");
+  Ok(())
+}
@@ -0,0 +1,266 @@
+use std::fmt;
+
+use super::interactive::ask;
+use super::progress;
+use super::wrap;
+
+/* What happens to a snippet of code */
+#[derive(Clone, Copy, PartialEq)]
+enum Outcome {
+  Compiles,
+  DoesNotCompile,
+  Panics
+}
+
+impl fmt::Display for Outcome {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str(match *self {
+      Outcome::Compiles => "it compiles and runs",
+      Outcome::DoesNotCompile => "it does not compile",
+      Outcome::Panics => "it compiles, then panics when run"
+    })
+  }
+}
+
+/* One question. The name is how borrowck progress remembers it was
+ * answered, and the topic is the lesson it goes with, so a reader who
+ * gets it wrong knows which lesson to look at again. */
+struct Question {
+  name: &'static str,
+  topic: &'static str,
+  code: &'static str,
+  answer: Outcome,
+  explanation: &'static str
+}
+
+/* The question bank. Each snippet is a whole function body, kept as
+ * short as it can be while still making its point. */
+const QUESTIONS: &[Question] = &[
+  Question {
+    name: "use-after-move",
+    topic: "moving",
+    code: "let v = vec![1, 2, 3];
+let v2 = v;
+
+println!(\"{:?}\", v);",
+    answer: Outcome::DoesNotCompile,
+    explanation: "This is tests/compile_fail/move_not_ok.rs. v was moved into v2, so it \
+                  cannot be used afterwards: \"borrow of moved value: `v`\"."
+  },
+  Question {
+    name: "copy-integers",
+    topic: "moving",
+    code: "let x = 5;
+let y = x;
+
+println!(\"{} {}\", x, y);",
+    answer: Outcome::Compiles,
+    explanation: "Integers are Copy, so `let y = x;` copies the number rather than \
+                  moving it, and x can still be used."
+  },
+  Question {
+    name: "clone-then-push",
+    topic: "copy",
+    code: "let mut v = vec![1, 2, 3];
+let v2 = v.clone();
+
+v.push(4);
+
+println!(\"{:?} {:?}\", v, v2);",
+    answer: Outcome::Compiles,
+    explanation: "v2 is a Vec of its own, so v can still be used and changed. This \
+                  prints [1, 2, 3, 4] [1, 2, 3]."
+  },
+  Question {
+    name: "two-shared-slices",
+    topic: "slices",
+    code: "let s = String::from(\"hello world\");
+
+let hello = &s[0..5];
+let world = &s[0..5];
+
+slice_fn(hello);
+slice_fn(world);",
+    answer: Outcome::Compiles,
+    explanation: "Any number of shared borrows of s can be alive at once, even of \
+                  the same part of it, so this just prints hello twice."
+  },
+  Question {
+    name: "clear-while-sliced",
+    topic: "slices",
+    code: "let mut s = String::from(\"hello world\");
+
+let hello = &s[0..5];
+s.clear();
+
+println!(\"{}\", hello);",
+    answer: Outcome::DoesNotCompile,
+    explanation: "This is much like tests/compile_fail/slices_bad.rs. clear needs \
+                  to borrow s mutably while hello still borrows part of it, and hello \
+                  is used afterwards. If this were allowed, hello would \
+                  point at text which is no longer there."
+  },
+  Question {
+    name: "slice-past-end",
+    topic: "slices",
+    code: "let s = String::from(\"hello\");
+
+let all = &s[0..10];
+
+println!(\"{}\", all);",
+    answer: Outcome::Panics,
+    explanation: "The borrow checker only checks who can use s, not how long it is. \
+                  Slicing past the end is found when it runs: \"byte index 10 is out \
+                  of range\"."
+  },
+  Question {
+    name: "slice-mid-char",
+    topic: "slices",
+    code: "let s = String::from(\"héllo\");
+
+let start = &s[0..2];
+
+println!(\"{}\", start);",
+    answer: Outcome::Panics,
+    explanation: "Slices of a str count bytes, and é takes two of them, so byte 2 \
+                  is in the middle of it. A str must always be whole characters, so \
+                  this panics with \"byte index 2 is not a char boundary\"."
+  },
+  Question {
+    name: "borrow-in-block",
+    topic: "refcells",
+    code: "let x = RefCell::new(3);
+
+{
+  let mut y = x.borrow_mut();
+  *y += 1;
+}
+
+println!(\"{:?}\", x);",
+    answer: Outcome::Compiles,
+    explanation: "y's borrow ends with its block, before x is printed. This prints \
+                  RefCell { value: 4 }."
+  },
+  Question {
+    name: "two-borrow-muts",
+    topic: "refcells",
+    code: "let x = RefCell::new(3);
+
+let mut y = x.borrow_mut();
+let mut z = x.borrow_mut();
+
+*y += 1;
+*z += 1;",
+    answer: Outcome::Panics,
+    explanation: "A RefCell checks borrows when they happen, so this compiles. But \
+                  y is still alive when z asks for a second mutable borrow, and that \
+                  panics with \"already borrowed\"."
+  },
+  Question {
+    name: "mut-borrow-of-immutable",
+    topic: "refcells",
+    code: "let x = 3;
+
+let y = &mut x;
+*y += 1;",
+    answer: Outcome::DoesNotCompile,
+    explanation: "x was not declared mut, so it cannot be borrowed mutably. This is \
+                  the job a RefCell does: change through something which is not mut."
+  }
+];
+
+/* The names of every question, for borrowck progress */
+pub fn names() -> Vec<&'static str> {
+  QUESTIONS.iter().map(|q| q.name).collect()
+}
+
+/* borrowck quiz [topic]
+ *
+ * Asks, for each snippet in the question bank (or only the ones about
+ * one lesson), whether it compiles, fails to compile or panics when it
+ * runs. Each answer is marked and explained straight away. At the end
+ * comes the score, overall and for each topic, so the lessons worth
+ * going back to stand out. q, or the end of stdin, stops early. */
+pub fn run(topic: Option<&str>) -> Result<(), String> {
+  let questions = QUESTIONS.iter().filter(|q| topic.is_none_or(|t| q.topic == t)).collect::<Vec<&Question>>();
+  if questions.is_empty() {
+    let mut topics = QUESTIONS.iter().map(|q| q.topic).collect::<Vec<&str>>();
+    topics.dedup();
+    return Err(format!("There are no questions about {:?}, try one of {}", topic.unwrap_or(""), topics.join(", ")));
+  }
+
+  /* Each topic, in the order they first come up, with how many of its
+   * questions were asked and how many were answered right */
+  let mut scores: Vec<(&str, usize, usize)> = Vec::new();
+  for (n, question) in questions.iter().enumerate() {
+    println!("== Question {} of {} ({}) ==\n", n + 1, questions.len(), question.topic);
+    for line in question.code.lines() {
+      if line.is_empty() {
+        println!();
+      } else {
+        println!("    {}", line);
+      }
+    }
+    println!();
+
+    let guess = loop {
+      match ask("Does it (c)ompile, (n)ot compile or (p)anic?").as_deref() {
+        Some("c") => break Some(Outcome::Compiles),
+        Some("n") => break Some(Outcome::DoesNotCompile),
+        Some("p") => break Some(Outcome::Panics),
+        Some(_) => continue,
+        None => break None
+      }
+    };
+    let guess = match guess {
+      Some(guess) => guess,
+      None => break
+    };
+
+    let right = guess == question.answer;
+    if right {
+      progress::update(|p| p.answered(question.name));
+    }
+    if right {
+      println!("\nRight, {}.", question.answer);
+    } else {
+      println!("\nNot quite: {}.", question.answer);
+    }
+    for line in wrap(question.explanation) {
+      println!("{}", line);
+    }
+    println!();
+
+    let i = match scores.iter().position(|s| s.0 == question.topic) {
+      Some(i) => i,
+      None => {
+        scores.push((question.topic, 0, 0));
+        scores.len() - 1
+      }
+    };
+    scores[i].1 += 1;
+    if right {
+      scores[i].2 += 1;
+    }
+  }
+  report(&scores);
+  Ok(())
+}
+
+/* Prints the score, overall and for each topic, and keeps it for
+ * borrowck progress */
+fn report(scores: &[(&str, usize, usize)]) {
+  let asked = scores.iter().map(|s| s.1).sum::<usize>();
+  if asked == 0 {
+    return;
+  }
+  let right = scores.iter().map(|s| s.2).sum::<usize>();
+  println!("You scored {} out of {}.\n", right, asked);
+  progress::update(|p| p.scored(right, asked));
+
+  let width = scores.iter().map(|s| s.0.len()).max().unwrap_or(0);
+  for &(topic, asked, right) in scores {
+    let hint = if right < asked { format!("  (try borrowck run {} --explain)", topic) } else { String::new() };
+    println!("  {:width$}  {} of {}{}", topic, right, asked, hint, width = width);
+  }
+}
@@ -0,0 +1,128 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/* Everything the lessons use, so a snippet copied out of one compiles
+ * without adding its own use lines */
+const PRELUDE: &str = "use std::cell::{Cell, RefCell};
+use std::rc::{Rc, Weak};
+use std::sync::{Arc, Mutex};
+use std::thread;";
+
+/* A snippet without a main is the body of one. The first line of it
+ * goes on the same line as fn main, and the use lines after it (items
+ * in a block can come anywhere in it), so the line numbers rustc gives
+ * are the snippet's own. */
+fn wrap(snippet: &str) -> String {
+  if snippet.contains("fn main") {
+    return snippet.to_string();
+  }
+  format!("#[allow(unused)] fn main() {{ {}\n{}\n}}\n", snippet.trim_end(), PRELUDE)
+}
+
+/* What came of compiling and running a snippet */
+struct Outcome {
+  compiled: bool,
+  diagnostics: String,
+  /* Only if it compiled: whether it ran to the end, and what it printed */
+  ran: bool,
+  output: String
+}
+
+/* Compiles with the rustc on the PATH, in a directory of our own, and
+ * runs what it makes */
+fn local(name: &str, code: &str) -> Result<Outcome, String> {
+  let dir = env::temp_dir().join(format!("borrowck-try-{}", std::process::id()));
+  fs::create_dir_all(&dir).map_err(|e| format!("Could not create {}: {}", dir.display(), e))?;
+  let result = compile_and_run(&dir, name, code);
+  let _ = fs::remove_dir_all(&dir);
+  result
+}
+
+fn compile_and_run(dir: &Path, name: &str, code: &str) -> Result<Outcome, String> {
+  fs::write(dir.join(name), code).map_err(|e| format!("Could not write {}: {}", name, e))?;
+  let compiled = Command::new("rustc").args(["--edition", "2021", "-o", "snippet", name])
+    .current_dir(dir).output()
+    .map_err(|e| format!("Could not run rustc: {}", e))?;
+  let diagnostics = String::from_utf8_lossy(&compiled.stderr).into_owned();
+  if !compiled.status.success() {
+    return Ok(Outcome { compiled: false, diagnostics, ran: false, output: String::new() });
+  }
+
+  let ran = Command::new(dir.join("snippet")).env_remove("RUST_BACKTRACE").output().map_err(|e| format!("Could not run the snippet: {}", e))?;
+  let mut output = String::from_utf8_lossy(&ran.stdout).into_owned();
+  output.push_str(&String::from_utf8_lossy(&ran.stderr));
+  Ok(Outcome { compiled: true, diagnostics, ran: ran.status.success(), output })
+}
+
+/* Sends the snippet to the Rust playground instead, for when there is
+ * no rustc to hand. The playground compiles and runs it in one go, so
+ * whether it compiled is told from whether rustc's part of what it
+ * says ends in an error. */
+#[cfg(feature = "online")]
+fn online(code: &str) -> Result<Outcome, String> {
+  use serde_json::{json, Value};
+
+  let request = json!({
+    "channel": "stable",
+    "mode": "debug",
+    "edition": "2021",
+    "crateType": "bin",
+    "tests": false,
+    "backtrace": false,
+    "code": code
+  });
+  let mut response = ureq::post("https://play.rust-lang.org/execute")
+    .header("Content-Type", "application/json")
+    .send(request.to_string())
+    .map_err(|e| format!("Could not reach the playground: {}", e))?;
+  let reply = response.body_mut().read_to_string().ok()
+    .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+    .ok_or("The playground's answer made no sense")?;
+
+  let stderr = reply["stderr"].as_str().unwrap_or("").to_string();
+  let success = reply["success"].as_bool().unwrap_or(false);
+  let compiled = success || !stderr.contains("error: could not compile");
+  let (diagnostics, output) = match stderr.find("     Running `") {
+    Some(i) => (stderr[..i].to_string(), reply["stdout"].as_str().unwrap_or("").to_string() + &stderr[i..]),
+    None => (stderr, reply["stdout"].as_str().unwrap_or("").to_string())
+  };
+  Ok(Outcome { compiled, diagnostics, ran: success, output })
+}
+
+#[cfg(not(feature = "online"))]
+fn online(_code: &str) -> Result<Outcome, String> {
+  Err("--online needs borrowck to be built with --features online".to_string())
+}
+
+/* borrowck try snippet.rs [--online]
+ *
+ * Tries out some code: the quiz's questions, a lesson changed to see
+ * what happens, one of tests/compile_fail. The file can be a whole
+ * program or just the lines which would go in main. It is compiled
+ * with rustc, and says whether it compiled, with everything rustc had
+ * to say about it; if it did, it is run too, to see whether it
+ * panics. With --online it goes to the Rust playground instead of
+ * the local rustc. */
+pub fn run(path: &str, use_online: bool) -> Result<bool, String> {
+  let snippet = fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path, e))?;
+  let name = Path::new(path).file_name().map_or("snippet.rs".into(), |n| n.to_string_lossy());
+  let code = wrap(&snippet);
+  let outcome = if use_online { online(&code)? } else { local(&name, &code)? };
+
+  print!("{}", outcome.diagnostics);
+  if !outcome.compiled {
+    println!("It does not compile.");
+    return Ok(false);
+  }
+  if !outcome.output.is_empty() {
+    println!("Running it printed:\n{}", outcome.output.trim());
+  }
+  if outcome.ran {
+    println!("It compiles and runs.");
+  } else {
+    println!("It compiles, but does not run to the end.");
+  }
+  Ok(outcome.ran)
+}
@@ -0,0 +1,45 @@
+use std::env;
+use std::io;
+use std::io::IsTerminal;
+
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::fmt::format::FmtSpan;
+
+use crate::cli::Args;
+
+/* Sets up where log messages go (stderr, so they never end up mixed
+ * into the output) and which ones are shown:
+ *
+ * --quiet  only errors
+ * default  warnings and errors
+ * -v       plus informational messages
+ * -vv      plus debug messages, including how long each phase took
+ *
+ * RUST_LOG takes precedence over all of these when it is set, so
+ * something like RUST_LOG=getting_started=trace still works. */
+pub fn init(args: &Args) {
+  let level = if args.has("--quiet") {
+    "error"
+  } else {
+    match args.verbosity() {
+      0 => "warn",
+      1 => "info",
+      _ => "debug"
+    }
+  };
+
+  let filter = match env::var("RUST_LOG") {
+    Ok(spec) => EnvFilter::new(spec),
+    Err(_) => EnvFilter::new(level)
+  };
+
+  /* FmtSpan::CLOSE logs a line as each phase's span finishes, with
+   * the time spent inside it. Phase spans are debug level, so those
+   * timings only show up with -vv. */
+  tracing_subscriber::fmt()
+    .with_env_filter(filter)
+    .with_span_events(FmtSpan::CLOSE)
+    .with_writer(io::stderr)
+    .with_ansi(io::stderr().is_terminal())
+    .init();
+}
@@ -0,0 +1,5 @@
+/* getting-started stats ... and getting-started learn ..., everything
+ * else is in app.rs */
+const SUBCOMMAND: Option<&str> = None;
+
+include!("app.rs");
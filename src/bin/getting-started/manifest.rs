@@ -0,0 +1,84 @@
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::prelude::*;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::cli::{Args, print_output, read_data};
+use crate::error::{CliError, CliResult};
+
+/* What we knew about a source file when it was imported: enough to
+ * tell later on whether it has been edited or cut short since */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entry {
+  pub source: String,
+  pub sha256: String,
+  pub rows: usize,
+  pub imported: String
+}
+
+/* The SHA-256 of a file's bytes, as hex */
+pub fn sha256(path: &str) -> CliResult<String> {
+  let bytes = fs::read(path).map_err(|error| CliError::Io { path: path.to_string(), error })?;
+  Ok(Sha256::digest(&bytes).iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/* Adds an entry to the end of a manifest, which is a line of JSON per
+ * import. Existing lines are never touched. */
+pub fn append(manifest: &Path, entry: &Entry) -> CliResult<()> {
+  let io_error = |error| CliError::Io { path: manifest.display().to_string(), error };
+  let line = serde_json::to_string(entry).map_err(|e| CliError::Usage(e.to_string()))?;
+  let mut file = OpenOptions::new().create(true).append(true).open(manifest).map_err(io_error)?;
+  writeln!(file, "{}", line).map_err(io_error)
+}
+
+/* What is wrong with a source file now, or None if nothing is */
+fn check(entry: &Entry, args: &Args) -> Option<String> {
+  let hash = match sha256(&entry.source) {
+    Ok(h) => h,
+    Err(e) => return Some(format!("cannot be read: {}", e))
+  };
+  if hash == entry.sha256 {
+    return None;
+  }
+  match read_data(&entry.source, args).map(|input| input.contents.lines().count()) {
+    Ok(rows) if rows < entry.rows => Some(format!("has been truncated, {} rows where there were {}", rows, entry.rows)),
+    Ok(rows) if rows != entry.rows => Some(format!("has been edited, {} rows where there were {}", rows, entry.rows)),
+    _ => Some(format!("has been edited since {}", entry.imported))
+  }
+}
+
+/* batsmen verify <manifest>
+ *
+ * Checks every file listed in a manifest (batsmen.db/manifest.ndjson
+ * is the one db add keeps) against the checksum and row count it had
+ * when it was imported. Each file which has changed is reported, and
+ * if any have, the exit code is the one for a validation failure. */
+pub fn run(args: &Args) -> CliResult<()> {
+  let path = args.positional(0, "a manifest to verify")?;
+  let contents = fs::read_to_string(path).map_err(|error| CliError::Io { path: path.to_string(), error })?;
+
+  let mut output = String::new();
+  let mut failures = 0;
+  for (i, l) in contents.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+    let entry = serde_json::from_str::<Entry>(l).map_err(|e| {
+      CliError::Usage(format!("{} line {} is not a manifest entry: {}", path, i + 1, e))
+    })?;
+    match check(&entry, args) {
+      None => output.push_str(&format!("ok       {}\n", entry.source)),
+      Some(problem) => {
+        failures += 1;
+        output.push_str(&format!("CHANGED  {} {}\n", entry.source, problem));
+      }
+    }
+  }
+  print_output(args, &output)?;
+
+  if failures > 0 {
+    Err(CliError::Validation(failures))
+  } else {
+    Ok(())
+  }
+}
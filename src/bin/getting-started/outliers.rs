@@ -0,0 +1,47 @@
+use getting_started::outliers::{outliers, OutlierMethod};
+use getting_started::output::{Format, Table};
+use getting_started::record::{numbers, Value};
+
+use crate::cli::{Args, parse_batsmen, print_output, read_input};
+use crate::error::{CliError, CliResult};
+
+/* batsmen outliers [file] [--metric average] [--method zscore|iqr]
+ *                  [--threshold x] [--format table|json|markdown]
+ *
+ * Flags records whose metric is unusually far from everyone else's,
+ * which is usually a sign of a typo in the data. */
+pub fn run(args: &Args) -> CliResult<()> {
+  let metric = args.value("--metric").unwrap_or("average");
+  let method = args.parsed::<OutlierMethod>("--method")?.unwrap_or(OutlierMethod::ZScore);
+  let threshold = args.parsed::<f32>("--threshold")?.unwrap_or_else(|| method.default_threshold());
+  let format = args.parsed::<Format>("--format")?.unwrap_or(Format::Table);
+
+  let input = read_input(args.positional.first(), args)?;
+  let batsmen = parse_batsmen(&input)?;
+
+  let values = numbers(&batsmen, metric)
+    .ok_or_else(|| CliError::Usage(format!("{:?} is not a numeric field", metric)))?;
+
+  let found = outliers(&values, method, threshold);
+  let rows = found.iter().map(|o| {
+    let b = &batsmen[o.index];
+    vec![
+      Some(Value::Text(b.initials)),
+      Some(Value::Text(b.surname)),
+      Some(Value::Float(o.value)),
+      Some(Value::Float(o.score)),
+      Some(Value::Float(threshold))
+    ]
+  }).collect();
+  let table = Table::new(vec![
+    "initials".to_string(),
+    "surname".to_string(),
+    metric.to_string(),
+    "score".to_string(),
+    "threshold".to_string()
+  ], rows);
+
+  let positions = found.iter().map(|o| o.index).collect::<Vec<usize>>();
+  print_output(args, &args.sourced_columns(table, &input, &positions)?.render(format, &args.style()?))?;
+  Ok(())
+}
@@ -0,0 +1,107 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use getting_started::batsman::{Batsman, career_span};
+use getting_started::output::Table;
+
+use crate::cli::{Args, Input};
+use crate::error::{CliError, CliResult};
+use crate::plugins;
+
+/* What --partition-by splits results up by: the year each career
+ * started, the year it ended, or the decade it started in */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Partition {
+  Year,
+  Last,
+  Decade
+}
+
+impl FromStr for Partition {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Partition, String> {
+    match s {
+      "year" => Ok(Partition::Year),
+      "last" => Ok(Partition::Last),
+      "decade" => Ok(Partition::Decade),
+      other => Err(format!("Unknown partition {:?}, expected year, last or decade", other))
+    }
+  }
+}
+
+impl Partition {
+  /* The partition a batsman goes in, from the debut and last years on
+   * the line they were parsed from. Lines without them go in one
+   * called unknown, rather than holding everything else up. */
+  fn key(&self, line: Option<&str>) -> String {
+    match line.map(career_span) {
+      Some(Ok(Some((debut, last)))) => match *self {
+        Partition::Year => debut.to_string(),
+        Partition::Last => last.to_string(),
+        Partition::Decade => format!("{}s", debut / 10 * 10)
+      },
+      _ => "unknown".to_string()
+    }
+  }
+}
+
+/* The extension of a file holding a format */
+fn extension(format: &str) -> &str {
+  match format {
+    "table" => "txt",
+    "markdown" => "md",
+    other => other
+  }
+}
+
+/* Writes each partition to dir/<partition>.<extension>, in order of
+ * partition, making dir if it is not there yet */
+fn write(args: &Args, extension: &str, partitions: BTreeMap<String, String>) -> CliResult<()> {
+  let dir = args.value("--output-dir")
+    .ok_or_else(|| CliError::Usage("--partition-by needs an --output-dir to write the partitions to".to_string()))?;
+  let io_error = |path: &Path, error| CliError::Io { path: path.display().to_string(), error };
+  fs::create_dir_all(dir).map_err(|e| io_error(Path::new(dir), e))?;
+  for (key, text) in &partitions {
+    let path = Path::new(dir).join(format!("{}.{}", key, extension));
+    fs::write(&path, text).map_err(|e| io_error(&path, e))?;
+  }
+  info!(files = partitions.len(), dir, "wrote partitions");
+  Ok(())
+}
+
+/* --partition-by without --format: the lines the batsmen were read
+ * from, whole and in the order they were listed, so each partition is
+ * a batting file that any command can read back in. There is nowhere
+ * on a batting line for --with-source to put a file and line (the
+ * years have to be at the end), so the leaderboard writes tables
+ * instead when it is given. */
+pub fn write_lines(args: &Args, partition: Partition, input: &Input, batsmen: &[Batsman], rows: &[usize]) -> CliResult<()> {
+  let lines = input.contents.lines().collect::<Vec<&str>>();
+  let mut partitions = BTreeMap::<String, String>::new();
+  for (b, &row) in batsmen.iter().zip(rows) {
+    let line = lines.get(row).copied();
+    let text = partitions.entry(partition.key(line)).or_default();
+    text.push_str(&line.map(|l| input.names.line(l)).unwrap_or_else(|| input.names.batsman(b).to_string()));
+    text.push('\n');
+  }
+  write(args, "csv", partitions)
+}
+
+/* --partition-by with a --format: the table split up by row, each part
+ * with its own --fields, --totals and so on. Row r of the table is the
+ * one parsed at position rows[r] of the input. */
+pub fn write_tables<'a>(args: &Args, partition: Partition, format: &str, input: &'a Input, table: Table<'a>, rows: &[usize]) -> CliResult<()> {
+  let lines = input.contents.lines().collect::<Vec<&str>>();
+  let mut parts = BTreeMap::<String, Vec<usize>>::new();
+  for (r, &row) in rows.iter().enumerate() {
+    parts.entry(partition.key(lines.get(row).copied())).or_default().push(r);
+  }
+  let partitions = parts.into_iter().map(|(key, part)| {
+    let positions = part.iter().map(|&r| rows[r]).collect::<Vec<usize>>();
+    Ok((key, plugins::render(args, format, &args.sourced_columns(table.select(&part), input, &positions)?)?))
+  }).collect::<CliResult<BTreeMap<String, String>>>()?;
+  write(args, extension(format), partitions)
+}
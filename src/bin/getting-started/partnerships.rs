@@ -0,0 +1,78 @@
+use getting_started::output::{Format, Table};
+use getting_started::partnership::{Partnership, average, best_by_wicket, by_pair, partnerships};
+use getting_started::record::Value;
+use getting_started::scorecard::ScorecardEntry;
+
+use crate::cli::{Args, print_output, read_input};
+use crate::error::{CliError, CliResult, parse_error};
+
+/* Every partnership in a match file, which holds one or more innings
+ * in scorecard format separated by blank lines */
+fn match_partnerships(contents: &str) -> CliResult<Vec<Partnership<'_>>> {
+  let mut all = Vec::new();
+  let mut innings = Vec::new();
+  let mut start = 1;
+  /* The extra blank line at the end finishes off the last innings */
+  for (i, l) in contents.lines().chain(Some("")).enumerate() {
+    if !l.trim().is_empty() {
+      innings.push(ScorecardEntry::parse(l).map_err(|error| parse_error(i, l, error))?);
+      continue;
+    }
+    if !innings.is_empty() {
+      all.extend(partnerships(&innings).map_err(|error| CliError::Parse { file: None, line: start, column: None, error })?);
+      innings.clear();
+    }
+    start = i + 2;
+  }
+  Ok(all)
+}
+
+/* batsmen partnerships [match-file] [--format table|json|markdown]
+ *
+ * The highest partnership for each wicket, how every pair has done
+ * together and the average partnership, from scorecards which give the
+ * score at each fall of wicket like "AN Cook, 21, caught, MA Starc, 35".
+ * A file can hold several innings, with a blank line between each. */
+pub fn run(args: &Args) -> CliResult<()> {
+  let format = args.parsed::<Format>("--format")?.unwrap_or(Format::Table);
+  let input = read_input(args.positional.first(), args)?;
+  let all = match_partnerships(&input.contents).map_err(|e| input.locate(e))?.into_iter().map(|p| {
+    let [(i1, s1), (i2, s2)] = p.batsmen;
+    Partnership { batsmen: [input.names.name(i1, s1), input.names.name(i2, s2)], ..p }
+  }).collect::<Vec<Partnership>>();
+
+  let best = best_by_wicket(&all);
+  let best_pairs = best.iter().map(|p| p.pair()).collect::<Vec<String>>();
+  let wickets = Table::new(
+    vec!["wicket".to_string(), "runs".to_string(), "pair".to_string()],
+    best.iter().zip(best_pairs.iter()).map(|(p, pair)| {
+      vec![Some(Value::Integer(p.wicket)), Some(Value::Integer(p.runs)), Some(Value::Text(pair))]
+    }).collect());
+
+  let pairs = by_pair(&all);
+  let pairs_table = Table::new(
+    ["pair", "partnerships", "runs", "highest", "average"].iter().map(|c| c.to_string()).collect(),
+    pairs.iter().map(|p| vec![
+      Some(Value::Text(&p.pair)),
+      Some(Value::Integer(p.partnerships)),
+      Some(Value::Integer(p.runs)),
+      Some(Value::Integer(p.highest)),
+      Some(Value::Float(p.average))
+    ]).collect());
+
+  let mean = average(&all);
+  let style = args.style()?;
+  print_output(args, &match format {
+    Format::Json => format!("{{\"by_wicket\": {}, \"by_pair\": {}, \"average\": {}}}\n",
+                            wickets.render(format, &style).trim_end(),
+                            pairs_table.render(format, &style).trim_end(),
+                            mean.map(|m| m.to_string()).unwrap_or_else(|| "null".to_string())),
+    _ => format!("Highest partnership for each wicket\n\n{}\nPartnerships by pair\n\n{}{}",
+                 wickets.render(format, &style),
+                 pairs_table.render(format, &style),
+                 match mean {
+                   Some(m) => format!("\nAverage partnership: {:.2}\n", m),
+                   None => "\nNo completed partnerships\n".to_string()
+                 })
+  })
+}
@@ -0,0 +1,109 @@
+use std::env;
+use std::fs;
+use std::io::prelude::*;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use getting_started::output::{Format, Formatter, Registry, Style, Table};
+
+use crate::cli::Args;
+use crate::error::{CliError, CliResult};
+
+/* Programs in the plugins directory named like this add a format */
+const PREFIX: &str = "batsmen-format-";
+
+/* An output format provided by a separate program, so that formats
+ * only one club needs (an old scoreboard's file layout, say) can be
+ * written in any language and kept out of this crate. The protocol is
+ * as simple as we could make it: the program gets the table as JSON on
+ * stdin, the same as --format json prints, and whatever it writes to
+ * stdout is the output. Exiting with anything but 0 is an error, and
+ * whatever it wrote to stderr is the message. */
+pub struct Plugin {
+  name: String,
+  path: PathBuf
+}
+
+impl Formatter for Plugin {
+  fn name(&self) -> &str {
+    &self.name
+  }
+
+  fn render(&self, table: &Table, _: &Style) -> Result<String, String> {
+    let failed = |e: &dyn ToString| format!("{}: {}", self.path.display(), e.to_string());
+
+    let mut child = Command::new(&self.path)
+      .stdin(Stdio::piped())
+      .stdout(Stdio::piped())
+      .stderr(Stdio::piped())
+      .spawn()
+      .map_err(|e| failed(&e))?;
+    /* stdin is dropped at the end of this block, which closes it so
+     * the plugin knows the table has finished */
+    {
+      let json = table.render(Format::Json, &Style::plain());
+      let mut stdin = child.stdin.take().ok_or_else(|| failed(&"no stdin"))?;
+      stdin.write_all(json.as_bytes()).map_err(|e| failed(&e))?;
+    }
+
+    let output = child.wait_with_output().map_err(|e| failed(&e))?;
+    if !output.status.success() {
+      return Err(failed(&format!("{}: {}", output.status, String::from_utf8_lossy(&output.stderr).trim())));
+    }
+    String::from_utf8(output.stdout).map_err(|e| failed(&e))
+  }
+}
+
+/* Every plugin in a directory, in name order */
+fn discover(dir: &Path) -> CliResult<Vec<Plugin>> {
+  let entries = fs::read_dir(dir).map_err(|error| {
+    CliError::Io { path: dir.display().to_string(), error }
+  })?;
+
+  let mut plugins = entries.filter_map(|e| e.ok())
+    .filter(|e| e.path().is_file())
+    .filter_map(|e| {
+      let file = e.file_name().to_string_lossy().into_owned();
+      /* Windows needs the .exe to run it, but it is not part of the name */
+      let name = file.trim_end_matches(".exe").strip_prefix(PREFIX)?.to_string();
+      Some(Plugin { name, path: e.path() })
+    })
+    .collect::<Vec<Plugin>>();
+  plugins.sort_by(|lhs, rhs| lhs.name.cmp(&rhs.name));
+  Ok(plugins)
+}
+
+/* The built in formats plus any plugins, which come from --plugins
+ * or else the BATSMEN_PLUGINS environment variable */
+pub fn registry(args: &Args) -> CliResult<Registry> {
+  let mut registry = Registry::new();
+  let dir = match args.value("--plugins") {
+    Some(d) => Some(PathBuf::from(d)),
+    None => env::var_os("BATSMEN_PLUGINS").map(PathBuf::from)
+  };
+  if let Some(dir) = dir {
+    for plugin in discover(&dir)? {
+      let path = plugin.path.display().to_string();
+      if !registry.register(Box::new(plugin)) {
+        warn!("{} has the same name as another format, skipping it", path);
+      }
+    }
+  }
+  Ok(registry)
+}
+
+/* Renders a table with the formatter --format names. The plugins
+ * directory is only looked at for a format that is not built in. */
+pub fn render(args: &Args, name: &str, table: &Table) -> CliResult<String> {
+  let style = args.style()?;
+  let builtin = name.parse::<Format>();
+  if let Ok(format) = builtin {
+    return Ok(table.render(format, &style));
+  }
+
+  let registry = registry(args)?;
+  match registry.get(name) {
+    Some(f) => f.render(table, &style).map_err(CliError::Usage),
+    None => Err(CliError::Usage(format!("Unknown format {:?}, expected one of {}", name, registry.names().join(", "))))
+  }
+}
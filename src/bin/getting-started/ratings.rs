@@ -0,0 +1,49 @@
+use getting_started::anonymize::Pseudonyms;
+use getting_started::innings::Innings;
+use getting_started::output::{Format, Table};
+use getting_started::ratings::{Rating, ratings};
+
+use crate::cli::{Args, Input, print_output, read_input};
+use crate::error::{CliError, CliResult};
+
+/* Whether a date is written year-month-day, like the innings files */
+fn is_date(s: &str) -> bool {
+  let parts = s.split('-').collect::<Vec<&str>>();
+  parts.len() == 3 &&
+    parts.iter().zip(&[4, 2, 2]).all(|(p, &n)| p.len() == n && p.chars().all(|c| c.is_ascii_digit()))
+}
+
+/* batsmen ratings [file...] [--as-of 2016-01-01] [--format table|json|markdown]
+ *
+ * Elo ratings for every batsman in one or more per-innings files,
+ * updated one match at a time in date order, with the highest rated
+ * first. --as-of only counts matches up to and including that date,
+ * to see how the ratings stood at the time. Without any files the
+ * innings are read from stdin. */
+pub fn run(args: &Args) -> CliResult<()> {
+  let as_of = args.value("--as-of");
+  if let Some(date) = as_of.filter(|d| !is_date(d)) {
+    return Err(CliError::Usage(format!("Expected --as-of to be a date like 2016-01-01, got {:?}", date)));
+  }
+  let format = args.parsed::<Format>("--format")?.unwrap_or(Format::Table);
+
+  let inputs = if args.positional.is_empty() {
+    vec![read_input(None, args)?]
+  } else {
+    args.positional.iter().map(|p| read_input(Some(p), args)).collect::<CliResult<Vec<Input>>>()?
+  };
+  let mut innings = Vec::new();
+  let mut names = Pseudonyms::default();
+  for input in &inputs {
+    innings.extend(input.parse(Innings::parse)?);
+    names.extend(&input.names);
+  }
+
+  /* Ratings are only shown to a decimal place, there is nothing to
+   * be read into the digits after that */
+  let rated = ratings(&innings, as_of).into_iter().map(|r| {
+    Rating { rating: (r.rating * 10.0).round() / 10.0, ..r }
+  }).collect::<Vec<Rating>>();
+  print_output(args, &args.columns(Table::from_records(&rated), &names)?.render(format, &args.style()?))?;
+  Ok(())
+}
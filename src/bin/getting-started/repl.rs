@@ -0,0 +1,258 @@
+use std::env;
+use std::io::{self, IsTerminal};
+use std::path::PathBuf;
+
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+
+use getting_started::anonymize::Pseudonyms;
+use getting_started::batsman::Batsman;
+use getting_started::expr::Expr;
+use getting_started::output::{Format, Style, Table};
+use getting_started::record::numbers;
+use getting_started::sort::SortSpec;
+use getting_started::stats::{mean, median, std_dev};
+
+use crate::cli::{Args, parse_batsmen, read_data};
+use crate::error::{CliError, CliResult};
+
+const COMMANDS: &[&str] = &["count", "filter", "find", "help", "quit", "reset", "show", "sort", "stats"];
+
+const FIELDS: &[&str] = &["initials", "surname", "runs", "average"];
+
+const HELP: &str = "\
+filter <expr> <op> <expr>  keep batsmen where it holds, like: filter runs / 1000 >= 8
+                           (op is one of < <= = != >= >)
+find <name>                keep batsmen matching a name, like: find AN Cook
+sort <spec>                sort by fields, like: sort average:desc,surname
+show [n]                   print the first n batsmen (10 unless given)
+stats [field]              count, mean, median and spread of a field (average unless given)
+count                      how many batsmen are left
+reset                      undo every filter and sort
+quit                       leave (so does Ctrl-D)";
+
+/* How many batsmen show prints unless it is told */
+const SHOW: usize = 10;
+
+/* batsmen repl <file>
+ *
+ * Reads the file once and then takes commands from a prompt, each one
+ * narrowing down, sorting or summing up the batsmen left over from
+ * the last, so a file can be explored without reading it again for
+ * every question. Type help to see the commands. Lines can be edited,
+ * earlier ones are there with the up arrow (and kept between sessions
+ * in ~/.batsmen_history), and tab completes commands and field names.
+ *
+ * The commands come in on stdin, so unlike most commands this one
+ * needs a file. --fields and --derive apply to what show prints. */
+pub fn run(args: &Args) -> CliResult<()> {
+  let input = read_data(args.positional(0, "a file to explore")?, args)?;
+  let batsmen = parse_batsmen(&input)?;
+  let mut session = Session {
+    batsmen: &batsmen,
+    view: (0..batsmen.len()).collect(),
+    args,
+    names: &input.names,
+    style: args.style()?
+  };
+
+  let readline_error = |e: ReadlineError| CliError::Usage(format!("Could not read from the terminal: {}", e));
+  let mut editor = Editor::<Words, DefaultHistory>::new().map_err(readline_error)?;
+  editor.set_helper(Some(Words));
+
+  /* History is only kept for people typing, not for commands piped in */
+  let history = if io::stdin().is_terminal() { history_path() } else { None };
+  if let Some(ref path) = history {
+    let _ = editor.load_history(path);
+  }
+
+  loop {
+    let line = match editor.readline("batsmen> ") {
+      Ok(line) => line,
+      Err(ReadlineError::Interrupted) => continue,
+      Err(ReadlineError::Eof) => break,
+      Err(e) => return Err(readline_error(e))
+    };
+    let line = line.trim();
+    if line.is_empty() {
+      continue;
+    }
+    let _ = editor.add_history_entry(line);
+    if line == "quit" || line == "exit" {
+      break;
+    }
+
+    match session.command(line) {
+      Ok(output) => print!("{}", output),
+      Err(message) => eprintln!("{}", message)
+    }
+  }
+
+  if let Some(ref path) = history {
+    if let Err(e) = editor.save_history(path) {
+      warn!(error = %e, "could not save the history");
+    }
+  }
+  Ok(())
+}
+
+fn history_path() -> Option<PathBuf> {
+  env::var_os("HOME").map(|home| PathBuf::from(home).join(".batsmen_history"))
+}
+
+/* The batsmen, and the positions of the ones still in play in the
+ * order they are in, like the tui's view */
+struct Session<'s, 'a: 's> {
+  batsmen: &'s [Batsman<'a>],
+  view: Vec<usize>,
+  args: &'s Args,
+  /* What show prints each player as, with --anonymize */
+  names: &'s Pseudonyms,
+  style: Style
+}
+
+impl<'s, 'a> Session<'s, 'a> {
+  /* Runs one line, giving back what to print or what went wrong */
+  fn command(&mut self, line: &str) -> Result<String, String> {
+    let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let rest = rest.trim();
+
+    match command {
+      "help" => Ok(format!("{}\n", HELP)),
+      "count" => Ok(format!("{}\n", self.view.len())),
+      "reset" => {
+        self.view = (0..self.batsmen.len()).collect();
+        Ok(format!("{}\n", self.view.len()))
+      },
+      "filter" => {
+        let (lhs, op, rhs) = comparison(rest)?;
+        let mut kept = Vec::new();
+        for &i in &self.view {
+          let b = &self.batsmen[i];
+          if op.holds(lhs.eval(b)?, rhs.eval(b)?) {
+            kept.push(i);
+          }
+        }
+        self.view = kept;
+        Ok(format!("{}\n", self.view.len()))
+      },
+      "find" => {
+        let batsmen = self.batsmen;
+        self.view.retain(|&i| batsmen[i].matches_name(rest));
+        Ok(format!("{}\n", self.view.len()))
+      },
+      "sort" => {
+        let spec = rest.parse::<SortSpec>()?;
+        let batsmen = self.batsmen;
+        self.view.sort_by(|&i, &j| spec.compare(&batsmen[i], &batsmen[j]));
+        Ok(String::new())
+      },
+      "show" => {
+        let n = if rest.is_empty() {
+          SHOW
+        } else {
+          rest.parse::<usize>().map_err(|_| format!("Expected a number of batsmen to show, got {:?}", rest))?
+        };
+        let shown = self.records(self.view.iter().take(n));
+        let table = self.args.columns(Table::from_records(&shown), self.names).map_err(|e| e.to_string())?;
+        Ok(table.render(Format::Table, &self.style))
+      },
+      "stats" => {
+        let field = if rest.is_empty() { "average" } else { rest };
+        let values = numbers(&self.records(self.view.iter()), field)
+          .ok_or_else(|| format!("{:?} is not a numeric field", field))?;
+        let figure = |x: Option<f32>| x.map(|x| format!("{:.2}", x)).unwrap_or_else(|| "-".to_string());
+        Ok(format!("count   {}\nmean    {}\nmedian  {}\nstd dev {}\nmin     {}\nmax     {}\n",
+                   values.len(),
+                   figure(mean(&values)),
+                   figure(median(&values)),
+                   figure(std_dev(&values)),
+                   figure(values.iter().cloned().reduce(f32::min)),
+                   figure(values.iter().cloned().reduce(f32::max))))
+      },
+      _ => Err(format!("Unknown command {:?}, type help for the list", command))
+    }
+  }
+
+  fn records<'i, I: Iterator<Item = &'i usize>>(&self, positions: I) -> Vec<Batsman<'a>> {
+    positions.map(|&i| self.batsmen[i].clone()).collect()
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparison {
+  Less,
+  LessOrEqual,
+  Equal,
+  NotEqual,
+  GreaterOrEqual,
+  Greater
+}
+
+/* Longer operators come first, so that ">=" is not read as ">" */
+const COMPARISONS: &[(&str, Comparison)] = &[
+  ("<=", Comparison::LessOrEqual),
+  (">=", Comparison::GreaterOrEqual),
+  ("!=", Comparison::NotEqual),
+  ("<", Comparison::Less),
+  (">", Comparison::Greater),
+  ("=", Comparison::Equal)
+];
+
+impl Comparison {
+  pub fn holds(&self, lhs: f32, rhs: f32) -> bool {
+    match *self {
+      Comparison::Less => lhs < rhs,
+      Comparison::LessOrEqual => lhs <= rhs,
+      Comparison::Equal => lhs == rhs,
+      Comparison::NotEqual => lhs != rhs,
+      Comparison::GreaterOrEqual => lhs >= rhs,
+      Comparison::Greater => lhs > rhs
+    }
+  }
+}
+
+/* Splits "runs / 1000 >= 8" into its two expressions and the operator
+ * between them. batch.rs reads its filters with this too. */
+pub fn comparison(s: &str) -> Result<(Expr, Comparison, Expr), String> {
+  for &(symbol, op) in COMPARISONS {
+    if let Some((lhs, rhs)) = s.split_once(symbol) {
+      return Ok((lhs.parse::<Expr>()?, op, rhs.parse::<Expr>()?));
+    }
+  }
+  Err(format!("Expected a comparison like \"runs > 8000\", got {:?}", s))
+}
+
+/* Tab completion: the first word is a command, anything after that a
+ * field name (sort specs are separated by commas, so those count as
+ * the start of a word too) */
+struct Words;
+
+impl Completer for Words {
+  type Candidate = String;
+
+  fn complete(&self, line: &str, pos: usize, _: &Context<'_>) -> rustyline::Result<(usize, Vec<String>)> {
+    let start = line[..pos].rfind(|c: char| c.is_whitespace() || c == ',').map_or(0, |i| i + 1);
+    let word = &line[start..pos];
+    let words = if line[..start].trim().is_empty() { COMMANDS } else { FIELDS };
+    Ok((start, words.iter().filter(|w| w.starts_with(word)).map(|w| w.to_string()).collect()))
+  }
+}
+
+impl Hinter for Words {
+  type Hint = String;
+}
+
+impl Highlighter for Words {
+}
+
+impl Validator for Words {
+}
+
+impl Helper for Words {
+}
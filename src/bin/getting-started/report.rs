@@ -0,0 +1,64 @@
+use std::collections::BTreeMap;
+use std::fs;
+
+use getting_started::batsman::Batsman;
+use getting_started::sort::{Field, SortSpec, sorted};
+
+use crate::cli::{Args, parse_batsmen, print_output, read_file, read_input};
+use crate::error::{CliError, CliResult};
+use crate::i18n;
+use crate::template::{context, render};
+
+/* The page we produce unless --template says otherwise. It has no
+ * outside stylesheets or scripts, so the file can be emailed as is. */
+const DEFAULT_TEMPLATE: &str = include_str!("report.html");
+
+/* The words on the page, which templates get in the chosen language as
+ * labels.summary, labels.players and so on */
+const LABELS: &[(&str, &str)] = &[
+  ("summary", "Summary"),
+  ("players", "Players"),
+  ("total_runs", "Total runs"),
+  ("top_scorer", "Top scorer"),
+  ("mean_average", "Mean average"),
+  ("median_average", "Median average"),
+  ("std_dev_average", "Std. dev. of averages"),
+  ("click_to_sort", "Click a column heading to sort by it, and again to reverse."),
+  ("initials", "Initials"),
+  ("surname", "Surname"),
+  ("runs", "Runs"),
+  ("average", "Average")
+];
+
+/* batsmen report [file] [--html out.html] [--title t] [--template t.html]
+ *
+ * A self-contained HTML page with summary figures and a table of
+ * every batsman that can be sorted by clicking its headings. The
+ * layout comes from a Tera template, which --template replaces with
+ * one of your own; see report.html for the variables it can use.
+ * The headings follow --lang, and lang holds its code for the page.
+ * Without --html the page is written to stdout. */
+pub fn run(args: &Args) -> CliResult<()> {
+  let input = read_input(args.positional.first(), args)?;
+  let batsmen = sorted(parse_batsmen(&input)?, SortSpec::by(Field::Runs).desc().comparator());
+
+  let template = match args.value("--template") {
+    Some(path) => read_file(path, false, None)?,
+    None => DEFAULT_TEMPLATE.to_string()
+  };
+
+  let shown = batsmen.iter().map(|b| input.names.batsman(b)).collect::<Vec<Batsman>>();
+  let mut context = context(&shown);
+  let labels = LABELS.iter().map(|&(key, english)| (key, i18n::tr(english))).collect::<BTreeMap<&str, &str>>();
+  context.insert("title", args.value("--title").unwrap_or(i18n::tr("Batting report")));
+  context.insert("labels", &labels);
+  context.insert("lang", i18n::lang().code());
+  let page = render(&template, &context, true)?;
+
+  match args.value("--html") {
+    Some(path) => fs::write(path, page).map_err(|error| {
+      CliError::Io { path: path.to_string(), error }
+    }),
+    None => print_output(args, &page)
+  }
+}
@@ -0,0 +1,36 @@
+use getting_started::output::{Format, Table};
+use getting_started::record::Value;
+use getting_started::schema::{infer, json_schema};
+
+use crate::cli::{Args, print_output, read_input};
+use crate::error::CliResult;
+
+/* batsmen schema [file] [--sample-rows 100] [--json-schema]
+ *                [--format table|json|markdown]
+ *
+ * Guesses the name, type and nullability of each column from the first
+ * few rows, which helps when working out how an unfamiliar export lines
+ * up with the columns we expect. */
+pub fn run(args: &Args) -> CliResult<()> {
+  let sample = args.parsed::<usize>("--sample-rows")?.unwrap_or(100);
+  let format = args.parsed::<Format>("--format")?.unwrap_or(Format::Table);
+
+  let input = read_input(args.positional.first(), args)?;
+  let columns = infer(input.contents.lines(), sample);
+
+  if args.has("--json-schema") {
+    return print_output(args, &json_schema(&columns));
+  }
+
+  let kinds = columns.iter().map(|c| c.kind.to_string()).collect::<Vec<String>>();
+  let rows = columns.iter().zip(kinds.iter()).map(|(c, kind)| {
+    vec![
+      Some(Value::Text(&c.name)),
+      Some(Value::Text(kind)),
+      Some(Value::Text(if c.nullable { "yes" } else { "no" }))
+    ]
+  }).collect();
+  let table = Table::new(vec!["column".to_string(), "type".to_string(), "nullable".to_string()], rows);
+
+  print_output(args, &table.render(format, &args.style()?))
+}
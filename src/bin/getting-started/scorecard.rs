@@ -0,0 +1,31 @@
+use getting_started::batsman::Batsman;
+use getting_started::output::{Format, Table};
+use getting_started::record::Value;
+use getting_started::scorecard::{ScorecardEntry, totals};
+use getting_started::sort::{Field, SortSpec, sorted};
+
+use crate::cli::{Args, print_output, read_input};
+use crate::error::CliResult;
+
+/* batsmen scorecard [file] [--sort-by runs:desc] [--format table|json|markdown]
+ *
+ * Reads match scorecards, one innings per line like
+ * "AN Cook, 21, caught, MA Starc", and prints each player's totals
+ * over all of them: the same runs and average as a batting file, plus
+ * how many innings they had and how many of those they were not out. */
+pub fn run(args: &Args) -> CliResult<()> {
+  let spec = args.parsed::<SortSpec>("--sort-by")?.unwrap_or_else(|| SortSpec::by(Field::Runs).desc());
+  let format = args.parsed::<Format>("--format")?.unwrap_or(Format::Table);
+
+  let input = read_input(args.positional.first(), args)?;
+  let entries = input.parse(ScorecardEntry::parse)?;
+  let players = sorted(totals(&entries), |lhs, rhs| spec.compare(&lhs.batsman, &rhs.batsman));
+
+  let batsmen = players.iter().map(|p| p.batsman.clone()).collect::<Vec<Batsman>>();
+  let mut table = Table::from_records(&batsmen);
+  let end = table.columns.len();
+  table.insert_column(end, "innings", players.iter().map(|p| Some(Value::Integer(p.innings))).collect());
+  table.insert_column(end + 1, "not_outs", players.iter().map(|p| Some(Value::Integer(p.not_outs))).collect());
+  print_output(args, &args.columns(table, &input.names)?.render(format, &args.style()?))?;
+  Ok(())
+}
@@ -0,0 +1,171 @@
+/* --script: custom fields, filters and changes to each batsman, written
+ * in rhai (https://rhai.rs) so they can be added without recompiling.
+ * A script defines any of these functions, each of which is given the
+ * batsman as a map of initials, surname, runs and average:
+ *
+ *   fn filter(p) { p.runs > 5000 }              keep only some batsmen
+ *   fn transform(p) { p.average = 0.0; p }     change runs or average
+ *   fn fields(p) { #{ per_innings: p.runs / 100 } }   extra columns
+ *
+ * Functions which are not there are skipped. Scripting needs batsmen
+ * to be built with --features scripting, and without it --script is a
+ * usage error. */
+use getting_started::batsman::Batsman;
+
+use crate::error::CliResult;
+
+/* A value from a script's fields function. Numbers stay numbers so
+ * that they line up and sort like the built in columns. */
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(not(feature = "scripting"), allow(dead_code))]
+pub enum Derived {
+  Number(f32),
+  Text(String)
+}
+
+#[cfg(feature = "scripting")]
+pub use self::enabled::Script;
+#[cfg(not(feature = "scripting"))]
+pub use self::disabled::Script;
+
+#[cfg(feature = "scripting")]
+mod enabled {
+  use std::path::PathBuf;
+
+  use rhai::{Dynamic, Engine, EvalAltResult, Map, Scope, AST};
+
+  use getting_started::batsman::Batsman;
+  use getting_started::numeric::{average_f32, average_from_f32};
+
+  use crate::error::{CliError, CliResult};
+  use super::Derived;
+
+  pub struct Script {
+    path: String,
+    engine: Engine,
+    ast: AST
+  }
+
+  fn to_map(b: &Batsman) -> Map {
+    let mut map = Map::new();
+    map.insert("initials".into(), b.initials.into());
+    map.insert("surname".into(), b.surname.into());
+    map.insert("runs".into(), (b.runs as i64).into());
+    map.insert("average".into(), (average_f32(b.average) as f64).into());
+    map
+  }
+
+  fn number(d: &Dynamic) -> Option<f64> {
+    d.as_float().ok().or_else(|| d.as_int().ok().map(|i| i as f64))
+  }
+
+  impl Script {
+    pub fn load(path: &str) -> CliResult<Script> {
+      let engine = Engine::new();
+      let ast = engine.compile_file(PathBuf::from(path)).map_err(|e| {
+        CliError::Usage(format!("{}: {}", path, e))
+      })?;
+      Ok(Script { path: path.to_string(), engine, ast })
+    }
+
+    fn has(&self, name: &str) -> bool {
+      self.ast.iter_functions().any(|f| f.name == name && f.params.len() == 1)
+    }
+
+    fn call(&self, name: &str, b: &Batsman) -> CliResult<Dynamic> {
+      self.engine.call_fn::<Dynamic>(&mut Scope::new(), &self.ast, name, (to_map(b),))
+        .map_err(|e: Box<EvalAltResult>| CliError::Usage(format!("{}: {} failed for {} {}: {}",
+                                                                  self.path, name, b.initials, b.surname, e)))
+    }
+
+    fn error(&self, name: &str, message: &str) -> CliError {
+      CliError::Usage(format!("{}: {} {}", self.path, name, message))
+    }
+
+    pub fn filter(&self, b: &Batsman) -> CliResult<bool> {
+      if !self.has("filter") {
+        return Ok(true);
+      }
+      self.call("filter", b)?.as_bool().map_err(|_| self.error("filter", "has to return true or false"))
+    }
+
+    /* Only the numbers can be changed, since the names are borrowed
+     * from the input text */
+    pub fn transform<'a>(&self, b: Batsman<'a>) -> CliResult<Batsman<'a>> {
+      if !self.has("transform") {
+        return Ok(b);
+      }
+      let map = self.call("transform", &b)?.try_cast::<Map>()
+        .ok_or_else(|| self.error("transform", "has to return the batsman it was given"))?;
+      let field = |name: &str| map.get(name).and_then(number);
+      let runs = field("runs").filter(|&r| r >= 0.0)
+        .ok_or_else(|| self.error("transform", "has to leave runs a whole number of at least zero"))?;
+      let average = field("average").filter(|&a| a.is_finite() && a >= 0.0)
+        .ok_or_else(|| self.error("transform", "has to leave average a number of at least zero"))?;
+      /* An average the script left alone stays exactly as it was,
+       * which matters when averages are Decimals */
+      let average = if average == average_f32(b.average) as f64 {
+        b.average
+      } else {
+        average_from_f32(average as f32).unwrap_or_default()
+      };
+      Ok(Batsman { runs: runs as u32, average, ..b })
+    }
+
+    pub fn fields(&self, b: &Batsman) -> CliResult<Vec<(String, Derived)>> {
+      if !self.has("fields") {
+        return Ok(Vec::new());
+      }
+      let map = self.call("fields", b)?.try_cast::<Map>()
+        .ok_or_else(|| self.error("fields", "has to return a map like #{ name: value }"))?;
+      Ok(map.into_iter().map(|(name, value)| {
+        (name.to_string(), match number(&value) {
+          Some(n) => Derived::Number(n as f32),
+          None => Derived::Text(value.to_string())
+        })
+      }).collect())
+    }
+  }
+}
+
+#[cfg(not(feature = "scripting"))]
+mod disabled {
+  use getting_started::batsman::Batsman;
+
+  use crate::error::{CliError, CliResult};
+  use super::Derived;
+
+  /* Never made, since load always fails, so the methods are never
+   * called. They are only here so callers do not need #[cfg]s. */
+  pub enum Script {}
+
+  impl Script {
+    pub fn load(path: &str) -> CliResult<Script> {
+      Err(CliError::Usage(format!("Running {} needs batsmen to be built with --features scripting", path)))
+    }
+
+    pub fn filter(&self, _: &Batsman) -> CliResult<bool> {
+      match *self {}
+    }
+
+    pub fn transform<'a>(&self, _: Batsman<'a>) -> CliResult<Batsman<'a>> {
+      match *self {}
+    }
+
+    pub fn fields(&self, _: &Batsman) -> CliResult<Vec<(String, Derived)>> {
+      match *self {}
+    }
+  }
+}
+
+/* Runs a script's filter and transform over every batsman, each
+ * along with the position they were parsed at, which they keep */
+pub fn apply<'a>(script: &Script, batsmen: Vec<(usize, Batsman<'a>)>) -> CliResult<Vec<(usize, Batsman<'a>)>> {
+  let mut kept = Vec::with_capacity(batsmen.len());
+  for (row, b) in batsmen {
+    if script.filter(&b)? {
+      kept.push((row, script.transform(b)?));
+    }
+  }
+  Ok(kept)
+}
@@ -0,0 +1,85 @@
+use getting_started::batsman::Batsman;
+use getting_started::invariants::{Broken, check_finite, check_ranks, check_sorted};
+use getting_started::leaderboard::Leaderboard;
+use getting_started::numeric::average_f32;
+use getting_started::rank::{RankMethod, rank};
+use getting_started::sort::{Field, SortSpec, sort_in_place, sorted, sorted_iter, sorted_unstable};
+#[cfg(feature = "parallel")]
+use getting_started::sort::par_sorted;
+
+use crate::cli::{Args, parse_batsmen, print_output, read_input};
+use crate::error::{CliError, CliResult};
+
+/* par_sorted, when there is one */
+#[cfg(feature = "parallel")]
+fn parallel(batsmen: &[Batsman], spec: &SortSpec) -> Option<(&'static str, Result<(), Broken>)> {
+  let cmp = spec.comparator();
+  Some(("par_sorted", check_sorted(&par_sorted(batsmen.to_vec(), &cmp), &cmp)))
+}
+
+#[cfg(not(feature = "parallel"))]
+fn parallel(_batsmen: &[Batsman], _spec: &SortSpec) -> Option<(&'static str, Result<(), Broken>)> {
+  None
+}
+
+/* Every way the file gets put in order, by spec: each of the sorts and
+ * the Leaderboard. The names are what selfcheck prints. */
+fn sorts(batsmen: &[Batsman], spec: &SortSpec) -> Vec<(&'static str, Result<(), Broken>)> {
+  let cmp = spec.comparator();
+  let mut in_place = batsmen.to_vec();
+  sort_in_place(&mut in_place, true, &cmp);
+  let mut checks = vec![
+    ("sorted", check_sorted(&sorted(batsmen.to_vec(), &cmp), &cmp)),
+    ("sorted_unstable", check_sorted(&sorted_unstable(batsmen.to_vec(), &cmp), &cmp)),
+    ("sort_in_place", check_sorted(&in_place, &cmp)),
+    ("sorted_iter", check_sorted(&sorted_iter(batsmen, &cmp).collect::<Vec<&Batsman>>(), |lhs, rhs| cmp(lhs, rhs))),
+    ("leaderboard", check_sorted(Leaderboard::from_vec(spec.clone(), batsmen.to_vec()).as_slice(), &cmp))
+  ];
+  checks.extend(parallel(batsmen, spec));
+  checks
+}
+
+/* batsmen selfcheck [file]
+ *
+ * Puts the file through every sort and every way of ranking, on each
+ * field both ways round, and checks that the answers keep the promises
+ * in invariants.rs: sorted output really is sorted, averages are
+ * finite and ranks have no gaps they should not. Debug builds check
+ * all of this as they go; this is for release builds, and for files
+ * which might turn up something the tests did not. Prints what broke,
+ * if anything, and exits with the validation exit code if it did. */
+pub fn run(args: &Args) -> CliResult<()> {
+  let input = read_input(args.positional.first(), args)?;
+  let batsmen = parse_batsmen(&input)?;
+
+  let mut checks = vec![
+    ("averages".to_string(), check_finite(&batsmen.iter().map(|b| average_f32(b.average)).collect::<Vec<f32>>()))
+  ];
+  for &field in &[Field::Initials, Field::Surname, Field::Runs, Field::Average] {
+    for spec in &[SortSpec::by(field).asc(), SortSpec::by(field).desc()] {
+      for (name, result) in sorts(&batsmen, spec) {
+        checks.push((format!("{} by {}", name, spec), result));
+      }
+      let ordered = sorted(batsmen.clone(), spec.comparator());
+      for &method in &[RankMethod::Standard, RankMethod::Dense, RankMethod::Ordinal] {
+        let ranks = rank(&ordered, method, spec.key_comparator());
+        checks.push((format!("{:?} ranks by {}", method, spec).to_lowercase(), check_ranks(&ranks, method)));
+      }
+    }
+  }
+
+  let failures = checks.iter().filter_map(|(name, result)| result.as_ref().err().map(|e| (name, e))).collect::<Vec<_>>();
+  let mut output = failures.iter().map(|(name, broken)| format!("{}: {}\n", name, broken)).collect::<String>();
+
+  /* How many checks there are depends on the features (par_sorted is
+   * only there with parallel), so it is only worth saying when some of
+   * them failed */
+  if failures.is_empty() {
+    output.push_str("all checks passed\n");
+    print_output(args, &output)
+  } else {
+    output.push_str(&format!("{} of {} checks passed\n", checks.len() - failures.len(), checks.len()));
+    print_output(args, &output)?;
+    Err(CliError::Validation(failures.len()))
+  }
+}
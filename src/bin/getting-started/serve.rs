@@ -0,0 +1,224 @@
+use std::fs;
+use std::io;
+use std::mem;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::SystemTime;
+
+#[cfg(unix)]
+use signal_hook::consts::SIGHUP;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use getting_started::anonymize::Pseudonyms;
+use getting_started::batsman::Batsman;
+use getting_started::dataset::ParsedDataset;
+use getting_started::output::{Format, Style, Table, json_string};
+use getting_started::record::Record;
+use getting_started::sort::sorted;
+
+use crate::cli::{Args, read_data};
+use crate::error::{CliError, CliResult};
+use crate::template::summary;
+
+/* What a request gets back: a status code and a JSON body */
+type Reply = (u16, String);
+
+/* The file being served. It is read the way every other command reads
+ * its input, so --input-format, --coerce, --anonymize and the rest all
+ * work, and parsed once when it is loaded, into a ParsedDataset which
+ * owns the text its Batsmen borrow from, so a reload can swap the
+ * whole thing out in one go. */
+struct Dataset {
+  path: String,
+  batsmen: ParsedDataset,
+  /* With --anonymize, what each player is served as */
+  names: Pseudonyms,
+  modified: Option<SystemTime>
+}
+
+fn modified(path: &str) -> Option<SystemTime> {
+  fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+impl Dataset {
+  fn load(path: &str, args: &Args) -> CliResult<Dataset> {
+    let mut input = read_data(path, args)?;
+    let batsmen = ParsedDataset::parse(mem::take(&mut input.contents)).map_err(|(line, error)| {
+      input.locate(CliError::Parse { file: None, line, column: None, error })
+    })?;
+    Ok(Dataset {
+      path: path.to_string(),
+      batsmen,
+      names: input.names,
+      modified: modified(path)
+    })
+  }
+
+  /* Every batsman, as they should be served */
+  fn batsmen(&self) -> Vec<Batsman<'_>> {
+    self.batsmen.iter().map(|b| self.names.batsman(&b)).collect()
+  }
+
+  /* Reads the file again if it has changed on disk or we were sent
+   * SIGHUP. If the new version does not load, we carry on serving the
+   * old one rather than going down. */
+  fn reload_if_needed(&mut self, hangup: &AtomicBool, args: &Args) {
+    let forced = hangup.swap(false, Ordering::Relaxed);
+    if !forced && modified(&self.path) == self.modified {
+      return;
+    }
+
+    match Dataset::load(&self.path, args) {
+      Ok(d) => {
+        info!(path = %self.path, "reloaded dataset");
+        *self = d;
+      },
+      Err(e) => warn!("keeping the old dataset: {}", e)
+    }
+  }
+}
+
+/* Undoes the %xx escapes (and + for space) in part of a URL. Anything
+ * which is not a valid escape is left as it is. */
+fn percent_decode(s: &str) -> String {
+  let hex = |b: u8| (b as char).to_digit(16).map(|d| d as u8);
+  let bytes = s.as_bytes();
+  let mut out = Vec::with_capacity(bytes.len());
+  let mut i = 0;
+  while i < bytes.len() {
+    match bytes[i] {
+      b'+' => out.push(b' '),
+      b'%' if i + 2 < bytes.len() => match (hex(bytes[i + 1]), hex(bytes[i + 2])) {
+        (Some(hi), Some(lo)) => {
+          out.push(hi * 16 + lo);
+          i += 2;
+        },
+        _ => out.push(b'%')
+      },
+      b => out.push(b)
+    }
+    i += 1;
+  }
+  String::from_utf8_lossy(&out).into_owned()
+}
+
+/* The value of a key in a query string like "n=10&by=average" */
+fn query_value(query: &str, key: &str) -> Option<String> {
+  query.split('&')
+    .filter_map(|pair| {
+      let mut parts = pair.splitn(2, '=');
+      match (parts.next(), parts.next()) {
+        (Some(k), Some(v)) if percent_decode(k) == key => Some(percent_decode(v)),
+        _ => None
+      }
+    })
+    .next_back()
+}
+
+fn error_reply(status: u16, message: &str) -> Reply {
+  (status, format!("{{\"error\": {}}}\n", json_string(message)))
+}
+
+fn players_reply(batsmen: &[Batsman]) -> Reply {
+  (200, Table::from_records(batsmen).render(Format::Json, &Style::plain()))
+}
+
+/* Works out the reply to a GET of path (with its query string) */
+fn route(path: &str, query: &str, batsmen: Vec<Batsman>) -> Reply {
+  let segments = path.trim_matches('/').split('/').collect::<Vec<&str>>();
+
+  match segments.as_slice() {
+    ["players"] => players_reply(&batsmen),
+    ["players", name] => {
+      let name = percent_decode(name);
+      let found = batsmen.into_iter().filter(|b| b.matches_name(&name)).collect::<Vec<Batsman>>();
+      if found.is_empty() {
+        error_reply(404, &format!("No player matching {:?}", name))
+      } else {
+        players_reply(&found)
+      }
+    },
+    ["stats"] => match serde_json::to_string(&summary(&batsmen)) {
+      Ok(json) => (200, json + "\n"),
+      Err(e) => error_reply(500, &e.to_string())
+    },
+    ["top"] => {
+      let n = match query_value(query, "n").map(|n| n.parse::<usize>()) {
+        None => 10,
+        Some(Ok(n)) => n,
+        Some(Err(e)) => return error_reply(400, &format!("Bad value for n: {}", e))
+      };
+      let by = query_value(query, "by").unwrap_or_else(|| "runs".to_string());
+      if batsmen.first().is_some_and(|b| b.field(&by).is_none()) {
+        return error_reply(400, &format!("Unknown field {:?}", by));
+      }
+
+      let mut best = sorted(batsmen, |lhs, rhs| rhs.cmp_by(lhs, &by));
+      best.truncate(n);
+      players_reply(&best)
+    },
+    _ => error_reply(404, &format!("Nothing at {}", path))
+  }
+}
+
+fn respond(request: Request, dataset: &Dataset) -> io::Result<()> {
+  let url = request.url().to_string();
+  let (path, query) = match url.find('?') {
+    Some(i) => (&url[..i], &url[i + 1..]),
+    None => (url.as_str(), "")
+  };
+
+  let (status, body) = if *request.method() != Method::Get {
+    error_reply(405, "Only GET is supported")
+  } else {
+    route(path, query, dataset.batsmen())
+  };
+  debug!(%path, status, "request");
+
+  let json = Header::from_bytes("Content-Type", "application/json").expect("static header is valid");
+  let cors = Header::from_bytes("Access-Control-Allow-Origin", "*").expect("static header is valid");
+  request.respond(Response::from_string(body)
+                    .with_status_code(status)
+                    .with_header(json)
+                    .with_header(cors))
+}
+
+/* batsmen serve <file> [--port 8080] [--host 127.0.0.1]
+ *
+ * Serves the batting file as JSON over HTTP, for a club website or
+ * anything else that would rather not run the command itself:
+ *
+ * GET /players            every batsman
+ * GET /players/<name>     batsmen matching a name, like /players/Cook
+ * GET /stats              summary figures, as in the report
+ * GET /top?n=10&by=runs   the best n batsmen on a field
+ *
+ * The file is read again whenever it changes, or on SIGHUP. */
+pub fn run(args: &Args) -> CliResult<()> {
+  let path = args.positional(0, "a file to serve")?;
+  let port = args.parsed::<u16>("--port")?.unwrap_or(8080);
+  let host = args.value("--host").unwrap_or("127.0.0.1");
+  let address = format!("{}:{}", host, port);
+
+  let mut dataset = Dataset::load(path, args)?;
+
+  /* There is no SIGHUP on Windows, so there only file changes count */
+  let hangup = Arc::new(AtomicBool::new(false));
+  #[cfg(unix)]
+  signal_hook::flag::register(SIGHUP, Arc::clone(&hangup)).map_err(|error| {
+    CliError::Io { path: "SIGHUP".to_string(), error }
+  })?;
+
+  let server = Server::http(&address).map_err(|e| {
+    CliError::Io { path: address.clone(), error: io::Error::other(e) }
+  })?;
+  info!(%address, "serving {}", path);
+
+  for request in server.incoming_requests() {
+    dataset.reload_if_needed(&hangup, args);
+    if let Err(e) = respond(request, &dataset) {
+      warn!("failed to send response: {}", e);
+    }
+  }
+  Ok(())
+}
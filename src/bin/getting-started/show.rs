@@ -0,0 +1,75 @@
+use std::cmp::Ordering;
+
+use getting_started::batsman::Batsman;
+use getting_started::numeric::average_f32;
+use getting_started::output::{Format, Table, json_string, json_value};
+use getting_started::record::{Record, Value};
+
+use crate::cli::{Args, parse_batsmen, print_output, read_input};
+use crate::compare::find_player;
+use crate::error::CliResult;
+
+/* Where a value comes among everyone's, counting from 1 for the
+ * highest. Players level with others share the better place, as in
+ * standard competition ranking. */
+fn place(batsmen: &[Batsman], player: &Batsman, field: &str) -> Option<u32> {
+  let value = player.field(field)?;
+  let above = batsmen.iter()
+    .filter(|b| b.field(field).is_some_and(|v| v.compare(&value) == Ordering::Greater))
+    .count();
+  Some(above as u32 + 1)
+}
+
+/* batsmen show "AN Cook" [file] [--format table|json|markdown]
+ *                        [--derive "name = expression"] [--fields f,g]
+ *
+ * Everything we know about one player: their fields, the number of
+ * times they were out (which is what their runs and average imply),
+ * and where their runs and average place them among everyone in the
+ * file. --derive adds more lines to the card, and --fields picks which
+ * ones are shown. */
+pub fn run(args: &Args) -> CliResult<()> {
+  let name = args.positional(0, "a player to show")?;
+  let format = args.parsed::<Format>("--format")?.unwrap_or(Format::Table);
+
+  let input = read_input(args.positional.get(1), args)?;
+  let batsmen = parse_batsmen(&input)?;
+  let player = find_player(&batsmen, name)?.clone();
+
+  let players = [player.clone()];
+  let mut card = Table::from_records(&players);
+  let mut add = |name: &str, value: Option<Value<'static>>| {
+    let end = card.columns.len();
+    card.insert_column(end, name, vec![value]);
+  };
+  let average = average_f32(player.average);
+  add("dismissals", if average > 0.0 {
+    Some(Value::Integer((player.runs as f32 / average).round() as u32))
+  } else {
+    None
+  });
+  add("runs_rank", place(&batsmen, &player, "runs").map(Value::Integer));
+  add("average_rank", place(&batsmen, &player, "average").map(Value::Integer));
+  add("players", Some(Value::Integer(batsmen.len() as u32)));
+  /* find_player made sure only one batsman matches, so this is the line
+   * of the input they were on */
+  let rows = batsmen.iter().position(|b| b.matches_name(name)).into_iter().collect::<Vec<usize>>();
+  let card = args.sourced_columns(card, &input, &rows)?;
+
+  /* The card is one row, which reads better turned on its side as a
+   * line per field, or as a single JSON object */
+  let fields = card.columns.iter().zip(card.rows[0].iter()).collect::<Vec<(&String, &Option<Value>)>>();
+  let output = match format {
+    Format::Table | Format::Markdown => {
+      let rows = fields.iter().map(|&(name, value)| vec![Some(Value::Text(name.as_str())), *value]).collect();
+      Table::new(vec!["field".to_string(), "value".to_string()], rows).render(format, &args.style()?)
+    },
+    Format::Json => {
+      let fields = fields.iter().map(|&(name, value)| {
+        format!("{}: {}", json_string(name), value.map(|v| json_value(&v)).unwrap_or_else(|| "null".to_string()))
+      }).collect::<Vec<String>>();
+      format!("{{{}}}\n", fields.join(", "))
+    }
+  };
+  print_output(args, &output)
+}
@@ -0,0 +1,79 @@
+use getting_started::batting_order::best_xi;
+use getting_started::numeric::average_f32;
+use getting_started::output::{Format, Table, json_string};
+use getting_started::record::{numbers, Value};
+use getting_started::rng::Rng;
+use getting_started::simulate::{Distribution, simulate};
+
+use crate::cli::{Args, parse_batsmen, print_output, read_input};
+use crate::error::{CliError, CliResult};
+
+/* batsmen simulate [file] [--innings 10000] [--distribution geometric]
+ *                  [--confidence 0.95] [--metric runs] [--seed s]
+ *                  [--format table|json|markdown]
+ *
+ * Picks the best eleven on --metric, as batsmen xi does, and plays
+ * them --innings times over with each batsman's scores drawn at random
+ * around their average. Prints what each of them can expect to score
+ * and what the team can expect to make, with the range the middle
+ * --confidence of totals fell in. Our files have no strike rates, so
+ * the average is all that goes into it. Passing --seed gives the same
+ * answer every time. */
+pub fn run(args: &Args) -> CliResult<()> {
+  let innings = args.parsed::<usize>("--innings")?.unwrap_or(10000);
+  let distribution = args.parsed::<Distribution>("--distribution")?.unwrap_or(Distribution::Geometric);
+  let confidence = args.parsed::<f32>("--confidence")?.unwrap_or(0.95);
+  if !(confidence > 0.0 && confidence < 1.0) {
+    return Err(CliError::Usage("--confidence has to be between 0 and 1".to_string()));
+  }
+  let metric = args.value("--metric").unwrap_or("runs");
+  let format = args.parsed::<Format>("--format")?.unwrap_or(Format::Table);
+  let mut rng = match args.parsed::<u64>("--seed")? {
+    Some(seed) => Rng::new(seed),
+    None => Rng::from_time()
+  };
+
+  let input = read_input(args.positional.first(), args)?;
+  let batsmen = parse_batsmen(&input)?;
+  if numbers(&batsmen, metric).is_none() {
+    return Err(CliError::Usage(format!("{:?} is not a numeric field", metric)));
+  }
+
+  let side = best_xi(&batsmen, metric);
+  let averages = side.players().iter().map(|b| average_f32(b.average)).collect::<Vec<f32>>();
+  let result = simulate(&averages, innings, distribution, &mut rng);
+  let total = result.mean_total();
+  let interval = result.interval(confidence);
+
+  let mut table = Table::new(
+    ["position", "initials", "surname", "average", "expected"].iter().map(|c| c.to_string()).collect(),
+    side.iter().zip(result.expected.iter()).map(|((p, b), &e)| vec![
+      Some(Value::Integer(p as u32)),
+      Some(Value::Text(b.initials)),
+      Some(Value::Text(b.surname)),
+      Some(Value::average(b.average)),
+      Some(Value::Float((e * 100.0).round() / 100.0))
+    ]).collect());
+  table.anonymize(&input.names);
+  let style = args.style()?;
+
+  print_output(args, &match format {
+    Format::Json => {
+      let optional = |v: Option<f32>| v.map(|x| x.to_string()).unwrap_or_else(|| "null".to_string());
+      format!("{{\"innings\": {}, \"distribution\": {}, \"players\": {}, \"expected_total\": {}, \"confidence\": {}, \"interval\": [{}, {}]}}\n",
+              innings,
+              json_string(&format!("{:?}", distribution).to_lowercase()),
+              table.render(format, &style).trim_end(),
+              optional(total),
+              confidence,
+              optional(interval.map(|i| i.0)),
+              optional(interval.map(|i| i.1)))
+    },
+    _ => format!("{}{}", table.render(format, &style), match (total, interval) {
+      (Some(t), Some((low, high))) =>
+        format!("\nExpected total: {:.1} over {} innings\n{}% of totals were between {:.0} and {:.0}\n",
+                t, innings, confidence * 100.0, low, high),
+      _ => "\nNo innings were played\n".to_string()
+    })
+  })
+}
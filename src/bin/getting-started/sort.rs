@@ -0,0 +1,197 @@
+use std::collections::hash_map::RandomState;
+use std::env;
+use std::fs::{self, DirBuilder, File};
+use std::hash::{BuildHasher, Hasher};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+#[cfg(unix)]
+use std::os::unix::fs::DirBuilderExt;
+use std::path::PathBuf;
+use std::process;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+
+use signal_hook::consts::TERM_SIGNALS;
+
+use getting_started::batsman::Batsman;
+use getting_started::external::ExternalSort;
+use getting_started::numeric::Average;
+use getting_started::rng::Rng;
+use getting_started::sort::{Field, SortSpec};
+
+use crate::cli::Args;
+use crate::error::{CliError, CliResult, parse_error};
+
+/* How much of the file is kept in memory at once, in megabytes,
+ * unless --memory-budget says otherwise */
+const DEFAULT_BUDGET: usize = 256;
+
+/* What a line is sorted by: the batsman on it, parsed once, with the
+ * names copied out so that it can be kept alongside the line */
+struct Key {
+  initials: String,
+  surname: String,
+  runs: u32,
+  average: Average
+}
+
+impl Key {
+  fn of(b: &Batsman) -> Key {
+    Key { initials: b.initials.to_string(), surname: b.surname.to_string(), runs: b.runs, average: b.average }
+  }
+
+  fn batsman(&self) -> Batsman<'_> {
+    Batsman { initials: &self.initials, surname: &self.surname, runs: self.runs, average: self.average }
+  }
+}
+
+/* A new directory of our own for the temporary files. Anyone can write
+ * to the system's temporary directory, so its name cannot be one that
+ * someone could guess and make first (as a link to somewhere else, say):
+ * it gets a random suffix, and if it is already there, whoever made it,
+ * another one is tried rather than using it. Only we can read it. */
+fn temp_dir() -> io::Result<PathBuf> {
+  let mut rng = Rng::new(RandomState::new().build_hasher().finish() ^ process::id() as u64);
+  let mut builder = DirBuilder::new();
+  #[cfg(unix)]
+  builder.mode(0o700);
+  loop {
+    let dir = env::temp_dir().join(format!("batsmen-sort-{:016x}", rng.next_u64()));
+    match builder.create(&dir) {
+      Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+      result => return result.map(|()| dir)
+    }
+  }
+}
+
+/* batsmen sort [file] [--sort-by runs:desc] [--memory-budget 256]
+ *              [--external-sort] [--output sorted.txt]
+ *
+ * Sorts a batting file and writes its lines back out in the new order,
+ * unchanged otherwise. Unlike the other commands the file is read a
+ * line at a time, so it can be bigger than memory: once the lines read
+ * so far take up more than --memory-budget megabytes they are sorted
+ * and put to one side in a temporary file, and at the end all those
+ * files are merged (see external.rs). --external-sort goes through the
+ * temporary files even for a file which would fit.
+ *
+ * Only plain comma separated UTF-8 files can be read this way. A BOM at
+ * the start is skipped and CRLF line endings are read as plain ones,
+ * but the lines are written out with LF endings.
+ *
+ * Ctrl-C stops the sort cleanly: the temporary files are deleted, the
+ * number of rows read so far is reported, and the command exits with
+ * code 130. --output is written to a file alongside it and only renamed
+ * into place once it is complete, so an interrupted sort leaves any
+ * earlier output as it was rather than half written. A second Ctrl-C
+ * kills it straight away. */
+pub fn run(args: &Args) -> CliResult<()> {
+  let spec = args.parsed::<SortSpec>("--sort-by")?.unwrap_or_else(|| SortSpec::by(Field::Runs).desc());
+  let budget = args.parsed::<usize>("--memory-budget")?.unwrap_or(DEFAULT_BUDGET);
+  if budget == 0 {
+    return Err(CliError::Usage("--memory-budget has to be at least 1".to_string()));
+  }
+
+  let (path, input): (&str, Box<dyn BufRead>) = match args.positional.first().map(|p| p.as_str()) {
+    None | Some("-") => ("<stdin>", Box::new(BufReader::new(io::stdin()))),
+    Some(p) => (p, Box::new(BufReader::new(File::open(p).map_err(|error| {
+      CliError::Io { path: p.to_string(), error }
+    })?)))
+  };
+
+  let dir = temp_dir().map_err(|error| CliError::Io { path: env::temp_dir().display().to_string(), error })?;
+  let temp_error = |error| CliError::Io { path: dir.display().to_string(), error };
+
+  /* The key of a line read back from a temporary file for the merge.
+   * Every line is parsed as it is read in, and only those which parse
+   * are written out, so this one parses too. */
+  let key = |l: &str| Key::of(&Batsman::parse(l).expect("every line was checked before it was pushed"));
+  let cmp = |l: &Key, r: &Key| spec.compare(&l.batsman(), &r.batsman());
+  let mut sorter = ExternalSort::new(&dir, budget * 1024 * 1024, key, cmp).spill_all(args.has("--external-sort"));
+
+  let interrupted = Arc::new(AtomicBool::new(false));
+  for &signal in TERM_SIGNALS {
+    let registered = signal_hook::flag::register_conditional_shutdown(signal, 130, Arc::clone(&interrupted))
+      .and_then(|_| signal_hook::flag::register(signal, Arc::clone(&interrupted)));
+    registered.map_err(|error| CliError::Io { path: format!("signal {}", signal), error })?;
+  }
+  let mut rows = 0;
+
+  let result = (|| {
+    for (i, line) in input.lines().enumerate() {
+      if interrupted.load(AtomicOrdering::Relaxed) {
+        return Err(CliError::Interrupted(rows));
+      }
+      let mut line = line.map_err(|error| CliError::Io { path: path.to_string(), error })?;
+      if i == 0 && line.starts_with('\u{feff}') {
+        line.remove(0);
+      }
+      let key = Key::of(&Batsman::parse(&line).map_err(|error| match parse_error(i, &line, error) {
+        CliError::Parse { line, column, error, .. } => CliError::Parse { file: Some(path.to_string()), line, column, error },
+        e => e
+      })?);
+      sorter.push(key, line).map_err(temp_error)?;
+      rows += 1;
+    }
+    debug!(runs = sorter.runs(), "sorted in chunks");
+
+    /* Merging can take as long as reading, so the flag is checked on
+     * every write too */
+    let stopped = |error: io::Error, path: &str| if interrupted.load(AtomicOrdering::Relaxed) {
+      CliError::Interrupted(rows)
+    } else {
+      CliError::Io { path: path.to_string(), error }
+    };
+    match args.value("--output") {
+      Some(out) => {
+        let partial = format!("{}.partial", out);
+        let written = (|| {
+          let file = File::create(&partial).map_err(|error| stopped(error, &partial))?;
+          let mut w = BufWriter::new(Cancellable { inner: file, interrupted: &interrupted });
+          sorter.finish(&mut w).map_err(|error| stopped(error, &dir.display().to_string()))?;
+          w.flush().map_err(|error| stopped(error, &partial))?;
+          fs::rename(&partial, out).map_err(|error| stopped(error, out))
+        })();
+        if written.is_err() {
+          let _ = fs::remove_file(&partial);
+        }
+        written
+      },
+      None => {
+        let stdout = io::stdout();
+        let mut w = BufWriter::new(Cancellable { inner: stdout.lock(), interrupted: &interrupted });
+        sorter.finish(&mut w).map_err(|error| stopped(error, &dir.display().to_string()))?;
+        w.flush().map_err(|error| stopped(error, "<stdout>"))
+      }
+    }
+  })();
+
+  /* Failing to tidy up is only worth a warning: the sort has already
+   * worked or failed, and that is what gets reported */
+  if let Err(error) = fs::remove_dir_all(&dir) {
+    warn!("could not delete the temporary files in {}: {}", dir.display(), error);
+  }
+  if let Err(CliError::Interrupted(rows)) = result {
+    info!(rows, "interrupted, cleaned up the temporary files");
+  }
+  result
+}
+
+/* A writer which fails once Ctrl-C has been pressed, so that a long
+ * merge stops at the next line rather than running to the end */
+struct Cancellable<'f, W> {
+  inner: W,
+  interrupted: &'f AtomicBool
+}
+
+impl<'f, W: Write> Write for Cancellable<'f, W> {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    if self.interrupted.load(AtomicOrdering::Relaxed) {
+      return Err(io::Error::other("interrupted"));
+    }
+    self.inner.write(buf)
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    self.inner.flush()
+  }
+}
@@ -0,0 +1,76 @@
+use std::error::Error;
+
+use serde::Serialize;
+use tera::{Context, Tera};
+
+use getting_started::batsman::Batsman;
+use getting_started::numeric::average_f32;
+use getting_started::stats::{mean, median, std_dev};
+
+use crate::error::{CliError, CliResult};
+
+/* What a template sees of each batsman */
+#[derive(Serialize)]
+struct Player<'a> {
+  initials: &'a str,
+  surname: &'a str,
+  runs: u32,
+  average: f32
+}
+
+/* Figures about the whole list, for the top of a report */
+#[derive(Serialize)]
+pub struct Summary {
+  players: usize,
+  total_runs: u32,
+  mean_average: Option<f32>,
+  median_average: Option<f32>,
+  std_dev_average: Option<f32>,
+  top_scorer: Option<String>
+}
+
+pub fn summary(batsmen: &[Batsman]) -> Summary {
+  let averages = batsmen.iter().map(|b| average_f32(b.average)).collect::<Vec<f32>>();
+  Summary {
+    players: batsmen.len(),
+    total_runs: batsmen.iter().map(|b| b.runs).sum(),
+    mean_average: mean(&averages),
+    median_average: median(&averages),
+    std_dev_average: std_dev(&averages),
+    top_scorer: batsmen.iter().max().map(|b| format!("{} {}", b.initials, b.surname))
+  }
+}
+
+/* Everything a template can use: "players" is the list of batsmen in
+ * the order given, "summary" holds the figures for all of them */
+pub fn context(batsmen: &[Batsman]) -> Context {
+  let players = batsmen.iter().map(|b| {
+    Player {
+      initials: b.initials,
+      surname: b.surname,
+      runs: b.runs,
+      average: average_f32(b.average)
+    }
+  }).collect::<Vec<Player>>();
+
+  let mut context = Context::new();
+  context.insert("players", &players);
+  context.insert("summary", &summary(batsmen));
+  context
+}
+
+/* Renders a Tera template. Tera tucks the useful part of an error
+ * (which variable, which line) away in its source, so the whole chain
+ * goes into the message. A broken template is the user's to fix, so it
+ * counts as a usage error. */
+pub fn render(source: &str, context: &Context, autoescape: bool) -> CliResult<String> {
+  Tera::one_off(source, context, autoescape).map_err(|e| {
+    let mut message = e.to_string();
+    let mut cause = e.source();
+    while let Some(c) = cause {
+      message.push_str(&format!(": {}", c));
+      cause = c.source();
+    }
+    CliError::Usage(message)
+  })
+}
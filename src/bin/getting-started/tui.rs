@@ -0,0 +1,233 @@
+use std::io;
+
+use ratatui::DefaultTerminal;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState};
+
+use getting_started::batsman::Batsman;
+use getting_started::join::normalize_name;
+use getting_started::numeric::average_f32;
+use getting_started::record::Record;
+
+use crate::cli::{Args, parse_batsmen, read_data};
+use crate::error::{CliError, CliResult};
+
+const FIELDS: &[&str] = &["initials", "surname", "runs", "average"];
+
+const HELP: &str = "↑↓ move  ←→ sort column  r reverse  / search  esc clear  q quit";
+
+/* Everything the browser needs to remember between key presses. The
+ * batsmen themselves never move, view holds the positions of the ones
+ * currently showing in the order they are showing. */
+struct App<'a> {
+  batsmen: Vec<Batsman<'a>>,
+  view: Vec<usize>,
+  sort_field: usize,
+  descending: bool,
+  query: String,
+  searching: bool,
+  state: TableState
+}
+
+impl<'a> App<'a> {
+  fn new(batsmen: Vec<Batsman<'a>>) -> App<'a> {
+    let mut app = App {
+      batsmen,
+      view: Vec::new(),
+      sort_field: 2,
+      descending: true,
+      query: String::new(),
+      searching: false,
+      state: TableState::default()
+    };
+    app.refresh();
+    app
+  }
+
+  /* Works out the view again after the sort order or search changes,
+   * keeping the same player selected if they are still showing */
+  fn refresh(&mut self) {
+    let selected = self.selected();
+    let query = normalize_name(&self.query);
+    let field = FIELDS[self.sort_field];
+
+    let batsmen = &self.batsmen;
+    let mut view = (0..batsmen.len()).filter(|&i| {
+      let b = &batsmen[i];
+      normalize_name(&format!("{} {}", b.initials, b.surname)).contains(&query)
+    }).collect::<Vec<usize>>();
+    view.sort_by(|&l, &r| batsmen[l].cmp_by(&batsmen[r], field));
+    if self.descending {
+      view.reverse();
+    }
+
+    let position = selected.and_then(|s| view.iter().position(|&i| i == s));
+    self.view = view;
+    self.state.select(match position {
+      Some(p) => Some(p),
+      None if self.view.is_empty() => None,
+      None => Some(0)
+    });
+  }
+
+  /* Position in batsmen of the highlighted row */
+  fn selected(&self) -> Option<usize> {
+    self.state.selected().and_then(|row| self.view.get(row).cloned())
+  }
+
+  fn move_by(&mut self, delta: isize) {
+    if self.view.is_empty() {
+      return;
+    }
+    let current = self.state.selected().unwrap_or(0) as isize;
+    let last = self.view.len() as isize - 1;
+    self.state.select(Some((current + delta).clamp(0, last) as usize));
+  }
+
+  fn sort_on(&mut self, field: usize) {
+    self.sort_field = field;
+    self.refresh();
+  }
+
+  /* Returns false once the user has asked to quit */
+  fn key(&mut self, code: KeyCode) -> bool {
+    if self.searching {
+      match code {
+        KeyCode::Enter => self.searching = false,
+        KeyCode::Esc => {
+          self.searching = false;
+          self.query.clear();
+        },
+        KeyCode::Backspace => {
+          self.query.pop();
+        },
+        KeyCode::Char(c) => self.query.push(c),
+        _ => return true
+      }
+      self.refresh();
+      return true;
+    }
+
+    match code {
+      KeyCode::Char('q') => return false,
+      KeyCode::Up | KeyCode::Char('k') => self.move_by(-1),
+      KeyCode::Down | KeyCode::Char('j') => self.move_by(1),
+      KeyCode::PageUp => self.move_by(-10),
+      KeyCode::PageDown => self.move_by(10),
+      KeyCode::Home => self.move_by(-(self.view.len() as isize)),
+      KeyCode::End => self.move_by(self.view.len() as isize),
+      KeyCode::Left => self.sort_on((self.sort_field + FIELDS.len() - 1) % FIELDS.len()),
+      KeyCode::Right | KeyCode::Tab => self.sort_on((self.sort_field + 1) % FIELDS.len()),
+      KeyCode::Char(c @ '1'..='4') => self.sort_on(c as usize - '1' as usize),
+      KeyCode::Char('r') => {
+        self.descending = !self.descending;
+        self.refresh();
+      },
+      KeyCode::Char('/') => self.searching = true,
+      KeyCode::Esc => {
+        self.query.clear();
+        self.refresh();
+      },
+      _ => ()
+    }
+    true
+  }
+
+  /* The detail pane for whoever is highlighted. Dismissals are only
+   * an estimate, since averages are quoted to two decimal places. */
+  fn detail(&self) -> Vec<Line<'_>> {
+    let b = match self.selected() {
+      Some(i) => &self.batsmen[i],
+      None => return vec![Line::from("No players match")]
+    };
+    let rank = self.batsmen.iter().filter(|other| other.runs > b.runs).count() + 1;
+
+    let mut lines = vec![
+      Line::from(format!("{} {}", b.initials, b.surname))
+        .style(Style::default().add_modifier(Modifier::BOLD)),
+      Line::from(""),
+      Line::from(format!("Runs        {}", b.runs)),
+      Line::from(format!("Average     {:.2}", b.average)),
+      Line::from(format!("Rank        {} of {}", rank, self.batsmen.len()))
+    ];
+    let average = average_f32(b.average);
+    if average > 0.0 {
+      lines.push(Line::from(format!("Dismissals  ~{}", (b.runs as f32 / average).round())));
+    }
+    lines
+  }
+
+  fn draw(&mut self, frame: &mut ratatui::Frame) {
+    let [main, status] = Layout::vertical([Constraint::Min(1), Constraint::Length(1)])
+      .areas(frame.area());
+    let [list, detail] = Layout::horizontal([Constraint::Percentage(65), Constraint::Percentage(35)])
+      .areas(main);
+
+    /* The column being sorted on gets an arrow showing which way */
+    let header = Row::new(FIELDS.iter().enumerate().map(|(i, name)| {
+      if i == self.sort_field {
+        Cell::from(format!("{} {}", name, if self.descending { "▼" } else { "▲" }))
+      } else {
+        Cell::from(*name)
+      }
+    })).style(Style::default().add_modifier(Modifier::BOLD));
+
+    let rows = self.view.iter().map(|&i| Row::new(self.batsmen[i].to_row())).collect::<Vec<Row>>();
+    let table = Table::new(rows, [
+      Constraint::Length(8),
+      Constraint::Min(12),
+      Constraint::Length(8),
+      Constraint::Length(10)
+    ])
+      .header(header)
+      .block(Block::default().borders(Borders::ALL).title(format!("Batsmen ({})", self.view.len())))
+      .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    frame.render_stateful_widget(table, list, &mut self.state);
+
+    let pane = Paragraph::new(self.detail())
+      .block(Block::default().borders(Borders::ALL).title("Player"));
+    frame.render_widget(pane, detail);
+
+    let line = if self.searching || !self.query.is_empty() {
+      format!("/{}{}", self.query, if self.searching { "_" } else { "" })
+    } else {
+      HELP.to_string()
+    };
+    frame.render_widget(Paragraph::new(line), status);
+  }
+}
+
+fn run_app(terminal: &mut DefaultTerminal, app: &mut App) -> io::Result<()> {
+  loop {
+    terminal.draw(|frame| app.draw(frame))?;
+    if let Event::Key(key) = event::read()? {
+      /* Windows reports key releases as well as presses */
+      if key.kind == KeyEventKind::Press && !app.key(key.code) {
+        return Ok(());
+      }
+    }
+  }
+}
+
+/* batsmen tui <file>
+ *
+ * A full screen browser for a batting file: scroll through the table,
+ * change which column it is sorted on, search by name and see more
+ * about the highlighted player. The file has to be named rather than
+ * piped in, since the keyboard is read from the terminal. */
+pub fn run(args: &Args) -> CliResult<()> {
+  let path = args.positional(0, "a file to browse")?;
+  let input = read_data(path, args)?;
+  /* Searching goes by the names on the screen, so with --anonymize
+   * those are the pseudonyms */
+  let mut app = App::new(parse_batsmen(&input)?.iter().map(|b| input.names.batsman(b)).collect());
+
+  let terminal_error = |error| CliError::Io { path: "<terminal>".to_string(), error };
+  let mut terminal = ratatui::try_init().map_err(terminal_error)?;
+  let result = run_app(&mut terminal, &mut app);
+  ratatui::try_restore().map_err(terminal_error)?;
+  result.map_err(terminal_error)
+}
@@ -0,0 +1,59 @@
+use getting_started::output::{Format, Table};
+use getting_started::record::Value;
+use getting_started::validate::validate_with;
+
+use crate::cli::{Args, print_output, read_input};
+use crate::error::{CliError, CliResult, ErrorFormat, json_diagnostic};
+
+/* batsmen validate [file] [--format json|table|markdown] [--error-format json]
+ *
+ * Prints every problem found in the file (an empty list if there are
+ * none) and exits with the validation exit code if there were any, so
+ * it can be used as a check before committing a data file. With
+ * --error-format json each problem is also written to stderr as a
+ * JSON object, like any other error, for editors and CI jobs to
+ * annotate the bad lines with. Lines are the ones in the file as it
+ * was read, which for JSON is the line each record starts on, and
+ * problems in an archive say which of its files they were found in. */
+pub fn run(args: &Args) -> CliResult<()> {
+  let format = args.parsed::<Format>("--format")?.unwrap_or(Format::Json);
+  let input = read_input(args.positional.first(), args)?;
+  let name = args.positional.first().map(|p| p.as_str()).unwrap_or("<stdin>");
+  let source = |i: usize| input.sources.line(i).unwrap_or((name, i + 1));
+  let violations = validate_with(&input.contents, |i| match source(i) {
+    (file, line) if input.archive => format!("{} line {}", file, line),
+    (_, line) => format!("line {}", line)
+  });
+  let sources = violations.iter().map(|v| source(v.line - 1)).collect::<Vec<(&str, usize)>>();
+
+  let rows = violations.iter().zip(&sources).map(|(v, &(file, line))| {
+    let mut row = vec![
+      Some(Value::Integer(line as u32)),
+      Some(Value::Text(v.code)),
+      Some(Value::Text(&v.message))
+    ];
+    if input.archive {
+      row.insert(0, Some(Value::Text(file)));
+    }
+    row
+  }).collect();
+  let mut columns = vec!["line".to_string(), "code".to_string(), "message".to_string()];
+  if input.archive {
+    columns.insert(0, "file".to_string());
+  }
+  let table = Table::new(columns, rows);
+
+  print_output(args, &table.render(format, &args.style()?))?;
+
+  if args.parsed::<ErrorFormat>("--error-format")? == Some(ErrorFormat::Json) {
+    for (v, &(file, line)) in violations.iter().zip(&sources) {
+      eprintln!("{}", json_diagnostic(Some(file), Some(line), None, v.code, &v.message));
+    }
+  }
+
+  if violations.is_empty() {
+    Ok(())
+  } else {
+    Err(CliError::Validation(violations.len()))
+  }
+}
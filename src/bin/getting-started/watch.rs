@@ -0,0 +1,91 @@
+use std::io;
+use std::io::prelude::*;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use notify::{RecursiveMode, Watcher};
+
+use crate::cli::Args;
+use crate::error::{CliError, CliResult};
+
+/* Clears the terminal and moves the cursor back to the top left */
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+/* Editors tend to save a file as several writes (or write a new copy
+ * and rename it over the top), so we wait this long after the first
+ * change for things to settle before re-running */
+const SETTLE: Duration = Duration::from_millis(100);
+
+/* The files a command reads from: any positional argument which names
+ * a file that exists, plus the --batting and --bowling files */
+fn input_paths(args: &Args) -> Vec<PathBuf> {
+  args.positional.iter()
+    .map(|p| p.as_str())
+    .chain(args.value("--batting"))
+    .chain(args.value("--bowling"))
+    .map(PathBuf::from)
+    .filter(|p| p.is_file())
+    .collect()
+}
+
+fn watch_error(path: &Path, e: notify::Error) -> CliError {
+  CliError::Io {
+    path: path.display().to_string(),
+    error: io::Error::other(e)
+  }
+}
+
+/* --watch: runs the command, then runs it again every time one of its
+ * input files changes, clearing the screen first so the latest report
+ * is the only thing showing. Errors are logged rather than ending the
+ * loop, since a half-saved file is usually fixed by the next save.
+ * This only returns if the watcher itself fails. */
+pub fn run<F>(args: &Args, command: F) -> CliResult<()>
+  where F: Fn(&Args) -> CliResult<()>
+{
+  let paths = input_paths(args);
+  if paths.is_empty() {
+    return Err(CliError::Usage("--watch needs an input file, it cannot watch stdin".to_string()));
+  }
+
+  let (tx, rx) = mpsc::channel();
+  let mut watcher = notify::recommended_watcher(tx).map_err(|e| watch_error(&paths[0], e))?;
+
+  /* Watching the directory rather than the file itself means we still
+   * see changes after an editor has replaced the file with a new one */
+  for path in &paths {
+    let dir = match path.parent() {
+      Some(d) if !d.as_os_str().is_empty() => d,
+      _ => Path::new(".")
+    };
+    watcher.watch(dir, RecursiveMode::NonRecursive).map_err(|e| watch_error(path, e))?;
+  }
+  let names = paths.iter().filter_map(|p| p.file_name()).collect::<Vec<_>>();
+
+  loop {
+    print!("{}", CLEAR_SCREEN);
+    if let Err(e) = command(args) {
+      error!("{}", e);
+    }
+    let _ = io::stdout().flush();
+
+    /* Wait for an event touching one of our files, then swallow
+     * whatever else arrives while the writes settle */
+    loop {
+      let event = match rx.recv() {
+        Ok(Ok(event)) => event,
+        Ok(Err(e)) => return Err(watch_error(&paths[0], e)),
+        Err(_) => return Ok(())
+      };
+      if event.kind.is_access() {
+        continue;
+      }
+      if event.paths.iter().any(|p| p.file_name().is_some_and(|n| names.contains(&n))) {
+        break;
+      }
+    }
+    while rx.recv_timeout(SETTLE).is_ok() {}
+    debug!("input changed, running again");
+  }
+}
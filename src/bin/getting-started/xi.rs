@@ -0,0 +1,27 @@
+use getting_started::batting_order::best_xi;
+use getting_started::output::{Format, Table};
+use getting_started::record::{numbers, Value};
+
+use crate::cli::{Args, parse_batsmen, print_output, read_input};
+use crate::error::{CliError, CliResult};
+
+/* batsmen xi [file] [--metric runs] [--format table|json|markdown]
+ *
+ * Picks the best eleven from a batting file, going by a numeric field,
+ * and prints them as a batting order with the best player opening. */
+pub fn run(args: &Args) -> CliResult<()> {
+  let metric = args.value("--metric").unwrap_or("runs");
+  let format = args.parsed::<Format>("--format")?.unwrap_or(Format::Table);
+
+  let input = read_input(args.positional.first(), args)?;
+  let batsmen = parse_batsmen(&input)?;
+  if numbers(&batsmen, metric).is_none() {
+    return Err(CliError::Usage(format!("{:?} is not a numeric field", metric)));
+  }
+
+  let order = best_xi(&batsmen, metric);
+  let mut table = Table::from_records(order.players());
+  table.insert_column(0, "position", order.iter().map(|(p, _)| Some(Value::Integer(p as u32))).collect());
+  print_output(args, &args.columns(table, &input.names)?.render(format, &args.style()?))?;
+  Ok(())
+}
@@ -0,0 +1,54 @@
+use std::io;
+
+use calamine::{open_workbook_auto, Data, Reader};
+
+use crate::error::{CliError, CliResult};
+
+/* Reads one sheet of a spreadsheet (the first, unless a name is
+ * given) and writes it out as the comma separated lines the rest of
+ * the program expects, so that a spreadsheet goes through exactly the
+ * same parsing as a text file. Along with the lines comes the number of
+ * the row each one was, as the spreadsheet shows it.
+ *
+ * Spreadsheets usually start with a row of headings, which is skipped
+ * if the second cell of the first row is text rather than a number.
+ * Blank rows are skipped as well. */
+pub fn read_sheet(path: &str, sheet: Option<&str>) -> CliResult<(String, Vec<usize>)> {
+  let io_error = |e: calamine::Error| CliError::Io { path: path.to_string(), error: io::Error::other(e) };
+
+  let mut workbook = open_workbook_auto(path).map_err(io_error)?;
+  let range = match sheet {
+    Some(name) => {
+      if !workbook.sheet_names().iter().any(|n| n == name) {
+        return Err(CliError::Usage(format!("{} has no sheet called {:?}, it has {:?}",
+                                           path, name, workbook.sheet_names())));
+      }
+      workbook.worksheet_range(name).map_err(io_error)?
+    },
+    None => match workbook.worksheet_range_at(0) {
+      Some(r) => r.map_err(io_error)?,
+      None => return Err(CliError::Usage(format!("{} has no sheets", path)))
+    }
+  };
+
+  let top = range.start().map(|(row, _)| row as usize + 1).unwrap_or(1);
+  let mut rows = range.rows()
+    .enumerate()
+    .filter(|(_, row)| row.iter().any(|c| *c != Data::Empty))
+    .peekable();
+  if let Some((_, first)) = rows.peek() {
+    if let Some(Data::String(_)) = first.get(1) {
+      rows.next();
+    }
+  }
+
+  let mut contents = String::new();
+  let mut numbers = Vec::new();
+  for (i, row) in rows {
+    let cells = row.iter().map(|c| c.to_string()).collect::<Vec<String>>();
+    contents.push_str(cells.join(", ").trim_end_matches([',', ' ']));
+    contents.push('\n');
+    numbers.push(top + i);
+  }
+  Ok((contents, numbers))
+}
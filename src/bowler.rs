@@ -0,0 +1,55 @@
+use error::ParseError;
+use line;
+use record::{Record, Value};
+
+/* The bowling counterpart to Batsman. Like Batsman, it borrows its
+ * names from the line it was parsed from. A bowling average is runs
+ * conceded per wicket, so unlike batting, lower is better. */
+#[derive(Debug, Clone)]
+pub struct Bowler<'a> {
+  pub initials: &'a str,
+  pub surname: &'a str,
+  pub wickets: u32,
+  pub average: f32
+}
+
+impl<'a> Bowler<'a> {
+  /* Parses a line like "JM Anderson, 506, 27.39, 1", which is the same
+   * layout as the batting file with wickets in place of runs */
+  pub fn parse(l: &'a str) -> Result<Bowler<'a>, ParseError> {
+    let v = line::split(l);
+    let (initials, surname) = line::name(v[0])?;
+
+    Ok(Bowler {
+      initials,
+      surname,
+      wickets: line::number(&v, 1, "wickets", "u32")?,
+      average: line::number(&v, 2, "average", "f32")?
+    })
+  }
+
+  pub fn from_line(l: &'a str) -> Bowler<'a> {
+    match Bowler::parse(l) {
+      Ok(b) => b,
+      Err(e) => panic!("{}", e)
+    }
+  }
+}
+
+const BOWLER_FIELDS: &[&str] = &["initials", "surname", "wickets", "average"];
+
+impl<'a> Record for Bowler<'a> {
+  fn field_names(&self) -> &'static [&'static str] {
+    BOWLER_FIELDS
+  }
+
+  fn field(&self, name: &str) -> Option<Value<'_>> {
+    match name {
+      "initials" => Some(Value::Text(self.initials)),
+      "surname" => Some(Value::Text(self.surname)),
+      "wickets" => Some(Value::Integer(self.wickets)),
+      "average" => Some(Value::Float(self.average)),
+      _ => None
+    }
+  }
+}
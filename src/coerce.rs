@@ -0,0 +1,187 @@
+use std::str::FromStr;
+
+/* Cleaning rules for messy files, applied to each field before it is
+ * parsed, like
+ *
+ *   runs: strip_commas; average: strip_percent clamp(0, 200)
+ *
+ * which turns "1,843" into 1843 and "46.33%" into 46.33, and brings an
+ * average of 1000 down to 200. Each part before a semicolon names a
+ * field (name, runs or average, or the number of an item counting
+ * from 1, like 5 for the debut year) and the rules to apply to it, in
+ * order. The rules are:
+ *
+ *   strip_commas    thousands separators, as in 11,629
+ *   strip_percent   percent signs, as in 46.33%
+ *   strip_spaces    whitespace anywhere, as in 11 629
+ *   strip(chars)    any of these characters, as in strip(*) for 46*
+ *   clamp(lo, hi)   numbers below lo or above hi become lo or hi
+ *
+ * Rules are for fixing what a spreadsheet did to a file on its way out
+ * of it, so anything they cannot make sense of (clamp on something
+ * which is not a number, say) is left alone for the parser to report
+ * as it would have anyway. Whitespace at either end of a field is
+ * always trimmed, rules or not. */
+#[derive(Debug, Clone, PartialEq)]
+pub enum Rule {
+  StripCommas,
+  StripPercent,
+  StripSpaces,
+  Strip(String),
+  Clamp(f64, f64)
+}
+
+impl Rule {
+  pub fn apply(&self, field: &str) -> String {
+    match *self {
+      Rule::StripCommas => field.replace(',', ""),
+      Rule::StripPercent => field.replace('%', ""),
+      Rule::StripSpaces => field.chars().filter(|c| !c.is_whitespace()).collect(),
+      Rule::Strip(ref chars) => field.chars().filter(|c| !chars.contains(*c)).collect(),
+      Rule::Clamp(lo, hi) => match field.trim().parse::<f64>() {
+        Ok(x) if x < lo => lo.to_string(),
+        Ok(x) if x > hi => hi.to_string(),
+        _ => field.to_string()
+      }
+    }
+  }
+}
+
+impl FromStr for Rule {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Rule, String> {
+    let (name, argument) = match s.find('(') {
+      Some(i) if s.ends_with(')') => (&s[..i], Some(&s[i + 1..s.len() - 1])),
+      Some(_) => return Err(format!("{:?} is missing a closing bracket", s)),
+      None => (s, None)
+    };
+    match (name.trim(), argument) {
+      ("strip_commas", None) => Ok(Rule::StripCommas),
+      ("strip_percent", None) => Ok(Rule::StripPercent),
+      ("strip_spaces", None) => Ok(Rule::StripSpaces),
+      ("strip", Some(chars)) if !chars.is_empty() => Ok(Rule::Strip(chars.to_string())),
+      ("clamp", Some(bounds)) => {
+        let bounds = bounds.split(',').map(|b| b.trim().parse::<f64>()).collect::<Vec<_>>();
+        match bounds[..] {
+          [Ok(lo), Ok(hi)] if lo <= hi => Ok(Rule::Clamp(lo, hi)),
+          _ => Err(format!("{:?} needs two numbers, the lower one first, like clamp(0, 200)", s))
+        }
+      },
+      ("strip", _) => Err("strip needs the characters to strip, like strip(*)".to_string()),
+      _ => Err(format!("Unknown rule {:?}, expected strip_commas, strip_percent, strip_spaces, strip(chars) or clamp(lo, hi)", s))
+    }
+  }
+}
+
+/* The rules for each field of a line, by the field's position */
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Coercions {
+  fields: Vec<(usize, Vec<Rule>)>
+}
+
+/* The position of a field named in a rule */
+fn position(field: &str) -> Result<usize, String> {
+  match field {
+    "name" => Ok(0),
+    "runs" => Ok(1),
+    "average" => Ok(2),
+    n => match n.parse::<usize>() {
+      Ok(n) if n > 0 => Ok(n - 1),
+      _ => Err(format!("Unknown field {:?}, expected name, runs, average or an item number", n))
+    }
+  }
+}
+
+/* Splits a field's rules apart at spaces and commas, except for the
+ * commas between the brackets of clamp(0, 200) */
+fn rules(s: &str) -> Result<Vec<Rule>, String> {
+  let mut rules = Vec::new();
+  let mut rule = String::new();
+  let mut depth = 0;
+  for c in s.chars().chain(Some(' ')) {
+    match c {
+      '(' => depth += 1,
+      ')' => depth -= 1,
+      _ => ()
+    }
+    if depth == 0 && (c == ',' || c.is_whitespace()) {
+      if !rule.is_empty() {
+        rules.push(rule.parse::<Rule>()?);
+        rule.clear();
+      }
+    } else {
+      rule.push(c);
+    }
+  }
+  Ok(rules)
+}
+
+impl FromStr for Coercions {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Coercions, String> {
+    let fields = s.split(';').filter(|part| !part.trim().is_empty()).map(|part| {
+      let (field, rules_text) = part.split_once(':')
+        .ok_or_else(|| format!("{:?} should be a field and its rules, like runs: strip_commas", part.trim()))?;
+      let rules = rules(rules_text)?;
+      if rules.is_empty() {
+        return Err(format!("{:?} has no rules", field.trim()));
+      }
+      Ok((position(field.trim())?, rules))
+    }).collect::<Result<Vec<_>, String>>()?;
+    Ok(Coercions { fields })
+  }
+}
+
+/* The fields of a comma separated line, trimmed, where a field in
+ * double quotes can have commas in it, as spreadsheets write "1,843".
+ * The quotes are taken off, and a doubled one inside them is a quote. */
+pub fn fields(line: &str) -> Vec<String> {
+  let mut fields = vec![String::new()];
+  let mut quoted = false;
+  let mut chars = line.chars().peekable();
+  while let Some(c) = chars.next() {
+    let field = fields.last_mut().unwrap();
+    match c {
+      '"' if quoted && chars.peek() == Some(&'"') => {
+        field.push('"');
+        chars.next();
+      },
+      '"' if quoted => quoted = false,
+      '"' if field.trim().is_empty() => {
+        quoted = true;
+        field.clear();
+      },
+      ',' if !quoted => fields.push(String::new()),
+      c => field.push(c)
+    }
+  }
+  fields.iter().map(|f| f.trim().to_string()).collect()
+}
+
+impl Coercions {
+  /* Whether there are any rules at all */
+  pub fn is_empty(&self) -> bool {
+    self.fields.is_empty()
+  }
+
+  /* A line with each field cleaned by its rules, written back out the
+   * way the parsers expect. Blank lines stay blank. */
+  pub fn apply(&self, line: &str) -> String {
+    if line.trim().is_empty() {
+      return line.to_string();
+    }
+    let mut fields = fields(line);
+    for &(i, ref rules) in &self.fields {
+      if let Some(field) = fields.get_mut(i) {
+        *field = rules.iter().fold(field.clone(), |f, rule| rule.apply(&f)).trim().to_string();
+      }
+    }
+    /* A comma the rules left in stays quoted, so it does not turn into
+     * a field of its own */
+    fields.iter().map(|f| if f.contains(',') { format!("\"{}\"", f.replace('"', "\"\"")) } else { f.clone() })
+      .collect::<Vec<String>>()
+      .join(", ")
+  }
+}
@@ -0,0 +1,59 @@
+use record::{Record, Value};
+
+/* Which side of a head-to-head came out on top for a single field */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Winner {
+  Left,
+  Right,
+  Tie
+}
+
+/* One row of a head-to-head comparison. Text fields like the surname
+ * have no winner and no percentage difference, so those are Options. */
+#[derive(Debug, Clone)]
+pub struct FieldComparison<'a> {
+  pub field: &'static str,
+  pub left: Option<Value<'a>>,
+  pub right: Option<Value<'a>>,
+  pub winner: Option<Winner>,
+  /* How much bigger (or smaller) the left value is, as a percentage
+   * of the right value */
+  pub percent_difference: Option<f32>
+}
+
+/* Compares two records field by field. For numeric fields, the bigger
+ * number wins, which is right for batting stats but the wrong way
+ * around for something like a bowling average. */
+pub fn compare<'a, R: Record>(left: &'a R, right: &'a R) -> Vec<FieldComparison<'a>> {
+  left.field_names().iter().map(|&name| {
+    let l = left.field(name);
+    let r = right.field(name);
+
+    let numbers = match (l.and_then(|v| v.as_f32()), r.and_then(|v| v.as_f32())) {
+      (Some(a), Some(b)) => Some((a, b)),
+      _ => None
+    };
+
+    FieldComparison {
+      field: name,
+      left: l,
+      right: r,
+      winner: numbers.map(|(a, b)| {
+        if a > b {
+          Winner::Left
+        } else if b > a {
+          Winner::Right
+        } else {
+          Winner::Tie
+        }
+      }),
+      percent_difference: numbers.and_then(|(a, b)| {
+        if b == 0.0 {
+          None
+        } else {
+          Some((a - b) / b * 100.0)
+        }
+      })
+    }
+  }).collect()
+}
@@ -0,0 +1,92 @@
+use std::ops::Range;
+
+use batsman::Batsman;
+use error::ParseError;
+use numeric::Average;
+
+/* Where one batsman's names are in the text, and their numbers */
+#[derive(Debug, Clone)]
+struct Row {
+  initials: Range<usize>,
+  surname: Range<usize>,
+  runs: u32,
+  average: Average
+}
+
+/* A parsed batting file which owns its text. A Batsman borrows its
+ * names from the line it was parsed from, which is what makes parsing
+ * cheap, but it also means the text has to be kept somewhere that
+ * outlives every Batsman, and the two cannot simply be put in one
+ * struct (it would borrow from itself, and moving it would leave the
+ * names pointing at where the text used to be).
+ *
+ * So instead the dataset keeps the text, and for each batsman just
+ * where in it their names are. get and iter put Batsmen back together
+ * from those, borrowing from the dataset, which costs nothing. The
+ * whole file is one allocation for the text and one for the rows, and
+ * the dataset can be moved around, stored and sent between threads
+ * like any other value. */
+#[derive(Debug, Clone)]
+pub struct ParsedDataset {
+  text: String,
+  rows: Vec<Row>
+}
+
+/* Where s is in text. s has to be a slice of text, unless it is
+ * empty (like the initials of someone who has none), when it does not
+ * matter. */
+fn span(text: &str, s: &str) -> Range<usize> {
+  if s.is_empty() {
+    return 0..0;
+  }
+  let start = s.as_ptr() as usize - text.as_ptr() as usize;
+  start..start + s.len()
+}
+
+impl ParsedDataset {
+  /* Parses every line of text. A bad line is returned along with its
+   * line number, counting from 1. */
+  pub fn parse(text: String) -> Result<ParsedDataset, (usize, ParseError)> {
+    let rows = text.lines().enumerate().map(|(i, l)| {
+      let b = Batsman::parse(l).map_err(|e| (i + 1, e))?;
+      Ok(Row {
+        initials: span(&text, b.initials),
+        surname: span(&text, b.surname),
+        runs: b.runs,
+        average: b.average
+      })
+    }).collect::<Result<Vec<Row>, (usize, ParseError)>>()?;
+
+    Ok(ParsedDataset { text, rows })
+  }
+
+  fn batsman(&self, row: &Row) -> Batsman<'_> {
+    Batsman {
+      initials: &self.text[row.initials.clone()],
+      surname: &self.text[row.surname.clone()],
+      runs: row.runs,
+      average: row.average
+    }
+  }
+
+  pub fn get(&self, index: usize) -> Option<Batsman<'_>> {
+    self.rows.get(index).map(|row| self.batsman(row))
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = Batsman<'_>> {
+    self.rows.iter().map(move |row| self.batsman(row))
+  }
+
+  pub fn len(&self) -> usize {
+    self.rows.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.rows.is_empty()
+  }
+
+  /* The text the batsmen were parsed from */
+  pub fn text(&self) -> &str {
+    &self.text
+  }
+}
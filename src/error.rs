@@ -0,0 +1,116 @@
+use alloc::string::String;
+use core::error::Error;
+use core::fmt;
+
+/* Everything that can go wrong turning a line of text into a record.
+ * Returning one of these instead of panicking lets the caller decide
+ * whether a bad line should stop the program, be skipped, or be
+ * reported alongside every other bad line. */
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+  /* The line ran out of comma separated items before this field */
+  MissingField(&'static str),
+  /* The name did not have both initials and a surname */
+  BadName(String),
+  /* A field which should have been a number was not */
+  BadNumber {
+    field: &'static str,
+    expected: &'static str,
+    value: String
+  },
+  /* The line could not be read at all, like a line of broken JSON */
+  Malformed(String),
+  /* Every field was there, but together they do not make a valid record */
+  Invalid(ValidationError)
+}
+
+impl fmt::Display for ParseError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      ParseError::MissingField(field) =>
+        write!(f, "Expected a {} field", field),
+      ParseError::BadName(ref name) =>
+        write!(f, "Expected a name like \"AN Cook\", got {:?}", name),
+      ParseError::BadNumber { field, expected, ref value } =>
+        write!(f, "Expected {} to be an {}, got {:?}", field, expected, value),
+      ParseError::Malformed(ref message) =>
+        write!(f, "{}", message),
+      ParseError::Invalid(ref e) =>
+        write!(f, "{}", e)
+    }
+  }
+}
+
+/* Implementing Error means ParseError can be boxed up with any other
+ * kind of error, and works with the ? operator in functions returning
+ * Box<Error> */
+impl Error for ParseError {
+}
+
+/* Everything that can be wrong with a record, however it was made.
+ * Parsing checks that the text has the right shape and then hands
+ * over to the same checks that building a record in code goes
+ * through, so both ways of making one agree on what is valid. */
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationError {
+  /* A field which has no sensible default was never given */
+  MissingField(&'static str),
+  /* A name was empty */
+  EmptyName(&'static str),
+  /* A name had a space or comma in it, so it could not be written out
+   * and read back in again */
+  BadName {
+    field: &'static str,
+    value: String
+  },
+  /* A number was negative, infinite or not a number at all */
+  BadNumber {
+    field: &'static str,
+    value: f32
+  }
+}
+
+impl fmt::Display for ValidationError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      ValidationError::MissingField(field) =>
+        write!(f, "No {} was given", field),
+      ValidationError::EmptyName(field) =>
+        write!(f, "The {} cannot be empty", field),
+      ValidationError::BadName { field, ref value } =>
+        write!(f, "The {} cannot contain spaces or commas, got {:?}", field, value),
+      ValidationError::BadNumber { field, value } =>
+        write!(f, "Expected {} to be a finite number of at least zero, got {}", field, value)
+    }
+  }
+}
+
+impl Error for ValidationError {
+}
+
+/* Everything that can go wrong putting a batting order together */
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrderError {
+  /* All eleven places are taken */
+  Full,
+  /* This player is already in the order */
+  Duplicate(String),
+  /* Positions go from 1 to however many players there are */
+  BadPosition(usize)
+}
+
+impl fmt::Display for OrderError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      OrderError::Full =>
+        write!(f, "A batting order has at most 11 players"),
+      OrderError::Duplicate(ref name) =>
+        write!(f, "{} is already in the batting order", name),
+      OrderError::BadPosition(position) =>
+        write!(f, "There is no one batting at number {}", position)
+    }
+  }
+}
+
+impl Error for OrderError {
+}
@@ -0,0 +1,261 @@
+use std::str::FromStr;
+
+use record::{Record, Value};
+
+/* A little arithmetic language over the numeric fields of a record,
+ * like "0.6*average + 0.4*runs/100". It has numbers, field names,
+ * + - * /, unary minus and brackets, with the usual precedence. It is
+ * parsed once, up front, and then evaluated against each record. */
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+  Number(f32),
+  Field(String),
+  Neg(Box<Expr>),
+  Binary(Box<Expr>, Op, Box<Expr>)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Op {
+  Add,
+  Sub,
+  Mul,
+  Div
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+  Number(f32),
+  Name(String),
+  Op(Op),
+  Open,
+  Close
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token>, String> {
+  let mut tokens = Vec::new();
+  let mut chars = s.chars().peekable();
+  while let Some(&c) = chars.peek() {
+    if c.is_whitespace() {
+      chars.next();
+      continue;
+    }
+    if c.is_ascii_digit() || c == '.' {
+      let mut number = String::new();
+      while let Some(&d) = chars.peek().filter(|d| d.is_ascii_digit() || **d == '.') {
+        number.push(d);
+        chars.next();
+      }
+      tokens.push(Token::Number(number.parse().map_err(|_| format!("Bad number {:?}", number))?));
+      continue;
+    }
+    if c.is_alphabetic() || c == '_' {
+      let mut name = String::new();
+      while let Some(&d) = chars.peek().filter(|d| d.is_alphanumeric() || **d == '_') {
+        name.push(d);
+        chars.next();
+      }
+      tokens.push(Token::Name(name));
+      continue;
+    }
+    tokens.push(match c {
+      '+' => Token::Op(Op::Add),
+      '-' => Token::Op(Op::Sub),
+      '*' => Token::Op(Op::Mul),
+      '/' => Token::Op(Op::Div),
+      '(' => Token::Open,
+      ')' => Token::Close,
+      other => return Err(format!("Unexpected {:?}", other))
+    });
+    chars.next();
+  }
+  Ok(tokens)
+}
+
+/* A recursive descent parser: each function parses one level of
+ * precedence and calls down to the next one for its operands.
+ *
+ *   sum     = product (("+" | "-") product)*
+ *   product = unary (("*" | "/") unary)*
+ *   unary   = "-" unary | atom
+ *   atom    = number | name | "(" sum ")"
+ */
+struct Parser {
+  tokens: Vec<Token>,
+  position: usize,
+  depth: usize
+}
+
+/* Limits on how big an expression can be. Parsing, evaluating and even
+ * dropping an expression recurse once per level, so without these a
+ * long enough one (like a few thousand "(") overflows the stack rather
+ * than giving an error. Nobody writes a score that big by hand. */
+const MAX_TOKENS: usize = 1000;
+const MAX_DEPTH: usize = 64;
+
+impl Parser {
+  fn peek(&self) -> Option<&Token> {
+    self.tokens.get(self.position)
+  }
+
+  fn next(&mut self) -> Option<Token> {
+    let t = self.tokens.get(self.position).cloned();
+    self.position += 1;
+    t
+  }
+
+  fn binary<F>(&mut self, ops: &[Op], operand: F) -> Result<Expr, String>
+    where F: Fn(&mut Parser) -> Result<Expr, String>
+  {
+    let mut lhs = operand(self)?;
+    while let Some(&Token::Op(op)) = self.peek() {
+      if !ops.contains(&op) {
+        break;
+      }
+      self.next();
+      lhs = Expr::Binary(Box::new(lhs), op, Box::new(operand(self)?));
+    }
+    Ok(lhs)
+  }
+
+  fn sum(&mut self) -> Result<Expr, String> {
+    self.binary(&[Op::Add, Op::Sub], Parser::product)
+  }
+
+  fn product(&mut self) -> Result<Expr, String> {
+    self.binary(&[Op::Mul, Op::Div], Parser::unary)
+  }
+
+  /* Counts a level of brackets or minus signs on the way into it */
+  fn nested<F>(&mut self, parse: F) -> Result<Expr, String>
+    where F: Fn(&mut Parser) -> Result<Expr, String>
+  {
+    if self.depth == MAX_DEPTH {
+      return Err(format!("Expressions can only be nested {} deep", MAX_DEPTH));
+    }
+    self.depth += 1;
+    let e = parse(self);
+    self.depth -= 1;
+    e
+  }
+
+  fn unary(&mut self) -> Result<Expr, String> {
+    if self.peek() == Some(&Token::Op(Op::Sub)) {
+      self.next();
+      return Ok(Expr::Neg(Box::new(self.nested(Parser::unary)?)));
+    }
+    self.atom()
+  }
+
+  fn atom(&mut self) -> Result<Expr, String> {
+    match self.next() {
+      Some(Token::Number(n)) => Ok(Expr::Number(n)),
+      Some(Token::Name(name)) => Ok(Expr::Field(name)),
+      Some(Token::Open) => {
+        let inner = self.nested(Parser::sum)?;
+        match self.next() {
+          Some(Token::Close) => Ok(inner),
+          _ => Err("Missing )".to_string())
+        }
+      },
+      Some(t) => Err(format!("Unexpected {:?}", t)),
+      None => Err("Unexpected end of expression".to_string())
+    }
+  }
+}
+
+impl FromStr for Expr {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Expr, String> {
+    let tokens = tokenize(s)?;
+    if tokens.len() > MAX_TOKENS {
+      return Err(format!("Expressions can only be {} items long", MAX_TOKENS));
+    }
+    let mut parser = Parser { tokens, position: 0, depth: 0 };
+    let expr = parser.sum()?;
+    match parser.peek() {
+      None => Ok(expr),
+      Some(t) => Err(format!("Unexpected {:?} after the expression", t))
+    }
+  }
+}
+
+impl Expr {
+  /* Works the expression out for one record. Fields have to be
+   * numbers; dividing by zero gives infinity, as it does for floats. */
+  pub fn eval<R: Record>(&self, record: &R) -> Result<f32, String> {
+    self.eval_with(&|name| record.field(name))
+  }
+
+  /* The same, but with the fields looked up by a function, for rows
+   * which are not Records, like those of an output Table */
+  pub fn eval_with<'v, F>(&self, lookup: &F) -> Result<f32, String>
+    where F: Fn(&str) -> Option<Value<'v>>
+  {
+    match *self {
+      Expr::Number(n) => Ok(n),
+      Expr::Field(ref name) => match lookup(name) {
+        Some(Value::Text(_)) => Err(format!("{} is not a number", name)),
+        Some(v) => Ok(v.as_f32().unwrap_or(0.0)),
+        None => Err(format!("There is no field called {}", name))
+      },
+      Expr::Neg(ref e) => Ok(-e.eval_with(lookup)?),
+      Expr::Binary(ref lhs, op, ref rhs) => {
+        let (l, r) = (lhs.eval_with(lookup)?, rhs.eval_with(lookup)?);
+        Ok(match op {
+          Op::Add => l + r,
+          Op::Sub => l - r,
+          Op::Mul => l * r,
+          Op::Div => l / r
+        })
+      }
+    }
+  }
+}
+
+/* A new column defined by an expression, written "name = expression"
+ * as in "dismissals = runs / average" */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Derivation {
+  pub name: String,
+  pub expr: Expr
+}
+
+impl FromStr for Derivation {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Derivation, String> {
+    let (name, expr) = match s.find('=') {
+      Some(i) => (s[..i].trim(), &s[i + 1..]),
+      None => return Err(format!("Expected \"name = expression\", got {:?}", s))
+    };
+    let valid = name.chars().next().is_some_and(|c| c.is_alphabetic() || c == '_') &&
+      name.chars().all(|c| c.is_alphanumeric() || c == '_');
+    if !valid {
+      return Err(format!("{:?} is not a column name", name));
+    }
+    Ok(Derivation { name: name.to_string(), expr: expr.parse()? })
+  }
+}
+
+/* A record with a score worked out for it, which can be sorted and
+ * ranked on like any other field. The score is not one of the
+ * field_names, so it is not written out unless asked for. */
+#[derive(Debug, Clone)]
+pub struct Scored<R> {
+  pub record: R,
+  pub score: Option<f32>
+}
+
+impl<R: Record> Record for Scored<R> {
+  fn field_names(&self) -> &'static [&'static str] {
+    self.record.field_names()
+  }
+
+  fn field(&self, name: &str) -> Option<Value<'_>> {
+    match name {
+      "score" => self.score.map(Value::Float),
+      _ => self.record.field(name)
+    }
+  }
+}
@@ -0,0 +1,209 @@
+use std::cmp::Ordering;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::mem;
+use std::path::{Path, PathBuf};
+
+/* Sorting lines of text which might not all fit in memory. Lines are
+ * pushed in one at a time and kept until they take up more than the
+ * memory budget; then that chunk is sorted and written out to a "run"
+ * file in dir, and a new chunk is started. finish merges the runs back
+ * together, reading one line at a time from each, so however big the
+ * input, only about a budget's worth of it is ever in memory.
+ *
+ * Lines are compared by a key worked out from each one (for a batting
+ * file, the batsman on it), since working it out again for every
+ * comparison would be most of the work of the sort. The key is given
+ * with each line pushed, and key works it out again for lines read
+ * back from a run.
+ *
+ * If everything fits in the budget nothing is written to disk at all,
+ * unless spill_all was asked for.
+ *
+ * Equal lines keep the order they were pushed in: each chunk is sorted
+ * with a stable sort, and when two runs have equal lines at the front
+ * the earlier run's goes first. */
+pub struct ExternalSort<K, F, C> {
+  dir: PathBuf,
+  budget: usize,
+  spill_all: bool,
+  key: F,
+  cmp: C,
+  chunk: Vec<(K, String)>,
+  size: usize,
+  runs: Vec<PathBuf>
+}
+
+impl<K, F, C> ExternalSort<K, F, C>
+  where F: Fn(&str) -> K,
+        C: Fn(&K, &K) -> Ordering
+{
+  /* budget is in bytes. The run files go in dir, which has to exist. */
+  pub fn new(dir: &Path, budget: usize, key: F, cmp: C) -> ExternalSort<K, F, C> {
+    ExternalSort {
+      dir: dir.to_path_buf(),
+      budget,
+      spill_all: false,
+      key,
+      cmp,
+      chunk: Vec::new(),
+      size: 0,
+      runs: Vec::new()
+    }
+  }
+
+  /* Writes every chunk to disk, even one that would fit in memory */
+  pub fn spill_all(mut self, spill_all: bool) -> ExternalSort<K, F, C> {
+    self.spill_all = spill_all;
+    self
+  }
+
+  /* How many runs have been written to disk so far */
+  pub fn runs(&self) -> usize {
+    self.runs.len()
+  }
+
+  /* key is what key would give for the line */
+  pub fn push(&mut self, key: K, line: String) -> io::Result<()> {
+    /* A String costs its text plus the pointer, length and capacity.
+     * The key gets as much again as the text, for any it holds. */
+    self.size += 2 * line.len() + mem::size_of::<(K, String)>();
+    self.chunk.push((key, line));
+    if self.size >= self.budget {
+      self.spill()?;
+    }
+    Ok(())
+  }
+
+  /* Sorts the chunk in memory and writes it out as a new run */
+  fn spill(&mut self) -> io::Result<()> {
+    let cmp = &self.cmp;
+    let mut chunk = mem::take(&mut self.chunk);
+    chunk.sort_by(|l, r| cmp(&l.0, &r.0));
+    self.size = 0;
+
+    let path = self.dir.join(format!("run-{:06}", self.runs.len()));
+    let mut out = BufWriter::new(File::create(&path)?);
+    for (_, line) in chunk {
+      writeln!(out, "{}", line)?;
+    }
+    out.flush()?;
+    self.runs.push(path);
+    Ok(())
+  }
+
+  /* Writes every line pushed, in order, to out, and deletes the runs.
+   * What is returned is how the merge went: a run which cannot be
+   * deleted is left in dir for whoever made it to tidy up (and warn
+   * about, as batsmen sort does), rather than hiding whether the lines
+   * were all written. */
+  pub fn finish<W: Write>(mut self, out: &mut W) -> io::Result<()> {
+    if self.runs.is_empty() && !self.spill_all {
+      let cmp = &self.cmp;
+      self.chunk.sort_by(|l, r| cmp(&l.0, &r.0));
+      for (_, line) in &self.chunk {
+        writeln!(out, "{}", line)?;
+      }
+      return Ok(());
+    }
+
+    if !self.chunk.is_empty() {
+      self.spill()?;
+    }
+    let result = self.merge(out);
+    for run in &self.runs {
+      let _ = fs::remove_file(run);
+    }
+    result
+  }
+
+  /* A k-way merge. heads holds the next line of each run with its
+   * key, and heap the numbers of the runs which still have one,
+   * arranged so the run with the smallest line is always at the top. */
+  fn merge<W: Write>(&self, out: &mut W) -> io::Result<()> {
+    let keyed = |line: Option<String>| line.map(|l| ((self.key)(&l), l));
+    let mut readers = Vec::new();
+    let mut heads = Vec::new();
+    for run in &self.runs {
+      let mut lines = BufReader::new(File::open(run)?).lines();
+      heads.push(keyed(lines.next().transpose()?));
+      readers.push(lines);
+    }
+
+    let mut heap = Merge {
+      heads,
+      heap: Vec::new(),
+      cmp: &self.cmp
+    };
+    for run in 0..readers.len() {
+      if heap.heads[run].is_some() {
+        heap.push(run);
+      }
+    }
+
+    while let Some(run) = heap.pop() {
+      let next = keyed(readers[run].next().transpose()?);
+      if let Some((_, line)) = mem::replace(&mut heap.heads[run], next) {
+        writeln!(out, "{}", line)?;
+      }
+      if heap.heads[run].is_some() {
+        heap.push(run);
+      }
+    }
+    Ok(())
+  }
+}
+
+/* A binary heap of run numbers, ordered by the line at the front of
+ * each run and then by the run number, so that ties come out of the
+ * earlier run first */
+struct Merge<'c, K, C: 'c> {
+  heads: Vec<Option<(K, String)>>,
+  heap: Vec<usize>,
+  cmp: &'c C
+}
+
+impl<'c, K, C> Merge<'c, K, C>
+  where C: Fn(&K, &K) -> Ordering
+{
+  fn less(&self, a: usize, b: usize) -> bool {
+    let (ra, rb) = (self.heap[a], self.heap[b]);
+    let order = match (&self.heads[ra], &self.heads[rb]) {
+      (Some(la), Some(lb)) => (self.cmp)(&la.0, &lb.0),
+      _ => Ordering::Equal
+    };
+    order.then(ra.cmp(&rb)) == Ordering::Less
+  }
+
+  fn push(&mut self, run: usize) {
+    self.heap.push(run);
+    let mut i = self.heap.len() - 1;
+    while i > 0 && self.less(i, (i - 1) / 2) {
+      self.heap.swap(i, (i - 1) / 2);
+      i = (i - 1) / 2;
+    }
+  }
+
+  fn pop(&mut self) -> Option<usize> {
+    if self.heap.is_empty() {
+      return None;
+    }
+    let top = self.heap.swap_remove(0);
+    let mut i = 0;
+    loop {
+      let (left, right) = (2 * i + 1, 2 * i + 2);
+      let mut smallest = i;
+      if left < self.heap.len() && self.less(left, smallest) {
+        smallest = left;
+      }
+      if right < self.heap.len() && self.less(right, smallest) {
+        smallest = right;
+      }
+      if smallest == i {
+        return Some(top);
+      }
+      self.heap.swap(i, smallest);
+      i = smallest;
+    }
+  }
+}
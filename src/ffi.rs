@@ -0,0 +1,179 @@
+/* A C interface to the parser, so that scoreboard software written in
+ * C or C++ can use it. include/batsmen.h is the matching header, which
+ * is generated from this file with cbindgen (see cbindgen.toml).
+ *
+ * The rules for callers, which every function here relies on:
+ *
+ * - Every pointer passed in is either NULL or something this library
+ *   handed out and which has not been freed yet. Text is NUL
+ *   terminated UTF-8.
+ * - A BatsmenList belongs to the caller until it is passed to
+ *   batsmen_free, and the strings inside a CBatsman belong to the list
+ *   they came from, so they stop being valid once it is freed.
+ * - Error messages belong to the caller and are freed with
+ *   batsmen_free_string.
+ */
+#![allow(clippy::missing_safety_doc)]
+
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::ptr;
+
+use batsman::Batsman;
+use numeric::average_f32;
+use record::{Record, Value};
+
+/* A batsman which owns its text, since the C side has no idea about
+ * Rust lifetimes and the input text is not ours to keep */
+struct Entry {
+  initials: CString,
+  surname: CString,
+  runs: u32,
+  average: f32
+}
+
+impl Record for Entry {
+  fn field_names(&self) -> &'static [&'static str] {
+    &["initials", "surname", "runs", "average"]
+  }
+
+  fn field(&self, name: &str) -> Option<Value<'_>> {
+    match name {
+      "initials" => self.initials.to_str().ok().map(Value::Text),
+      "surname" => self.surname.to_str().ok().map(Value::Text),
+      "runs" => Some(Value::Integer(self.runs)),
+      "average" => Some(Value::Float(self.average)),
+      _ => None
+    }
+  }
+}
+
+/* Opaque to C: only ever seen through a pointer */
+pub struct BatsmenList {
+  entries: Vec<Entry>
+}
+
+/* One batsman, as handed to C. The strings are owned by the list. */
+#[repr(C)]
+pub struct CBatsman {
+  pub initials: *const c_char,
+  pub surname: *const c_char,
+  pub runs: u32,
+  pub average: f32
+}
+
+/* Hands a message over to C, or does nothing if there is nowhere to
+ * put it */
+unsafe fn set_error(error: *mut *mut c_char, message: String) {
+  if !error.is_null() {
+    let message = CString::new(message.replace('\0', "")).unwrap_or_default();
+    *error = message.into_raw();
+  }
+}
+
+/* Copies a line's text into an Entry. Names never contain a NUL since
+ * they came from a C string in the first place. */
+fn entry(b: &Batsman) -> Entry {
+  Entry {
+    initials: CString::new(b.initials).unwrap_or_default(),
+    surname: CString::new(b.surname).unwrap_or_default(),
+    runs: b.runs,
+    average: average_f32(b.average)
+  }
+}
+
+/* Parses the text of a whole batting file. Returns NULL if it cannot,
+ * and if error is not NULL, points it at a message saying why. */
+#[no_mangle]
+pub unsafe extern "C" fn batsmen_parse(text: *const c_char, error: *mut *mut c_char) -> *mut BatsmenList {
+  if text.is_null() {
+    set_error(error, "text is NULL".to_string());
+    return ptr::null_mut();
+  }
+  let text = match CStr::from_ptr(text).to_str() {
+    Ok(t) => t,
+    Err(e) => {
+      set_error(error, format!("text is not UTF-8: {}", e));
+      return ptr::null_mut();
+    }
+  };
+
+  let mut entries = Vec::new();
+  for (i, l) in text.lines().enumerate() {
+    match Batsman::parse(l) {
+      Ok(b) => entries.push(entry(&b)),
+      Err(e) => {
+        set_error(error, format!("Line {}: {}", i + 1, e));
+        return ptr::null_mut();
+      }
+    }
+  }
+
+  Box::into_raw(Box::new(BatsmenList { entries }))
+}
+
+/* How many batsmen are in the list. NULL counts as empty. */
+#[no_mangle]
+pub unsafe extern "C" fn batsmen_len(list: *const BatsmenList) -> usize {
+  match list.as_ref() {
+    Some(l) => l.entries.len(),
+    None => 0
+  }
+}
+
+/* The batsman at index. Out of range (or a NULL list) gives one with
+ * NULL names and zeroes everywhere else. */
+#[no_mangle]
+pub unsafe extern "C" fn batsmen_get(list: *const BatsmenList, index: usize) -> CBatsman {
+  match list.as_ref().and_then(|l| l.entries.get(index)) {
+    Some(e) => CBatsman {
+      initials: e.initials.as_ptr(),
+      surname: e.surname.as_ptr(),
+      runs: e.runs,
+      average: e.average
+    },
+    None => CBatsman {
+      initials: ptr::null(),
+      surname: ptr::null(),
+      runs: 0,
+      average: 0.0
+    }
+  }
+}
+
+/* Sorts the list in place on a field ("initials", "surname", "runs" or
+ * "average"). Returns 0 on success and -1 for an unknown field. */
+#[no_mangle]
+pub unsafe extern "C" fn batsmen_sort(list: *mut BatsmenList, field: *const c_char, descending: bool) -> i32 {
+  let list = match list.as_mut() {
+    Some(l) => l,
+    None => return -1
+  };
+  let field = match field.as_ref().map(|f| CStr::from_ptr(f).to_str()) {
+    Some(Ok(f)) if ["initials", "surname", "runs", "average"].contains(&f) => f,
+    _ => return -1
+  };
+
+  if descending {
+    list.entries.sort_by(|lhs, rhs| rhs.cmp_by(lhs, field));
+  } else {
+    list.entries.sort_by(|lhs, rhs| lhs.cmp_by(rhs, field));
+  }
+  0
+}
+
+/* Frees a list from batsmen_parse. NULL is ignored. */
+#[no_mangle]
+pub unsafe extern "C" fn batsmen_free(list: *mut BatsmenList) {
+  if !list.is_null() {
+    drop(Box::from_raw(list));
+  }
+}
+
+/* Frees an error message. NULL is ignored. */
+#[no_mangle]
+pub unsafe extern "C" fn batsmen_free_string(s: *mut c_char) {
+  if !s.is_null() {
+    drop(CString::from_raw(s));
+  }
+}
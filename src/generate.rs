@@ -0,0 +1,114 @@
+use anonymize::made_up_name;
+use rng::Rng;
+use simulate::Distribution;
+
+/* The years generated careers fall between, the first Test to the
+ * last season there are real files for */
+const FIRST_SEASON: u32 = 1877;
+const LAST_SEASON: u32 = 2025;
+
+/* What a typical career looks like: how many times a batsman gets out
+ * on average, and the spread of averages around the mean one */
+const MEAN_DISMISSALS: f32 = 40.0;
+const MEAN_AVERAGE: f64 = 28.0;
+const SPREAD: f64 = 12.0;
+
+/* The ways a corrupted row is broken, one for each way a line can fail
+ * to parse */
+const CORRUPTIONS: usize = 5;
+
+/* Made up batting files, as big as needed and the same every time for
+ * the same seed. Each line looks like one of ours,
+ *
+ *   KT Ventalmar, 1843, 36.14, 1, 1994, 2003
+ *
+ * with a made up name (see anonymize.rs), a career of a plausible
+ * length, an average around the usual ones and runs to match it, so
+ * the runs and average always agree with each other. The fourth field
+ * is the line number, and the last two the first and last seasons.
+ *
+ *   Generator::new(Rng::new(7)).take(1000)
+ *
+ * is a thousand of them. With corrupt(fraction), that fraction of the
+ * lines are broken in one of the ways real files are, for trying out
+ * what happens to bad lines. Which lines get broken is picked with a
+ * second Rng, so the rest are the same lines the seed gives without
+ * corrupt. */
+#[derive(Debug, Clone)]
+pub struct Generator {
+  rng: Rng,
+  breaker: Rng,
+  corrupt: f64,
+  line: usize
+}
+
+impl Generator {
+  pub fn new(rng: Rng) -> Generator {
+    let breaker = Rng::new(rng.clone().next_u64());
+    Generator { rng, breaker, corrupt: 0.0, line: 0 }
+  }
+
+  /* Breaks this fraction of the lines, between 0 (none, the default)
+   * and 1 (every one) */
+  pub fn corrupt(mut self, fraction: f64) -> Generator {
+    self.corrupt = fraction.clamp(0.0, 1.0);
+    self
+  }
+
+  /* A normally distributed average, by the Box-Muller transform, which
+   * is never below zero */
+  fn average(&mut self) -> f64 {
+    let u = 1.0 - self.rng.next_f64();
+    let z = (-2.0 * u.ln()).sqrt() * (2.0 * std::f64::consts::PI * self.rng.next_f64()).cos();
+    (MEAN_AVERAGE + SPREAD * z).max(0.0)
+  }
+
+  /* A good line. Runs are worked out from the average and how often
+   * they got out, and the average is then worked out again from the
+   * runs, as it would be in a real file. */
+  fn good(&mut self) -> String {
+    let name = made_up_name(&mut self.rng);
+    let dismissals = 1 + Distribution::Exponential.score(MEAN_DISMISSALS, &mut self.rng);
+    let runs = (self.average() * dismissals as f64).round() as u32;
+    let average = runs as f64 / dismissals as f64;
+    let debut = FIRST_SEASON + self.rng.below((LAST_SEASON - FIRST_SEASON + 1) as usize) as u32;
+    let last = (debut + dismissals / 8).min(LAST_SEASON);
+    format!("{}, {}, {:.2}, {}, {}, {}", name, runs, average, self.line, debut, last)
+  }
+
+  /* A good line broken in one of the ways files get broken */
+  fn broken(&mut self, good: String) -> String {
+    let fields = good.split(", ").collect::<Vec<&str>>();
+    let runs = fields[1].parse::<u32>().unwrap_or(0);
+    match self.breaker.below(CORRUPTIONS) {
+      /* A thousands separator, quoted as a spreadsheet would, which
+       * makes runs not a number */
+      0 if runs >= 1000 =>
+        format!("{}, \"{},{:03}\", {}", fields[0], runs / 1000, runs % 1000, fields[2..].join(", ")),
+      /* Figures which did not get written */
+      1 => fields[0].to_string(),
+      /* A name with a suffix, one word more than a name can have */
+      2 => format!("{} Jr, {}", fields[0], fields[1..].join(", ")),
+      /* A negative average, which -0.00 would not be */
+      3 if fields[2] != "0.00" => format!("{}, {}, -{}, {}", fields[0], fields[1], fields[2], fields[3..].join(", ")),
+      /* Something that is not a number at all, which is also what
+       * happens to lines the others cannot break */
+      _ => format!("{}, lots, {}", fields[0], fields[2..].join(", "))
+    }
+  }
+}
+
+/* Goes on for ever, so take() as many lines as are wanted */
+impl Iterator for Generator {
+  type Item = String;
+
+  fn next(&mut self) -> Option<String> {
+    self.line += 1;
+    let good = self.good();
+    if self.corrupt > 0.0 && self.breaker.next_f64() < self.corrupt {
+      Some(self.broken(good))
+    } else {
+      Some(good)
+    }
+  }
+}
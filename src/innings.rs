@@ -0,0 +1,59 @@
+use error::ParseError;
+use join::matches_name;
+use line;
+use record::{Record, Value};
+
+/* A single innings rather than a whole career. Files of these look
+ * like "AN Cook, 2016-11-09, 21" (player, match date, runs). Dates are
+ * kept as the year-month-day text they came in as, which has the handy
+ * property that sorting the text also sorts the dates. */
+#[derive(Debug, Clone)]
+pub struct Innings<'a> {
+  pub initials: &'a str,
+  pub surname: &'a str,
+  pub date: &'a str,
+  pub runs: u32
+}
+
+impl<'a> Innings<'a> {
+  pub fn parse(l: &'a str) -> Result<Innings<'a>, ParseError> {
+    let v = line::split(l);
+    let (initials, surname) = line::name(v[0])?;
+
+    Ok(Innings {
+      initials,
+      surname,
+      date: line::item(&v, 1, "date")?,
+      runs: line::number(&v, 2, "runs", "u32")?
+    })
+  }
+
+  pub fn from_line(l: &'a str) -> Innings<'a> {
+    match Innings::parse(l) {
+      Ok(i) => i,
+      Err(e) => panic!("{}", e)
+    }
+  }
+
+  pub fn matches_name(&self, query: &str) -> bool {
+    matches_name(query, self.initials, self.surname)
+  }
+}
+
+const INNINGS_FIELDS: &[&str] = &["initials", "surname", "date", "runs"];
+
+impl<'a> Record for Innings<'a> {
+  fn field_names(&self) -> &'static [&'static str] {
+    INNINGS_FIELDS
+  }
+
+  fn field(&self, name: &str) -> Option<Value<'_>> {
+    match name {
+      "initials" => Some(Value::Text(self.initials)),
+      "surname" => Some(Value::Text(self.surname)),
+      "date" => Some(Value::Text(self.date)),
+      "runs" => Some(Value::Integer(self.runs)),
+      _ => None
+    }
+  }
+}
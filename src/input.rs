@@ -0,0 +1,175 @@
+use std::str::FromStr;
+
+/* The kinds of text file we can read records from. Everything gets
+ * turned into comma separated lines before parsing, so this only
+ * decides how that happens. */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputFormat {
+  Csv,
+  Tsv,
+  Json,
+  Ndjson
+}
+
+impl FromStr for InputFormat {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<InputFormat, String> {
+    match s {
+      "csv" => Ok(InputFormat::Csv),
+      "tsv" => Ok(InputFormat::Tsv),
+      "json" => Ok(InputFormat::Json),
+      "ndjson" | "jsonl" => Ok(InputFormat::Ndjson),
+      other => Err(format!("Unknown input format {:?}, expected csv, tsv, json or ndjson", other))
+    }
+  }
+}
+
+/* How many lines detect looks at when counting delimiters */
+const SNIFF_LINES: usize = 20;
+
+/* How many bytes Encoding::detect looks at for zero bytes */
+const SNIFF_BYTES: usize = 1024;
+
+impl InputFormat {
+  /* The format a file name's extension says it is, if it says at all */
+  pub fn from_extension(path: &str) -> Option<InputFormat> {
+    let lower = path.to_lowercase();
+    let extension = lower.rsplit('.').next()?;
+    if extension.len() == lower.len() {
+      return None;
+    }
+
+    match extension {
+      "csv" => Some(InputFormat::Csv),
+      "tsv" | "tab" => Some(InputFormat::Tsv),
+      "json" => Some(InputFormat::Json),
+      "ndjson" | "jsonl" => Some(InputFormat::Ndjson),
+      _ => None
+    }
+  }
+
+  /* Works out the format from the contents of a file:
+   *
+   * - Starting with [ means a JSON array.
+   * - Starting with { means JSON objects. If every non-blank line is a
+   *   whole object, it is NDJSON (one object per line), otherwise it
+   *   is a single JSON document spread over several lines.
+   * - Anything else is delimited text, and whichever of tabs and commas
+   *   turns up more often in the first few lines is the delimiter.
+   *   Ties go to CSV, which is also the answer for empty input. */
+  pub fn detect(bytes: &[u8]) -> InputFormat {
+    /* A UTF-8 byte order mark is not part of the content */
+    let bytes = if bytes.starts_with(b"\xef\xbb\xbf") { &bytes[3..] } else { bytes };
+
+    match bytes.iter().find(|b| !b.is_ascii_whitespace()) {
+      Some(b'[') => return InputFormat::Json,
+      Some(b'{') => {
+        let whole_objects = bytes.split(|&b| b == b'\n')
+          .map(|l| l.trim_ascii())
+          .filter(|l| !l.is_empty())
+          .all(|l| l.starts_with(b"{") && l.ends_with(b"}"));
+        return if whole_objects { InputFormat::Ndjson } else { InputFormat::Json };
+      },
+      _ => ()
+    }
+
+    let count = |delimiter: u8| {
+      bytes.split(|&b| b == b'\n')
+        .take(SNIFF_LINES)
+        .map(|l| l.iter().filter(|&&b| b == delimiter).count())
+        .sum::<usize>()
+    };
+    if count(b'\t') > count(b',') {
+      InputFormat::Tsv
+    } else {
+      InputFormat::Csv
+    }
+  }
+}
+
+/* The text encodings input can be in. Everything is UTF-8 once it has
+ * been read, but spreadsheets on Windows tend to export UTF-16, with a
+ * byte order mark (BOM) at the start saying which way round its bytes
+ * go, and CRLF line endings. */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Encoding {
+  Utf8,
+  Utf16Le,
+  Utf16Be
+}
+
+impl FromStr for Encoding {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Encoding, String> {
+    match s.to_lowercase().as_str() {
+      "utf-8" | "utf8" => Ok(Encoding::Utf8),
+      "utf-16" | "utf16" | "utf-16le" | "utf16le" => Ok(Encoding::Utf16Le),
+      "utf-16be" | "utf16be" => Ok(Encoding::Utf16Be),
+      _ => Err(format!("Unknown encoding {:?}, expected utf-8, utf-16le or utf-16be", s))
+    }
+  }
+}
+
+impl Encoding {
+  /* Works out the encoding from the first few bytes. A BOM settles it;
+   * without one, text whose every other byte is zero is taken to be
+   * UTF-16 (which is how ASCII text comes out in it), and anything
+   * else is UTF-8. */
+  pub fn detect(bytes: &[u8]) -> Encoding {
+    if bytes.starts_with(b"\xff\xfe") {
+      return Encoding::Utf16Le;
+    }
+    if bytes.starts_with(b"\xfe\xff") {
+      return Encoding::Utf16Be;
+    }
+
+    let start = &bytes[..bytes.len().min(SNIFF_BYTES)];
+    let zeros = |offset: usize| start.iter().skip(offset).step_by(2).filter(|&&b| b == 0).count();
+    let pairs = start.len() / 2;
+    if pairs > 0 && zeros(1) == pairs && zeros(0) == 0 {
+      Encoding::Utf16Le
+    } else if pairs > 0 && zeros(0) == pairs && zeros(1) == 0 {
+      Encoding::Utf16Be
+    } else {
+      Encoding::Utf8
+    }
+  }
+
+  /* Turns bytes in this encoding into a String, dropping any BOM and
+   * turning CRLF line endings into plain LF ones */
+  pub fn decode(&self, bytes: Vec<u8>) -> Result<String, String> {
+    let text = match *self {
+      Encoding::Utf8 => {
+        let bytes = match bytes.strip_prefix(b"\xef\xbb\xbf") {
+          Some(rest) => rest.to_vec(),
+          None => bytes
+        };
+        String::from_utf8(bytes).map_err(|e| {
+          format!("Expected UTF-8 text, but byte {} is not (try --encoding)", e.utf8_error().valid_up_to())
+        })?
+      },
+      Encoding::Utf16Le | Encoding::Utf16Be => {
+        if !bytes.len().is_multiple_of(2) {
+          return Err("Expected UTF-16 text, but there is an odd number of bytes".to_string());
+        }
+        let units = bytes.chunks_exact(2).map(|pair| {
+          let pair = [pair[0], pair[1]];
+          if *self == Encoding::Utf16Le { u16::from_le_bytes(pair) } else { u16::from_be_bytes(pair) }
+        }).collect::<Vec<u16>>();
+        let text = String::from_utf16(&units).map_err(|_| "Expected UTF-16 text, but it is not valid".to_string())?;
+        match text.strip_prefix('\u{feff}') {
+          Some(rest) => rest.to_string(),
+          None => text
+        }
+      }
+    };
+
+    if text.contains('\r') {
+      Ok(text.replace("\r\n", "\n"))
+    } else {
+      Ok(text)
+    }
+  }
+}
@@ -0,0 +1,43 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/* Hands out one shared copy of each distinct string. Records which
+ * own their text (rather than borrowing it from the file they were
+ * parsed from) would otherwise hold a separate copy of every surname,
+ * and of the file name and date on every row of an import. Asking the
+ * interner instead means each of those is only stored once, and every
+ * record holding it just has a pointer to it.
+ *
+ * Arc<str> rather than Rc<str> so that the records can still be sent
+ * between threads. */
+#[derive(Debug, Default)]
+pub struct Interner {
+  strings: HashSet<Arc<str>>
+}
+
+impl Interner {
+  pub fn new() -> Interner {
+    Interner::default()
+  }
+
+  /* The shared copy of s, made the first time it is asked for */
+  pub fn intern(&mut self, s: &str) -> Arc<str> {
+    match self.strings.get(s) {
+      Some(shared) => Arc::clone(shared),
+      None => {
+        let shared: Arc<str> = Arc::from(s);
+        self.strings.insert(Arc::clone(&shared));
+        shared
+      }
+    }
+  }
+
+  /* How many distinct strings have been seen */
+  pub fn len(&self) -> usize {
+    self.strings.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.strings.is_empty()
+  }
+}
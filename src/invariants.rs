@@ -0,0 +1,79 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use rank::RankMethod;
+
+/* Promises the sorting and ranking code makes about its answers,
+ * written down as checks. Debug builds make them as they go, with
+ * debug_assert, so a change which breaks one fails the tests right
+ * where it went wrong instead of somewhere further down. Release
+ * builds leave them out, and batsmen selfcheck makes them on a real
+ * file instead.
+ *
+ * Each check gives back where its invariant first stopped holding. */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Broken {
+  pub invariant: &'static str,
+  pub index: usize,
+  pub message: String
+}
+
+impl fmt::Display for Broken {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{} does not hold at {}: {}", self.invariant, self.index, self.message)
+  }
+}
+
+/* Every element is in order with the one before it, by the same
+ * comparison that sorted them */
+pub fn check_sorted<T, F>(xs: &[T], mut cmp: F) -> Result<(), Broken>
+  where F: FnMut(&T, &T) -> Ordering
+{
+  match (1..xs.len()).find(|&i| cmp(&xs[i - 1], &xs[i]) == Ordering::Greater) {
+    Some(i) => Err(Broken {
+      invariant: "sorted",
+      index: i,
+      message: format!("element {} should come before element {}", i, i - 1)
+    }),
+    None => Ok(())
+  }
+}
+
+/* Every number is a number: not infinite and not NaN, either of which
+ * would sort and average nonsensically */
+pub fn check_finite(xs: &[f32]) -> Result<(), Broken> {
+  match xs.iter().position(|x| !x.is_finite()) {
+    Some(i) => Err(Broken {
+      invariant: "finite",
+      index: i,
+      message: format!("{} is not a finite number", xs[i])
+    }),
+    None => Ok(())
+  }
+}
+
+/* Ranks start at 1 and only ever go up by as much as the method says
+ * they can: by one at a time for Ordinal and Dense (which can also
+ * stay the same on a tie), and to the position in the list for
+ * Standard (or the same on a tie) */
+pub fn check_ranks(ranks: &[u32], method: RankMethod) -> Result<(), Broken> {
+  let broken = |index, message| Err(Broken { invariant: "contiguous ranks", index, message });
+  for (i, &r) in ranks.iter().enumerate() {
+    if i == 0 {
+      if r != 1 {
+        return broken(i, format!("the first rank is {} rather than 1", r));
+      }
+      continue;
+    }
+    let previous = ranks[i - 1];
+    let allowed = match method {
+      RankMethod::Ordinal => r == i as u32 + 1,
+      RankMethod::Standard => r == previous || r == i as u32 + 1,
+      RankMethod::Dense => r == previous || r == previous + 1
+    };
+    if !allowed {
+      return broken(i, format!("rank {} cannot follow rank {} with {:?} ranking", r, previous, method));
+    }
+  }
+  Ok(())
+}
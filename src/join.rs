@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+
+/* The name matching lives in name.rs, so that it builds without std,
+ * but joins are where it is mostly used */
+pub use name::{matches_name, normalize_name};
+
+/* Indexes one side of a join by its keys. If a key appears more than
+ * once, the first occurrence wins. */
+fn index<R, G>(right: &[R], right_key: G) -> HashMap<String, &R>
+  where G: Fn(&R) -> String
+{
+  let mut index = HashMap::new();
+  for r in right {
+    index.entry(right_key(r)).or_insert(r);
+  }
+  index
+}
+
+/* An inner join of two slices. Each side gets a function which pulls
+ * the join key out of an element. Only elements whose keys appear on
+ * both sides make it into the output, which is in the order of the
+ * left hand side.
+ *
+ * We build a HashMap of the right hand side first so that the join
+ * is linear rather than comparing every pair. If a key appears more
+ * than once on the right, the first occurrence wins. */
+pub fn inner_join<'l, 'r, L, R, F, G>(left: &'l [L],
+                                      right: &'r [R],
+                                      left_key: F,
+                                      right_key: G) -> Vec<(&'l L, &'r R)>
+  where F: Fn(&L) -> String,
+        G: Fn(&R) -> String
+{
+  let index = index(right, right_key);
+  left.iter().filter_map(|l| {
+    index.get(&left_key(l)).map(|r| (l, *r))
+  }).collect()
+}
+
+/* A left join: like inner_join, but every element of the left hand
+ * side makes it into the output, with None where nothing on the right
+ * has its key */
+pub fn left_join<'l, 'r, L, R, F, G>(left: &'l [L],
+                                     right: &'r [R],
+                                     left_key: F,
+                                     right_key: G) -> Vec<(&'l L, Option<&'r R>)>
+  where F: Fn(&L) -> String,
+        G: Fn(&R) -> String
+{
+  let index = index(right, right_key);
+  left.iter().map(|l| (l, index.get(&left_key(l)).cloned())).collect()
+}
+
+/* Extra facts about players kept somewhere else, like a spreadsheet of
+ * their countries, birth dates and batting hands, to be joined onto
+ * the batting records. It is read from comma separated text whose
+ * first line names the columns. */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reference {
+  pub columns: Vec<String>,
+  pub rows: Vec<Vec<String>>
+}
+
+impl Reference {
+  /* Blank lines are skipped, and every other line needs as many fields
+   * as the first. Lines are numbered from 1 in errors. */
+  pub fn parse(text: &str) -> Result<Reference, String> {
+    let split = |l: &str| l.split(',').map(|f| f.trim().to_string()).collect::<Vec<String>>();
+    let mut lines = text.lines().enumerate().filter(|&(_, l)| !l.trim().is_empty());
+
+    let columns = match lines.next() {
+      Some((_, header)) => split(header),
+      None => return Err("Expected a line of column names".to_string())
+    };
+    let rows = lines.map(|(i, l)| {
+      let row = split(l);
+      if row.len() == columns.len() {
+        Ok(row)
+      } else {
+        Err(format!("Line {}: expected {} fields, got {}", i + 1, columns.len(), row.len()))
+      }
+    }).collect::<Result<Vec<Vec<String>>, String>>()?;
+
+    Ok(Reference { columns, rows })
+  }
+
+  pub fn column(&self, name: &str) -> Option<usize> {
+    self.columns.iter().position(|c| c == name)
+  }
+}
@@ -0,0 +1,122 @@
+use std::cmp::Ordering;
+use std::slice;
+
+use invariants::check_sorted;
+use record::Record;
+use sort::SortSpec;
+
+/* A list of records which is always in SortSpec order. Rather than
+ * sorting everything again when a record arrives, each one is put
+ * straight into its place, which is found with a binary search. This
+ * suits the watch and serve modes, where records trickle in a few at a
+ * time rather than all arriving at once.
+ *
+ * Records which compare equal are kept in the order they were
+ * inserted, the same as a stable sort would leave them. */
+#[derive(Debug, Clone)]
+pub struct Leaderboard<T> {
+  spec: SortSpec,
+  records: Vec<T>
+}
+
+impl<T: Record> Leaderboard<T> {
+  pub fn new(spec: SortSpec) -> Leaderboard<T> {
+    Leaderboard { spec, records: Vec::new() }
+  }
+
+  /* Starts off with a whole vector of records, sorted once */
+  pub fn from_vec(spec: SortSpec, mut records: Vec<T>) -> Leaderboard<T> {
+    records.sort_by(|lhs, rhs| spec.compare(lhs, rhs));
+    debug_assert_eq!(check_sorted(&records, |lhs, rhs| spec.compare(lhs, rhs)), Ok(()));
+    Leaderboard { spec, records }
+  }
+
+  pub fn spec(&self) -> &SortSpec {
+    &self.spec
+  }
+
+  /* Changing the order means sorting everything once more */
+  pub fn set_spec(&mut self, spec: SortSpec) {
+    self.spec = spec;
+    let spec = &self.spec;
+    self.records.sort_by(|lhs, rhs| spec.compare(lhs, rhs));
+    debug_assert_eq!(check_sorted(&self.records, |lhs, rhs| spec.compare(lhs, rhs)), Ok(()));
+  }
+
+  /* Puts a record in its place, after any it compares equal to, and
+   * returns the position it ended up at */
+  pub fn insert(&mut self, record: T) -> usize {
+    let at = self.records.partition_point(|r| self.spec.compare(r, &record) != Ordering::Greater);
+    self.records.insert(at, record);
+    /* Only its neighbours need checking, the rest were already in order */
+    let around = &self.records[at.saturating_sub(1)..(at + 2).min(self.records.len())];
+    debug_assert_eq!(check_sorted(around, |lhs, rhs| self.spec.compare(lhs, rhs)), Ok(()));
+    at
+  }
+
+  /* Where a record with these values would be. Ok is the position of
+   * one which compares equal to it, Err is where it would be inserted. */
+  pub fn search(&self, record: &T) -> Result<usize, usize> {
+    self.records.binary_search_by(|r| self.spec.compare(r, record))
+  }
+
+  pub fn remove(&mut self, index: usize) -> T {
+    self.records.remove(index)
+  }
+
+  /* Changes the record at index and moves it to wherever it belongs
+   * now, returning its new position */
+  pub fn update<F>(&mut self, index: usize, change: F) -> usize
+    where F: FnOnce(&mut T)
+  {
+    let mut record = self.records.remove(index);
+    change(&mut record);
+    self.insert(record)
+  }
+
+  /* Replaces the first record that same says is the same one (the same
+   * player, usually) or inserts it if there is none. Returns the
+   * record that was replaced, if any. */
+  pub fn upsert<F>(&mut self, record: T, same: F) -> Option<T>
+    where F: Fn(&T, &T) -> bool
+  {
+    let old = self.records.iter().position(|r| same(r, &record)).map(|i| self.records.remove(i));
+    self.insert(record);
+    old
+  }
+
+  pub fn len(&self) -> usize {
+    self.records.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.records.is_empty()
+  }
+
+  pub fn get(&self, index: usize) -> Option<&T> {
+    self.records.get(index)
+  }
+
+  /* There is no iter_mut or as_mut_slice, since changing a record
+   * where it is could leave it out of order. Use update instead. */
+  pub fn iter(&self) -> slice::Iter<'_, T> {
+    self.records.iter()
+  }
+
+  pub fn as_slice(&self) -> &[T] {
+    &self.records
+  }
+
+  pub fn into_vec(self) -> Vec<T> {
+    self.records
+  }
+}
+
+impl<'l, T> IntoIterator for &'l Leaderboard<T> {
+  type Item = &'l T;
+  type IntoIter = slice::Iter<'l, T>;
+
+  fn into_iter(self) -> slice::Iter<'l, T> {
+    self.records.iter()
+  }
+}
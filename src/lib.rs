@@ -0,0 +1,124 @@
+/* The library half of the crate. The binaries in src/bin/ are thin
+ * wrappers around what lives here, so that the same types and helpers
+ * can be reused by every command without copy-pasting them around.
+ *
+ * Without the std feature (which is on by default) only the core of
+ * it is built: the record types and the line parser. Those need an
+ * allocator but nothing else, so they can run on an embedded
+ * scoreboard or in a stripped down WebAssembly module, as long as
+ * whatever uses them provides the allocator and a panic handler. */
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/* Strings and vectors, which in a std build come from std. A no_std
+ * build gets core brought in for it, but a std one in the 2015 edition
+ * has to ask for it by name, as PyO3's macros (which refer to ::core)
+ * do too. */
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate core;
+
+/* With --features python, the library is also a Python module */
+#[cfg(feature = "python")]
+extern crate pyo3;
+
+/* With --features decimal, averages are exact */
+#[cfg(feature = "decimal")]
+extern crate rust_decimal;
+/* With --features async, batsmen can be read without blocking */
+#[cfg(feature = "async")]
+extern crate futures_core;
+#[cfg(feature = "async")]
+extern crate tokio;
+
+/* With --features parallel, sorting can use every core */
+#[cfg(feature = "parallel")]
+extern crate rayon;
+
+/* With --features wasm, the library can be built for the browser */
+#[cfg(feature = "wasm")]
+extern crate js_sys;
+#[cfg(feature = "wasm")]
+extern crate wasm_bindgen;
+
+/* The core, which builds with or without std */
+pub mod batsman;
+pub mod error;
+pub mod line;
+pub mod name;
+pub mod numeric;
+pub mod record;
+
+/* Everything else reads files, keeps hash maps, uses threads or
+ * otherwise needs std */
+#[cfg(feature = "std")]
+pub mod allrounder;
+#[cfg(feature = "async")]
+pub mod async_reader;
+#[cfg(feature = "std")]
+pub mod anonymize;
+#[cfg(feature = "std")]
+pub mod batting_order;
+#[cfg(feature = "std")]
+pub mod bowler;
+#[cfg(feature = "std")]
+pub mod coerce;
+#[cfg(feature = "std")]
+pub mod compare;
+#[cfg(feature = "std")]
+pub mod dataset;
+#[cfg(feature = "std")]
+pub mod expr;
+#[cfg(feature = "std")]
+pub mod external;
+#[cfg(feature = "std")]
+pub mod ffi;
+#[cfg(feature = "std")]
+pub mod generate;
+#[cfg(feature = "std")]
+pub mod innings;
+#[cfg(feature = "std")]
+pub mod input;
+#[cfg(feature = "std")]
+pub mod intern;
+#[cfg(feature = "std")]
+pub mod invariants;
+#[cfg(feature = "std")]
+pub mod join;
+#[cfg(feature = "std")]
+pub mod leaderboard;
+#[cfg(feature = "std")]
+pub mod outliers;
+#[cfg(feature = "std")]
+pub mod output;
+#[cfg(feature = "std")]
+pub mod page;
+#[cfg(feature = "std")]
+pub mod partnership;
+#[cfg(feature = "std")]
+pub mod plot;
+#[cfg(feature = "std")]
+pub mod progress;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "std")]
+pub mod rank;
+#[cfg(feature = "std")]
+pub mod ratings;
+#[cfg(feature = "std")]
+pub mod rng;
+#[cfg(feature = "std")]
+pub mod schema;
+#[cfg(feature = "std")]
+pub mod scorecard;
+#[cfg(feature = "std")]
+pub mod simulate;
+#[cfg(feature = "std")]
+pub mod sort;
+#[cfg(feature = "std")]
+pub mod source;
+#[cfg(feature = "std")]
+pub mod stats;
+#[cfg(feature = "std")]
+pub mod validate;
+#[cfg(feature = "wasm")]
+pub mod wasm;
@@ -0,0 +1,54 @@
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+use error::ParseError;
+use numeric::Number;
+
+/* Helpers shared by every record type's parse function. All of our
+ * files are lines of comma separated items with a name first. */
+
+/* Need to explicitly trim each element of the split string, otherwise
+ * parse() will get upset */
+pub fn split(l: &str) -> Vec<&str> {
+  l.split(',').map(|x| x.trim()).collect()
+}
+
+/* Splits a name like "AN Cook" into its initials and surname */
+pub fn name(s: &str) -> Result<(&str, &str), ParseError> {
+  let words = s.split(' ').filter(|x| !x.is_empty()).collect::<Vec<&str>>();
+  if words.len() != 2 {
+    return Err(ParseError::BadName(s.to_string()));
+  }
+
+  Ok((words[0], words[1]))
+}
+
+/* The same, for records whose initials are optional, where a name can
+ * also be just a surname like "Cook", which has no initials */
+pub fn name_or_surname(s: &str) -> Result<(Option<&str>, &str), ParseError> {
+  let words = s.split(' ').filter(|x| !x.is_empty()).collect::<Vec<&str>>();
+  match words.len() {
+    1 => Ok((None, words[0])),
+    _ => name(s).map(|(initials, surname)| (Some(initials), surname))
+  }
+}
+
+/* The item at position i, which has to be there */
+pub fn item<'a>(items: &[&'a str], i: usize, field: &'static str) -> Result<&'a str, ParseError> {
+  items.get(i).cloned().ok_or(ParseError::MissingField(field))
+}
+
+/* The item at position i parsed as a number. expected is the name of
+ * the type for the error message, since Rust has no way of getting a
+ * readable name for T on its own. With the fast-parse feature the
+ * parsers in numeric.rs are tried first. */
+pub fn number<T: Number>(items: &[&str],
+                         i: usize,
+                         field: &'static str,
+                         expected: &'static str) -> Result<T, ParseError> {
+  let x = item(items, i, field)?;
+  let parsed = if cfg!(feature = "fast-parse") { T::parse_fast(x) } else { x.parse::<T>() };
+  parsed.map_err(|_| {
+    ParseError::BadNumber { field, expected, value: x.to_string() }
+  })
+}
@@ -0,0 +1,29 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/* Names come in from different files typed by different people, so
+ * "BL D'Oliveira", "bl  d'oliveira" and "BL DOliveira" should all be
+ * considered the same player. We lowercase everything, drop anything
+ * that is not a letter or a digit and squash runs of whitespace down
+ * to a single space. */
+pub fn normalize_name(name: &str) -> String {
+  name.split_whitespace().map(|word| {
+    word.chars()
+      .filter(|c| c.is_alphanumeric())
+      .flat_map(|c| c.to_lowercase())
+      .collect::<String>()
+  }).filter(|word| !word.is_empty()).collect::<Vec<String>>().join(" ")
+}
+
+/* Whether a name typed in by a user refers to the player with these
+ * initials and surname. The last word has to match the surname, and
+ * anything before it has to be the start of the initials. */
+pub fn matches_name(query: &str, initials: &str, surname: &str) -> bool {
+  let words = normalize_name(query).split(' ').map(String::from).collect::<Vec<String>>();
+  let (last, first) = match words.split_last() {
+    Some((last, first)) => (last.clone(), first.concat()),
+    None => return false
+  };
+
+  last == normalize_name(surname) && normalize_name(initials).starts_with(&first)
+}
@@ -0,0 +1,179 @@
+use core::fmt;
+use core::str::FromStr;
+
+#[cfg(feature = "decimal")]
+use rust_decimal::Decimal;
+#[cfg(feature = "decimal")]
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+
+/* What a batting average is kept as. An f32 is plenty for working
+ * things out with, but "46.33" has no exact f32, so what gets written
+ * back out is the nearest f32 printed again, and two averages which
+ * ought to be equal have to be compared with relative_eq below. With
+ * --features decimal it is a Decimal instead, which holds "46.33"
+ * exactly: it is written back out digit for digit and compares with
+ * plain ==. */
+#[cfg(not(feature = "decimal"))]
+pub type Average = f32;
+#[cfg(feature = "decimal")]
+pub type Average = Decimal;
+
+/* An average as an f32, for the statistics and charts which only need
+ * it roughly */
+#[cfg(not(feature = "decimal"))]
+pub fn average_f32(a: Average) -> f32 {
+  a
+}
+
+#[cfg(feature = "decimal")]
+pub fn average_f32(a: Average) -> f32 {
+  a.to_f32().unwrap_or(f32::NAN)
+}
+
+/* An average worked out as an f32 (rounded, or read from a source
+ * which only has floats), as an Average. A Decimal cannot be infinite
+ * or NaN, so those come out as None. */
+#[cfg(not(feature = "decimal"))]
+pub fn average_from_f32(x: f32) -> Option<Average> {
+  Some(x)
+}
+
+#[cfg(feature = "decimal")]
+pub fn average_from_f32(x: f32) -> Option<Average> {
+  Decimal::from_f32(x)
+}
+
+/* The average of runs scored over outs times out. As a Decimal it is
+ * rounded to the two places a file would give it, since most averages
+ * would go on for ever. */
+#[cfg(not(feature = "decimal"))]
+pub fn average_of(runs: u32, outs: u32) -> Average {
+  runs as f32 / outs as f32
+}
+
+#[cfg(feature = "decimal")]
+pub fn average_of(runs: u32, outs: u32) -> Average {
+  (Decimal::from(runs) / Decimal::from(outs)).round_dp(2)
+}
+
+/* Writes an average the way our files do: to two places as an f32,
+ * and as a Decimal with exactly the digits it was read with */
+pub struct DisplayAverage(pub Average);
+
+impl fmt::Display for DisplayAverage {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    if cfg!(feature = "decimal") {
+      write!(f, "{}", self.0)
+    } else {
+      write!(f, "{:.2}", self.0)
+    }
+  }
+}
+
+/* Whether two averages are the same: within relative_eq as f32s, and
+ * exactly as Decimals */
+#[cfg(not(feature = "decimal"))]
+pub fn average_eq(a: Average, b: Average) -> bool {
+  relative_eq(a, b)
+}
+
+#[cfg(feature = "decimal")]
+pub fn average_eq(a: Average, b: Average) -> bool {
+  a == b
+}
+
+/* Quicker ways of reading the numbers in our files than str::parse.
+ * The standard parsers cope with everything (exponents, "inf", signs,
+ * any number of digits), and pay for it on every call. Ours only cope
+ * with the plain digits and decimal point that batting files actually
+ * contain, and hand anything else on to str::parse, so the answer is
+ * always exactly what str::parse would give.
+ *
+ * line::number only uses these when built with --features fast-parse.
+ * `cargo bench --bench parse` shows the difference. */
+pub trait Number: FromStr {
+  /* The number, or None if s is not the simple kind we handle */
+  fn parse_simple(s: &str) -> Option<Self>;
+
+  fn parse_fast(s: &str) -> Result<Self, Self::Err> {
+    match Self::parse_simple(s) {
+      Some(n) => Ok(n),
+      None => s.parse()
+    }
+  }
+}
+
+impl Number for u32 {
+  fn parse_simple(s: &str) -> Option<u32> {
+    if s.is_empty() {
+      return None;
+    }
+    s.bytes().try_fold(0u32, |n, b| {
+      if !b.is_ascii_digit() {
+        return None;
+      }
+      n.checked_mul(10)?.checked_add((b - b'0') as u32)
+    })
+  }
+}
+
+/* 10 to the power of i, all of which are exact as f32s */
+const POWERS: [f32; 11] = [1e0, 1e1, 1e2, 1e3, 1e4, 1e5, 1e6, 1e7, 1e8, 1e9, 1e10];
+
+impl Number for f32 {
+  /* Digits with at most one decimal point, like "46.33". The digits
+   * are read as a whole number and then divided by a power of ten.
+   * As long as the whole number fits in an f32's 24 bit mantissa and
+   * the power is at most 10, both are exact, so the one division is
+   * the only rounding: the same correctly rounded answer str::parse
+   * gets. Anything bigger goes to str::parse. */
+  fn parse_simple(s: &str) -> Option<f32> {
+    let mut mantissa: u32 = 0;
+    let mut digits = 0;
+    let mut decimals = None;
+    for b in s.bytes() {
+      match b {
+        b'0'..=b'9' => {
+          mantissa = mantissa.checked_mul(10)?.checked_add((b - b'0') as u32)?;
+          digits += 1;
+          if let Some(ref mut d) = decimals {
+            *d += 1;
+          }
+        },
+        b'.' if decimals.is_none() => decimals = Some(0),
+        _ => return None
+      }
+    }
+
+    let decimals = decimals.unwrap_or(0);
+    if digits == 0 || mantissa >= 1 << 24 || decimals >= POWERS.len() {
+      return None;
+    }
+    Some(mantissa as f32 / POWERS[decimals])
+  }
+}
+
+/* Decimals are read exactly by str::parse, and there is no shortcut
+ * which would give the same answer, so that is always what is used */
+#[cfg(feature = "decimal")]
+impl Number for Decimal {
+  fn parse_simple(_s: &str) -> Option<Decimal> {
+    None
+  }
+}
+
+/* Rust has no concept of floating point equality because floating
+ * point numbers are not stable. You need to opt-in to using epsilon
+ * based equality and this is not in the standard library, so we do
+ * that here. This is the same test as the approx crate's relative_eq!
+ * with its default tolerances (which is what we used to use, but
+ * approx needs std): exactly equal is equal, anything within EPSILON
+ * of each other is too, and beyond that the difference is measured
+ * relative to the bigger of the two. */
+pub fn relative_eq(a: f32, b: f32) -> bool {
+  if a == b {
+    return true;
+  }
+  let diff = (a - b).abs();
+  diff <= f32::EPSILON || diff <= a.abs().max(b.abs()) * f32::EPSILON
+}
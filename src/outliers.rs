@@ -0,0 +1,87 @@
+use std::str::FromStr;
+
+use stats::{mean, quantile, std_dev};
+
+/* The two usual ways of deciding that a value is suspiciously far from
+ * the rest of the data.
+ *
+ * ZScore: how many standard deviations away from the mean a value is.
+ * Iqr: how many interquartile ranges a value lies beyond the nearest
+ *      quartile (Tukey's fences).
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutlierMethod {
+  ZScore,
+  Iqr
+}
+
+impl OutlierMethod {
+  /* The conventional cut-off for each method */
+  pub fn default_threshold(&self) -> f32 {
+    match *self {
+      OutlierMethod::ZScore => 3.0,
+      OutlierMethod::Iqr => 1.5
+    }
+  }
+}
+
+impl FromStr for OutlierMethod {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<OutlierMethod, String> {
+    match s {
+      "zscore" => Ok(OutlierMethod::ZScore),
+      "iqr" => Ok(OutlierMethod::Iqr),
+      other => Err(format!("Unknown outlier method {:?}, expected zscore or iqr", other))
+    }
+  }
+}
+
+/* A value which was flagged, along with where it was in the input and
+ * how far over the threshold it was */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Outlier {
+  pub index: usize,
+  pub value: f32,
+  pub score: f32
+}
+
+/* Scores every value and keeps the ones whose score is above the
+ * threshold. With too little data to work out a spread (or no spread
+ * at all) nothing can be called an outlier. */
+pub fn outliers(xs: &[f32], method: OutlierMethod, threshold: f32) -> Vec<Outlier> {
+  let score: Box<dyn Fn(f32) -> f32> = match method {
+    OutlierMethod::ZScore => {
+      match (mean(xs), std_dev(xs)) {
+        (Some(m), Some(sd)) if sd > 0.0 => Box::new(move |x| ((x - m) / sd).abs()),
+        _ => return Vec::new()
+      }
+    },
+    OutlierMethod::Iqr => {
+      match (quantile(xs, 0.25), quantile(xs, 0.75)) {
+        (Some(q1), Some(q3)) if q3 > q1 => {
+          let iqr = q3 - q1;
+          Box::new(move |x| {
+            if x > q3 {
+              (x - q3) / iqr
+            } else if x < q1 {
+              (q1 - x) / iqr
+            } else {
+              0.0
+            }
+          })
+        },
+        _ => return Vec::new()
+      }
+    }
+  };
+
+  xs.iter().enumerate().filter_map(|(index, &value)| {
+    let s = score(value);
+    if s > threshold {
+      Some(Outlier { index, value, score: s })
+    } else {
+      None
+    }
+  }).collect()
+}
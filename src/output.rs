@@ -0,0 +1,553 @@
+use std::cell::Cell;
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::env;
+use std::io;
+use std::io::IsTerminal;
+use std::str::FromStr;
+
+use anonymize::Pseudonyms;
+use expr::Derivation;
+use join::{Reference, left_join, normalize_name};
+use record::{Record, Value};
+use stats::mean;
+
+/* The ways we know how to print results. Commands that produce
+ * something other than the default listing pick one of these with
+ * --format. */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+  Table,
+  Json,
+  Markdown
+}
+
+/* Implementing FromStr is what makes "json".parse::<Format>() work */
+impl FromStr for Format {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Format, String> {
+    match s {
+      "table" => Ok(Format::Table),
+      "json" => Ok(Format::Json),
+      "markdown" => Ok(Format::Markdown),
+      other => Err(format!("Unknown format {:?}, expected table, json or markdown", other))
+    }
+  }
+}
+
+/* Quotes a string for JSON output, escaping anything that would
+ * otherwise end the string early or produce invalid JSON */
+pub fn json_string(s: &str) -> String {
+  let mut out = String::with_capacity(s.len() + 2);
+  out.push('"');
+  for c in s.chars() {
+    match c {
+      '"' => out.push_str("\\\""),
+      '\\' => out.push_str("\\\\"),
+      '\n' => out.push_str("\\n"),
+      '\r' => out.push_str("\\r"),
+      '\t' => out.push_str("\\t"),
+      c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+      c => out.push(c)
+    }
+  }
+  out.push('"');
+  out
+}
+
+/* Whether to colour the output. Auto colours it only when a person
+ * is likely to be looking at it, i.e. stdout is a terminal. */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorChoice {
+  Always,
+  Auto,
+  Never
+}
+
+impl FromStr for ColorChoice {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<ColorChoice, String> {
+    match s {
+      "always" => Ok(ColorChoice::Always),
+      "auto" => Ok(ColorChoice::Auto),
+      "never" => Ok(ColorChoice::Never),
+      other => Err(format!("Unknown color choice {:?}, expected always, auto or never", other))
+    }
+  }
+}
+
+const BOLD: &str = "\x1b[1m";
+const YELLOW: &str = "\x1b[33m";
+const RED: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+
+/* Everything about how output should look, as opposed to what is in
+ * it. Commands work this out once from the command line and pass it
+ * down to whatever does the rendering. */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Style {
+  pub color: bool
+}
+
+impl Style {
+  pub fn plain() -> Style {
+    Style { color: false }
+  }
+
+  /* Resolves Auto by checking whether stdout is a terminal. The
+   * NO_COLOR environment variable (see no-color.org) also turns auto
+   * colouring off. */
+  pub fn detect(choice: ColorChoice) -> Style {
+    Style {
+      color: match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none()
+      }
+    }
+  }
+
+  /* Wraps text in an ANSI colour code, if colour is turned on */
+  pub fn paint(&self, code: &str, s: &str) -> String {
+    if self.color && !s.is_empty() {
+      format!("{}{}{}", code, s, RESET)
+    } else {
+      s.to_string()
+    }
+  }
+}
+
+/* Lays out rows of cells in columns, padding each column to its widest
+ * cell. Every row is expected to have as many cells as the header.
+ *
+ * paint gets the row (None for the header), the column and the text of
+ * each cell, and can dress it up. Padding is added after painting so
+ * that colour codes do not throw the column widths off. */
+fn layout<F>(header: &[String], rows: &[Vec<String>], paint: F) -> String
+  where F: Fn(Option<usize>, usize, &str) -> String
+{
+  let widths = (0..header.len()).map(|i| {
+    rows.iter()
+      .map(|r| r[i].chars().count())
+      .chain(Some(header[i].chars().count()))
+      .max()
+      .unwrap_or(0)
+  }).collect::<Vec<usize>>();
+
+  let line = |row: Option<usize>, cells: &[String]| {
+    cells.iter().zip(widths.iter()).enumerate().map(|(i, (c, w))| {
+      format!("{}{}", paint(row, i, c), " ".repeat(w - c.chars().count()))
+    }).collect::<Vec<String>>().join("  ").trim_end().to_string()
+  };
+
+  let mut out = line(None, header);
+  out.push('\n');
+  for (i, r) in rows.iter().enumerate() {
+    out.push_str(&line(Some(i), r));
+    out.push('\n');
+  }
+  out
+}
+
+/* A plain table with a bold header */
+pub fn render_table(header: &[String], rows: &[Vec<String>], style: &Style) -> String {
+  layout(header, rows, |row, _, c| match row {
+    None => style.paint(BOLD, c),
+    Some(_) => c.to_string()
+  })
+}
+
+/* A GitHub flavoured Markdown table, ready to paste into an issue or a
+ * wiki page. Columns marked as numeric are right aligned and the rest
+ * are left aligned. A | inside a cell would start a new column, so it
+ * gets escaped. */
+pub fn markdown_table(header: &[String], rows: &[Vec<String>], numeric: &[bool]) -> String {
+  let line = |cells: &[String]| {
+    let cells = cells.iter().map(|c| c.replace('|', "\\|")).collect::<Vec<String>>();
+    format!("| {} |\n", cells.join(" | "))
+  };
+  let alignment = (0..header.len()).map(|i| {
+    if numeric.get(i).cloned().unwrap_or(false) { "---:" } else { ":---" }.to_string()
+  }).collect::<Vec<String>>();
+
+  let mut out = line(header);
+  out.push_str(&format!("|{}|\n", alignment.join("|")));
+  for r in rows {
+    out.push_str(&line(r));
+  }
+  out
+}
+
+/* Renders a single field as a JSON value: numbers stay numbers and
+ * text gets quoted */
+pub fn json_value(v: &Value) -> String {
+  match *v {
+    Value::Text(s) => json_string(s),
+    Value::Integer(i) => i.to_string(),
+    Value::Float(x) => if x.is_finite() { x.to_string() } else { "null".to_string() },
+    #[cfg(feature = "decimal")]
+    Value::Decimal(d) => d.to_string()
+  }
+}
+
+/* A generic table of results: named columns and rows of fields. Any
+ * slice of Records can be turned into one, and commands can then add
+ * extra columns (like a rank) before printing it in whichever Format
+ * was asked for. Missing fields are None.
+ *
+ * Cells can also be flagged as bad, for example a value which fails
+ * validation, so that renderers can make them stand out.
+ *
+ * A table can have footer rows too, like the totals, which the table
+ * and Markdown formats print under the rest. They are not records, so
+ * JSON leaves them out. */
+#[derive(Debug, Clone)]
+pub struct Table<'a> {
+  pub columns: Vec<String>,
+  pub rows: Vec<Vec<Option<Value<'a>>>>,
+  flagged: HashSet<(usize, usize)>,
+  footer: Vec<Vec<Option<Value<'a>>>>
+}
+
+impl<'a> Table<'a> {
+  pub fn new(columns: Vec<String>, rows: Vec<Vec<Option<Value<'a>>>>) -> Table<'a> {
+    Table {
+      columns,
+      rows,
+      flagged: HashSet::new(),
+      footer: Vec::new()
+    }
+  }
+
+  pub fn from_records<R: Record>(records: &'a [R]) -> Table<'a> {
+    let columns = match records.first() {
+      Some(r) => r.field_names().iter().map(|n| n.to_string()).collect(),
+      None => Vec::new()
+    };
+
+    let rows = records.iter().map(|r| {
+      columns.iter().map(|c| r.field(c)).collect()
+    }).collect();
+
+    Table::new(columns, rows)
+  }
+
+  /* Adds a column at the given position, one value per row */
+  pub fn insert_column(&mut self, index: usize, name: &str, values: Vec<Option<Value<'a>>>) {
+    self.columns.insert(index, name.to_string());
+    for (row, v) in self.rows.iter_mut().zip(values) {
+      row.insert(index, v);
+    }
+    for row in self.footer.iter_mut() {
+      row.insert(index, None);
+    }
+
+    /* Flags on columns at or after the new one shift along by one */
+    self.flagged = self.flagged.iter().map(|&(r, c)| {
+      (r, if c >= index { c + 1 } else { c })
+    }).collect();
+  }
+
+  /* Swaps the initials and surname of each row for their pseudonyms,
+   * once everything which goes by the real names, like --enrich, has
+   * had them. A table without those columns is left as it is. */
+  pub fn anonymize(&mut self, names: &'a Pseudonyms) {
+    let column = |name: &str| self.columns.iter().position(|c| c == name);
+    let (initials, surname) = match (column("initials"), column("surname")) {
+      (Some(i), Some(s)) => (i, s),
+      _ => return
+    };
+    for row in self.rows.iter_mut() {
+      if let (Some(Value::Text(i)), Some(Value::Text(s))) = (row[initials], row[surname]) {
+        let (i, s) = names.name(i, s);
+        row[initials] = Some(Value::Text(i));
+        row[surname] = Some(Value::Text(s));
+      }
+    }
+  }
+
+  /* Adds a column on the end worked out from the others in each row */
+  pub fn derive(&mut self, derivation: &Derivation) -> Result<(), String> {
+    self.derive_with(derivation, |_, _| None)
+  }
+
+  /* The same, with more values the expression can use which are not
+   * columns of the table, looked up by the position of the row and
+   * the name: None for a name extra does not have, Some(None) for one
+   * it has but not for that row, which leaves the new column blank
+   * there. Columns come first where both have a name. */
+  pub fn derive_with<F>(&mut self, derivation: &Derivation, extra: F) -> Result<(), String>
+    where F: Fn(usize, &str) -> Option<Option<Value<'a>>>
+  {
+    if self.columns.contains(&derivation.name) {
+      return Err(format!("There is already a column called {}", derivation.name));
+    }
+
+    let columns = &self.columns;
+    let values = self.rows.iter().enumerate().map(|(i, row)| {
+      /* The expression stops at the first value it cannot have, so if
+       * that was a blank from extra, the blank is why */
+      let blank = Cell::new(false);
+      let lookup = |name: &str| match columns.iter().position(|c| c == name) {
+        Some(c) => row[c],
+        None => extra(i, name).and_then(|v| {
+          blank.set(v.is_none());
+          v
+        })
+      };
+      match derivation.expr.eval_with(&lookup) {
+        Ok(x) => Ok(Some(Value::Float(x))),
+        Err(_) if blank.get() => Ok(None),
+        Err(e) => Err(e)
+      }
+    }).collect::<Result<Vec<Option<Value>>, String>>()?;
+
+    let end = self.columns.len();
+    self.insert_column(end, &derivation.name, values);
+    Ok(())
+  }
+
+  /* Adds the columns of the reference on the end, matching its rows to
+   * ours on the columns named in on, which both need to have. The
+   * values are compared as names are, ignoring case and punctuation.
+   * Rows with no match get blanks, and columns we already have are
+   * left as they are. */
+  pub fn enrich(&mut self, reference: &'a Reference, on: &[&str]) -> Result<(), String> {
+    let ours = on.iter().map(|&name| {
+      self.columns.iter().position(|c| c == name).ok_or_else(|| format!("The table has no {} column to join on", name))
+    }).collect::<Result<Vec<usize>, String>>()?;
+    let theirs = on.iter().map(|&name| {
+      reference.column(name).ok_or_else(|| format!("The reference has no {} column to join on", name))
+    }).collect::<Result<Vec<usize>, String>>()?;
+
+    let added = (0..reference.columns.len())
+      .filter(|&c| !self.columns.contains(&reference.columns[c]))
+      .collect::<Vec<usize>>();
+
+    let key = |values: Vec<String>| values.iter().map(|v| normalize_name(v)).collect::<Vec<String>>().join("\t");
+    let joined = left_join(&self.rows, &reference.rows,
+                           |row| key(ours.iter().map(|&c| row[c].map(|v| v.to_string()).unwrap_or_default()).collect()),
+                           |row| key(theirs.iter().map(|&c| row[c].clone()).collect()));
+    let matches = joined.into_iter().map(|(_, r)| r).collect::<Vec<Option<&Vec<String>>>>();
+
+    for c in added {
+      let values = matches.iter().map(|r| {
+        r.map(|row| row[c].as_str()).filter(|v| !v.is_empty()).map(Value::Text)
+      }).collect();
+      let end = self.columns.len();
+      self.insert_column(end, &reference.columns[c], values);
+    }
+    Ok(())
+  }
+
+  /* A copy of the table with only the named columns, in the order
+   * they are named. Flags move along with their cells. A table with no
+   * columns at all came from no records, so any names will do. */
+  pub fn project(&self, names: &[&str]) -> Result<Table<'a>, String> {
+    if self.columns.is_empty() {
+      return Ok(Table::new(names.iter().map(|n| n.to_string()).collect(), Vec::new()));
+    }
+
+    let picked = names.iter().map(|&name| {
+      self.columns.iter().position(|c| c == name).ok_or_else(|| {
+        format!("Unknown field {:?}, expected one of {}", name, self.columns.join(", "))
+      })
+    }).collect::<Result<Vec<usize>, String>>()?;
+
+    let rows = self.rows.iter().map(|row| {
+      picked.iter().map(|&c| row[c]).collect()
+    }).collect();
+    let mut table = Table::new(names.iter().map(|n| n.to_string()).collect(), rows);
+    table.flagged = self.flagged.iter().filter_map(|&(r, c)| {
+      picked.iter().position(|&p| p == c).map(|p| (r, p))
+    }).collect();
+    Ok(table)
+  }
+
+  /* A copy of the table with only the given rows, in the order they
+   * are given. Flags move along with their rows; the footer is left
+   * behind, since it was worked out from all of them. */
+  pub fn select(&self, rows: &[usize]) -> Table<'a> {
+    let mut table = Table::new(self.columns.clone(), rows.iter().map(|&r| self.rows[r].clone()).collect());
+    table.flagged = self.flagged.iter().filter_map(|&(r, c)| {
+      rows.iter().position(|&picked| picked == r).map(|p| (p, c))
+    }).collect();
+    table
+  }
+
+  /* Adds a footer with the total and the mean of each numeric column,
+   * labelled in the first text column. Only whole numbers, like runs,
+   * get a total, since a total of averages means nothing; nor does
+   * anything about ranks or line numbers, so columns called rank,
+   * ending in _rank or called source_line are left blank. */
+  pub fn add_totals(&mut self) {
+    let mut total = Vec::new();
+    let mut average = Vec::new();
+    let mut labelled = false;
+
+    for (c, name) in self.columns.iter().enumerate() {
+      let text = self.rows.iter().any(|row| matches!(row[c], Some(Value::Text(_))));
+      if text || self.rows.is_empty() {
+        total.push(if labelled { None } else { Some(Value::Text("total")) });
+        average.push(if labelled { None } else { Some(Value::Text("mean")) });
+        labelled = true;
+        continue;
+      }
+      if name == "rank" || name.ends_with("_rank") || name == "source_line" {
+        total.push(None);
+        average.push(None);
+        continue;
+      }
+
+      let xs = self.rows.iter().filter_map(|row| row[c].and_then(|v| v.as_f32())).collect::<Vec<f32>>();
+      let integers = self.rows.iter().all(|row| matches!(row[c], Some(Value::Integer(_)) | None));
+      let round = |x: f32| (x * 100.0).round() / 100.0;
+      total.push(if integers {
+        /* Added up exactly, as an f32 would start rounding past 2^24 */
+        let sum = self.rows.iter().filter_map(|row| match row[c] {
+          Some(Value::Integer(i)) => Some(i as u64),
+          _ => None
+        }).sum::<u64>();
+        Some(u32::try_from(sum).map(Value::Integer).unwrap_or(Value::Float(sum as f32)))
+      } else {
+        None
+      });
+      average.push(mean(&xs).map(|x| Value::Float(round(x))));
+    }
+
+    self.footer.push(total);
+    self.footer.push(average);
+  }
+
+  /* Marks the named column of a row as bad */
+  pub fn flag(&mut self, row: usize, column: &str) {
+    if let Some(c) = self.columns.iter().position(|name| name == column) {
+      self.flagged.insert((row, c));
+    }
+  }
+
+  /* Every cell as text, with missing ones left blank, and the footer
+   * after the rows */
+  fn cells(&self) -> Vec<Vec<String>> {
+    self.rows.iter().chain(self.footer.iter()).map(|row| {
+      row.iter().map(|v| match *v {
+        Some(ref x) => x.to_string(),
+        None => String::new()
+      }).collect()
+    }).collect()
+  }
+
+  /* Renders the table. When colour is on, the header is bold, the top
+   * three of any rank column are highlighted, flagged cells are red and
+   * the footer is bold.
+   * Colour only makes sense for the Table format. */
+  pub fn render(&self, format: Format, style: &Style) -> String {
+    match format {
+      Format::Table => {
+        let body = self.cells();
+        let rank_column = self.columns.iter().position(|c| c == "rank");
+        layout(&self.columns, &body, |row, column, c| {
+          match row {
+            None => style.paint(BOLD, c),
+            Some(r) if r >= self.rows.len() => style.paint(BOLD, c),
+            Some(r) if self.flagged.contains(&(r, column)) => style.paint(RED, c),
+            Some(r) if Some(column) == rank_column => match self.rows[r][column] {
+              Some(Value::Integer(rank)) if rank <= 3 => style.paint(YELLOW, c),
+              _ => c.to_string()
+            },
+            Some(_) => c.to_string()
+          }
+        })
+      },
+      Format::Markdown => {
+        /* A column is numeric if every value in it is a number */
+        let numeric = (0..self.columns.len()).map(|c| {
+          self.rows.iter().all(|row| !matches!(row[c], Some(Value::Text(_))))
+        }).collect::<Vec<bool>>();
+        markdown_table(&self.columns, &self.cells(), &numeric)
+      },
+      Format::Json => {
+        let objects = self.rows.iter().map(|row| {
+          let fields = self.columns.iter().zip(row.iter()).map(|(c, v)| {
+            format!("{}: {}", json_string(c), match *v {
+              Some(ref x) => json_value(x),
+              None => "null".to_string()
+            })
+          }).collect::<Vec<String>>();
+          format!("  {{{}}}", fields.join(", "))
+        }).collect::<Vec<String>>();
+
+        if objects.is_empty() {
+          "[]\n".to_string()
+        } else {
+          format!("[\n{}\n]\n", objects.join(",\n"))
+        }
+      }
+    }
+  }
+}
+
+/* Anything that can turn a Table into text. The built in formats are
+ * formatters, and others can be registered alongside them, which is
+ * how formats that live outside this crate get plugged in. */
+pub trait Formatter {
+  /* What --format calls it */
+  fn name(&self) -> &str;
+
+  fn render(&self, table: &Table, style: &Style) -> Result<String, String>;
+}
+
+impl Formatter for Format {
+  fn name(&self) -> &str {
+    match *self {
+      Format::Table => "table",
+      Format::Json => "json",
+      Format::Markdown => "markdown"
+    }
+  }
+
+  fn render(&self, table: &Table, style: &Style) -> Result<String, String> {
+    Ok(table.render(*self, style))
+  }
+}
+
+/* Every formatter we know about, looked up by name */
+pub struct Registry {
+  formatters: Vec<Box<dyn Formatter>>
+}
+
+impl Registry {
+  /* A registry holding the built in formats */
+  pub fn new() -> Registry {
+    Registry {
+      formatters: vec![Box::new(Format::Table), Box::new(Format::Json), Box::new(Format::Markdown)]
+    }
+  }
+
+  /* Adds a formatter. The first one registered under a name wins, so
+   * nothing can replace the built in formats. Returns whether it was
+   * added. */
+  pub fn register(&mut self, formatter: Box<dyn Formatter>) -> bool {
+    if self.get(formatter.name()).is_some() {
+      return false;
+    }
+    self.formatters.push(formatter);
+    true
+  }
+
+  pub fn get(&self, name: &str) -> Option<&dyn Formatter> {
+    self.formatters.iter().find(|f| f.name() == name).map(|f| f.as_ref())
+  }
+
+  pub fn names(&self) -> Vec<&str> {
+    self.formatters.iter().map(|f| f.name()).collect()
+  }
+}
+
+impl Default for Registry {
+  fn default() -> Registry {
+    Registry::new()
+  }
+}
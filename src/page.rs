@@ -0,0 +1,19 @@
+/* Cuts a window out of a result set after it has been sorted:
+ *
+ * offset skips that many records from the front,
+ * limit keeps at most that many of what is left,
+ * tail keeps at most that many from the end of what is left.
+ *
+ * Everything here is a sub-slice of the input, so nothing is copied
+ * and asking for more than there is just gives back less. */
+pub fn paginate<T>(xs: &[T], offset: usize, limit: Option<usize>, tail: Option<usize>) -> &[T] {
+  let xs = &xs[offset.min(xs.len())..];
+  let xs = match limit {
+    Some(n) => &xs[..n.min(xs.len())],
+    None => xs
+  };
+  match tail {
+    Some(n) => &xs[xs.len() - n.min(xs.len())..],
+    None => xs
+  }
+}
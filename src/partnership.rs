@@ -0,0 +1,124 @@
+use std::cmp::Reverse;
+
+use error::ParseError;
+use scorecard::ScorecardEntry;
+use stats::mean;
+
+/* Two batsmen's runs together, from when the second of them came in
+ * until one of them was out */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Partnership<'a> {
+  /* Which wicket fell at the end of it: 1 for the openers, and so on */
+  pub wicket: u32,
+  /* The pair, as (initials, surname), in batting order */
+  pub batsmen: [(&'a str, &'a str); 2],
+  pub runs: u32
+}
+
+impl<'a> Partnership<'a> {
+  /* "AN Cook & AJ Strauss", with the names in alphabetical order, so
+   * the same pair always gets the same name whoever batted first */
+  pub fn pair(&self) -> String {
+    let mut names = self.batsmen.iter().map(|&(i, s)| format!("{} {}", i, s)).collect::<Vec<String>>();
+    names.sort();
+    names.join(" & ")
+  }
+}
+
+/* Works out the partnerships of one innings from its scorecard, which
+ * has to list the batsmen in batting order with the team's score at
+ * each wicket (the fall field). The openers start at the crease, and
+ * whenever a wicket falls the next batsman in the list replaces
+ * whoever was out. Wickets are taken in order of the score they fell
+ * at. The last partnership is left out if it was never broken, since
+ * without the team's final total there is no knowing what it made. */
+pub fn partnerships<'a>(innings: &[ScorecardEntry<'a>]) -> Result<Vec<Partnership<'a>>, ParseError> {
+  let mut outs = Vec::new();
+  for (i, e) in innings.iter().enumerate() {
+    if e.dismissal.is_out() {
+      match e.fall {
+        Some(fall) => outs.push((fall, i)),
+        None => return Err(ParseError::Malformed(format!(
+          "{} {} was out, but there is no score for when", e.initials, e.surname)))
+      }
+    }
+  }
+  /* sort_by_key is stable, so wickets at the same score stay in
+   * batting order */
+  outs.sort_by_key(|&(fall, _)| fall);
+
+  let name = |i: usize| (innings[i].initials, innings[i].surname);
+  let mut crease = [0, 1];
+  let mut score = 0;
+  let mut result = Vec::new();
+  for (wicket, &(fall, out)) in outs.iter().enumerate() {
+    /* Two batsmen open, and one more comes in for each wicket */
+    let next = wicket + 2;
+    let end = match crease.iter().position(|&c| c == out) {
+      Some(end) if out < next => end,
+      _ => return Err(ParseError::Malformed(format!(
+        "{} {} was out without being at the crease", innings[out].initials, innings[out].surname)))
+    };
+
+    let mut pair = [name(crease[0]), name(crease[1])];
+    if crease[0] > crease[1] {
+      pair.swap(0, 1);
+    }
+    result.push(Partnership { wicket: wicket as u32 + 1, batsmen: pair, runs: fall - score });
+    score = fall;
+    crease[end] = next;
+  }
+  Ok(result)
+}
+
+/* The biggest partnership for each wicket, in wicket order. The first
+ * one found wins a tie. */
+pub fn best_by_wicket<'p, 'a>(partnerships: &'p [Partnership<'a>]) -> Vec<&'p Partnership<'a>> {
+  let mut best: Vec<&Partnership> = Vec::new();
+  for p in partnerships {
+    match best.iter().position(|b| b.wicket == p.wicket) {
+      Some(i) if best[i].runs < p.runs => best[i] = p,
+      Some(_) => (),
+      None => best.push(p)
+    }
+  }
+  best.sort_by_key(|p| p.wicket);
+  best
+}
+
+/* How a pair of batsmen have done together */
+#[derive(Debug, Clone, PartialEq)]
+pub struct PairSummary {
+  pub pair: String,
+  pub partnerships: u32,
+  pub runs: u32,
+  pub highest: u32,
+  pub average: f32
+}
+
+/* Every pair's partnerships added up, most runs together first */
+pub fn by_pair(partnerships: &[Partnership]) -> Vec<PairSummary> {
+  let mut pairs: Vec<(String, Vec<u32>)> = Vec::new();
+  for p in partnerships {
+    let pair = p.pair();
+    match pairs.iter_mut().find(|(name, _)| *name == pair) {
+      Some(entry) => entry.1.push(p.runs),
+      None => pairs.push((pair, vec![p.runs]))
+    }
+  }
+
+  let mut summaries = pairs.into_iter().map(|(pair, runs)| PairSummary {
+    pair,
+    partnerships: runs.len() as u32,
+    runs: runs.iter().sum(),
+    highest: runs.iter().cloned().max().unwrap_or(0),
+    average: mean(&runs.iter().map(|&r| r as f32).collect::<Vec<f32>>()).unwrap_or(0.0)
+  }).collect::<Vec<PairSummary>>();
+  summaries.sort_by_key(|s| Reverse(s.runs));
+  summaries
+}
+
+/* The mean size of a partnership, or None if there were none */
+pub fn average(partnerships: &[Partnership]) -> Option<f32> {
+  mean(&partnerships.iter().map(|p| p.runs as f32).collect::<Vec<f32>>())
+}
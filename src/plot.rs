@@ -0,0 +1,97 @@
+/* Draws a scatter plot out of characters, with xs along the bottom and
+ * ys up the side. Points that land in the same cell are drawn as a
+ * number (or + for more than nine), so clusters stay visible. */
+pub fn scatter(xs: &[f32], ys: &[f32], width: usize, height: usize) -> String {
+  let bounds = |v: &[f32]| {
+    v.iter().fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), &x| (lo.min(x), hi.max(x)))
+  };
+  let (x_lo, x_hi) = bounds(xs);
+  let (y_lo, y_hi) = bounds(ys);
+
+  /* Which cell along an axis of n cells a value falls into. A range of
+   * zero width puts everything in the middle. */
+  let cell = |v: f32, lo: f32, hi: f32, n: usize| {
+    if hi > lo {
+      (((v - lo) / (hi - lo)) * (n - 1) as f32).round() as usize
+    } else {
+      n / 2
+    }
+  };
+
+  let mut counts = vec![vec![0; width]; height];
+  if width > 0 && height > 0 {
+    for (&x, &y) in xs.iter().zip(ys) {
+      counts[height - 1 - cell(y, y_lo, y_hi, height)][cell(x, x_lo, x_hi, width)] += 1;
+    }
+  }
+
+  let mut out = String::new();
+  for (i, row) in counts.iter().enumerate() {
+    let label = if i == 0 {
+      format!("{:>10.2}", y_hi)
+    } else if i == height - 1 {
+      format!("{:>10.2}", y_lo)
+    } else {
+      " ".repeat(10)
+    };
+    let line = row.iter().map(|&c| match c {
+      0 => ' ',
+      1 => '*',
+      2..=9 => ::std::char::from_digit(c, 10).unwrap_or('+'),
+      _ => '+'
+    }).collect::<String>();
+    out.push_str(&format!("{} |{}\n", label, line.trim_end()));
+  }
+  out.push_str(&format!("{} +{}\n", " ".repeat(10), "-".repeat(width)));
+  out.push_str(&format!("{} {:<w$.2}{:>.2}\n",
+                        " ".repeat(10),
+                        x_lo,
+                        x_hi,
+                        w = width.saturating_sub(format!("{:.2}", x_hi).len()) + 1));
+  out
+}
+
+/* Escapes text for use inside an SVG (or any XML) document */
+fn xml_escape(s: &str) -> String {
+  s.replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+}
+
+/* A horizontal bar chart as a standalone SVG document, one bar per
+ * label, drawn top to bottom in the order given. Bars are measured
+ * from zero, so negative values get no bar at all. */
+pub fn bar_chart_svg(title: &str, labels: &[String], values: &[f32]) -> String {
+  const WIDTH: f32 = 640.0;
+  const LABEL_WIDTH: f32 = 160.0;
+  const VALUE_WIDTH: f32 = 70.0;
+  const BAR_HEIGHT: f32 = 24.0;
+  const TOP: f32 = 40.0;
+
+  let max = values.iter().cloned().fold(0.0, f32::max);
+  let scale = if max > 0.0 { (WIDTH - LABEL_WIDTH - VALUE_WIDTH) / max } else { 0.0 };
+  let height = TOP + BAR_HEIGHT * labels.len() as f32 + 10.0;
+
+  let mut out = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+                         viewBox=\"0 0 {} {}\" font-family=\"sans-serif\" font-size=\"12\">\n",
+                        WIDTH, height, WIDTH, height);
+  out.push_str(&format!("  <text x=\"{}\" y=\"24\" font-size=\"16\" font-weight=\"bold\">{}</text>\n",
+                        10, xml_escape(title)));
+
+  for (i, (label, &value)) in labels.iter().zip(values).enumerate() {
+    let y = TOP + BAR_HEIGHT * i as f32;
+    let length = (value * scale).max(0.0);
+    let middle = y + BAR_HEIGHT / 2.0 + 4.0;
+
+    out.push_str(&format!("  <text x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>\n",
+                          LABEL_WIDTH - 8.0, middle, xml_escape(label)));
+    out.push_str(&format!("  <rect x=\"{}\" y=\"{}\" width=\"{:.1}\" height=\"{}\" fill=\"#4a7ebb\"/>\n",
+                          LABEL_WIDTH, y + 3.0, length, BAR_HEIGHT - 6.0));
+    out.push_str(&format!("  <text x=\"{:.1}\" y=\"{}\">{}</text>\n",
+                          LABEL_WIDTH + length + 6.0, middle, value));
+  }
+
+  out.push_str("</svg>\n");
+  out
+}
@@ -0,0 +1,33 @@
+use std::io;
+use std::io::Read;
+
+/* Wraps any reader and tells a callback how many bytes have been read
+ * through it so far. The callback can then drive a progress bar (or
+ * anything else) without the code doing the reading having to know
+ * about it. */
+pub struct ProgressReader<R, F> {
+  inner: R,
+  read: u64,
+  report: F
+}
+
+impl<R: Read, F: FnMut(u64)> ProgressReader<R, F> {
+  pub fn new(inner: R, report: F) -> ProgressReader<R, F> {
+    ProgressReader {
+      inner,
+      read: 0,
+      report
+    }
+  }
+}
+
+/* Implementing Read means a ProgressReader can go anywhere a File
+ * could, read_to_string and BufReader included */
+impl<R: Read, F: FnMut(u64)> Read for ProgressReader<R, F> {
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    let n = self.inner.read(buf)?;
+    self.read += n as u64;
+    (self.report)(self.read);
+    Ok(n)
+  }
+}
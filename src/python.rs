@@ -0,0 +1,104 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use batsman::Batsman;
+use numeric::average_f32;
+use record::Record;
+use sort::sorted;
+use stats;
+
+/* Parses a whole batting file, turning the first bad line into a
+ * ValueError which says which line it was */
+fn parse_all(text: &str) -> PyResult<Vec<Batsman<'_>>> {
+  text.lines().enumerate().map(|(i, l)| {
+    Batsman::parse(l).map_err(|e| PyValueError::new_err(format!("Line {}: {}", i + 1, e)))
+  }).collect()
+}
+
+/* Each batsman becomes a dict, so that a list of them can be handed
+ * straight to pandas.DataFrame */
+fn to_dicts<'py>(py: Python<'py>, batsmen: &[Batsman]) -> PyResult<Vec<Bound<'py, PyDict>>> {
+  batsmen.iter().map(|b| {
+    let d = PyDict::new(py);
+    d.set_item("initials", b.initials)?;
+    d.set_item("surname", b.surname)?;
+    d.set_item("runs", b.runs)?;
+    /* Python floats are f64, and widening 46.33f32 directly gives
+     * 46.33000183..., so go via the shortest decimal form instead */
+    d.set_item("average", b.average.to_string().parse::<f64>().unwrap_or(average_f32(b.average) as f64))?;
+    Ok(d)
+  }).collect()
+}
+
+/* parse_batsmen(text) -> list of dicts, in file order */
+#[pyfunction]
+fn parse_batsmen<'py>(py: Python<'py>, text: &str) -> PyResult<Vec<Bound<'py, PyDict>>> {
+  to_dicts(py, &parse_all(text)?)
+}
+
+/* sort_batsmen(text, field, descending=False) -> list of dicts */
+#[pyfunction]
+#[pyo3(signature = (text, field, descending = false))]
+fn sort_batsmen<'py>(py: Python<'py>, text: &str, field: &str, descending: bool) -> PyResult<Vec<Bound<'py, PyDict>>> {
+  let batsmen = parse_all(text)?;
+  if batsmen.first().is_some_and(|b| b.field(field).is_none()) {
+    return Err(PyValueError::new_err(format!("Unknown field {:?}", field)));
+  }
+
+  let batsmen = if descending {
+    sorted(batsmen, |lhs, rhs| rhs.cmp_by(lhs, field))
+  } else {
+    sorted(batsmen, |lhs, rhs| lhs.cmp_by(rhs, field))
+  };
+  to_dicts(py, &batsmen)
+}
+
+/* The stats functions take a list of floats and give back None (rather
+ * than raising) wherever the Rust versions do */
+#[pyfunction]
+fn mean(xs: Vec<f32>) -> Option<f32> {
+  stats::mean(&xs)
+}
+
+#[pyfunction]
+fn median(xs: Vec<f32>) -> Option<f32> {
+  stats::median(&xs)
+}
+
+#[pyfunction]
+fn std_dev(xs: Vec<f32>) -> Option<f32> {
+  stats::std_dev(&xs)
+}
+
+#[pyfunction]
+fn quantile(xs: Vec<f32>, q: f32) -> Option<f32> {
+  stats::quantile(&xs, q)
+}
+
+#[pyfunction]
+fn pearson(xs: Vec<f32>, ys: Vec<f32>) -> Option<f32> {
+  stats::pearson(&xs, &ys)
+}
+
+/* linear_fit(xs, ys) -> (slope, intercept) */
+#[pyfunction]
+fn linear_fit(xs: Vec<f32>, ys: Vec<f32>) -> Option<(f32, f32)> {
+  stats::linear_fit(&xs, &ys)
+}
+
+/* The Python module. Its name has to match the library's name for
+ * "import getting_started" to find it. */
+#[pymodule]
+#[pyo3(name = "getting_started")]
+fn python_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+  m.add_function(wrap_pyfunction!(self::parse_batsmen, m)?)?;
+  m.add_function(wrap_pyfunction!(self::sort_batsmen, m)?)?;
+  m.add_function(wrap_pyfunction!(self::mean, m)?)?;
+  m.add_function(wrap_pyfunction!(self::median, m)?)?;
+  m.add_function(wrap_pyfunction!(self::std_dev, m)?)?;
+  m.add_function(wrap_pyfunction!(self::quantile, m)?)?;
+  m.add_function(wrap_pyfunction!(self::pearson, m)?)?;
+  m.add_function(wrap_pyfunction!(self::linear_fit, m)?)?;
+  Ok(())
+}
@@ -0,0 +1,63 @@
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use invariants::check_ranks;
+
+/* How to number records which tie with each other. With runs of
+ * 100, 90, 90 and 80:
+ *
+ * Standard: 1, 2, 2, 4 (the next rank skips over the tie)
+ * Dense:    1, 2, 2, 3 (the next rank carries on from the tie)
+ * Ordinal:  1, 2, 3, 4 (ties are broken by position in the list)
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RankMethod {
+  Standard,
+  Dense,
+  Ordinal
+}
+
+impl FromStr for RankMethod {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<RankMethod, String> {
+    match s {
+      "standard" => Ok(RankMethod::Standard),
+      "dense" => Ok(RankMethod::Dense),
+      "ordinal" => Ok(RankMethod::Ordinal),
+      other => Err(format!("Unknown rank method {:?}, expected standard, dense or ordinal", other))
+    }
+  }
+}
+
+/* Works out the rank of each element of an already sorted slice. Two
+ * neighbours are tied when cmp says they are Equal, so pass the same
+ * comparison that was used for sorting.
+ *
+ * Notice that this is a fold over the neighbouring pairs: each rank
+ * only depends on the previous rank and whether we tied with the
+ * previous element. */
+pub fn rank<T, F>(sorted: &[T], method: RankMethod, mut cmp: F) -> Vec<u32>
+  where F: FnMut(&T, &T) -> Ordering
+{
+  let mut ranks = Vec::with_capacity(sorted.len());
+
+  for (i, x) in sorted.iter().enumerate() {
+    let position = i as u32 + 1;
+    let r = match ranks.last() {
+      None => 1,
+      Some(&previous) => {
+        let tied = cmp(&sorted[i - 1], x) == Ordering::Equal;
+        match method {
+          RankMethod::Ordinal => position,
+          RankMethod::Standard => if tied { previous } else { position },
+          RankMethod::Dense => if tied { previous } else { previous + 1 }
+        }
+      }
+    };
+    ranks.push(r);
+  }
+
+  debug_assert_eq!(check_ranks(&ranks, method), Ok(()));
+  ranks
+}
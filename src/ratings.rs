@@ -0,0 +1,99 @@
+use std::cmp::Ordering;
+
+use innings::Innings;
+use record::{Record, Value};
+
+/* Where every player starts, and how far one match can move them */
+pub const INITIAL_RATING: f32 = 1500.0;
+pub const K: f32 = 32.0;
+
+/* A player's Elo rating: how they have been batting lately compared
+ * to the players they shared a match with */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rating<'a> {
+  pub initials: &'a str,
+  pub surname: &'a str,
+  pub rating: f32,
+  pub matches: u32
+}
+
+const RATING_FIELDS: &[&str] = &["initials", "surname", "rating", "matches"];
+
+impl<'a> Record for Rating<'a> {
+  fn field_names(&self) -> &'static [&'static str] {
+    RATING_FIELDS
+  }
+
+  fn field(&self, name: &str) -> Option<Value<'_>> {
+    match name {
+      "initials" => Some(Value::Text(self.initials)),
+      "surname" => Some(Value::Text(self.surname)),
+      "rating" => Some(Value::Float(self.rating)),
+      "matches" => Some(Value::Integer(self.matches)),
+      _ => None
+    }
+  }
+}
+
+/* The chance Elo gives a player rated a of outscoring one rated b */
+fn expected(a: f32, b: f32) -> f32 {
+  1.0 / (1.0 + 10f32.powf((b - a) / 400.0))
+}
+
+/* Elo was made for games between two players, so a match is treated
+ * as a game between every pair of batsmen in it, won by whichever of
+ * the two made more runs. Each player's rating then moves by K times
+ * how much better (or worse) they did than their rating expected,
+ * averaged over everyone they played against, so a match counts the
+ * same however many batsmen were in it. */
+fn play<'a>(ratings: &mut Vec<Rating<'a>>, game: &[Innings<'a>]) {
+  let players = game.iter().map(|i| {
+    match ratings.iter().position(|r| r.initials == i.initials && r.surname == i.surname) {
+      Some(p) => p,
+      None => {
+        ratings.push(Rating { initials: i.initials, surname: i.surname, rating: INITIAL_RATING, matches: 0 });
+        ratings.len() - 1
+      }
+    }
+  }).collect::<Vec<usize>>();
+
+  /* Everyone's change is worked out from the ratings before the match,
+   * so the order they are listed in makes no difference */
+  let before = players.iter().map(|&p| ratings[p].rating).collect::<Vec<f32>>();
+  let opponents = (game.len() - 1) as f32;
+  for (i, &p) in players.iter().enumerate() {
+    if opponents > 0.0 {
+      let (mut actual, mut predicted) = (0.0, 0.0);
+      for (j, other) in game.iter().enumerate().filter(|&(j, _)| j != i) {
+        actual += match game[i].runs.cmp(&other.runs) {
+          Ordering::Greater => 1.0,
+          Ordering::Equal => 0.5,
+          Ordering::Less => 0.0
+        };
+        predicted += expected(before[i], before[j]);
+      }
+      ratings[p].rating += K * (actual - predicted) / opponents;
+    }
+    ratings[p].matches += 1;
+  }
+}
+
+/* Everyone's rating after playing through the innings in date order,
+ * best first. Innings on the same date are taken to be the same
+ * match. With as_of, matches after that date (written the same
+ * year-month-day way as the innings) are left out, which gives the
+ * ratings as they stood then. */
+pub fn ratings<'a>(innings: &[Innings<'a>], as_of: Option<&str>) -> Vec<Rating<'a>> {
+  let mut played = innings.iter()
+    .filter(|i| as_of.is_none_or(|d| i.date <= d))
+    .cloned()
+    .collect::<Vec<Innings>>();
+  played.sort_by(|lhs, rhs| lhs.date.cmp(rhs.date));
+
+  let mut result = Vec::new();
+  for game in played.chunk_by(|lhs, rhs| lhs.date == rhs.date) {
+    play(&mut result, game);
+  }
+  result.sort_by(|lhs, rhs| rhs.cmp_by(lhs, "rating"));
+  result
+}
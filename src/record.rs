@@ -0,0 +1,131 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+
+#[cfg(feature = "decimal")]
+use rust_decimal::Decimal;
+
+use numeric::Average;
+#[cfg(feature = "decimal")]
+use numeric::average_f32;
+
+/* A single field pulled out of a record. Records have different shapes,
+ * but every field we care about is either a bit of text or a number, so
+ * generic code can work in terms of Value without knowing which struct
+ * it came from. */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Value<'a> {
+  Text(&'a str),
+  Integer(u32),
+  Float(f32),
+  /* A batting average, with --features decimal */
+  #[cfg(feature = "decimal")]
+  Decimal(Decimal)
+}
+
+impl<'a> Value<'a> {
+  /* A batting average, as whichever kind of number averages are */
+  #[cfg(not(feature = "decimal"))]
+  pub fn average(a: Average) -> Value<'a> {
+    Value::Float(a)
+  }
+
+  #[cfg(feature = "decimal")]
+  pub fn average(a: Average) -> Value<'a> {
+    Value::Decimal(a)
+  }
+
+  /* Numbers as an f32 so they can be used in arithmetic, text is None */
+  pub fn as_f32(&self) -> Option<f32> {
+    match *self {
+      Value::Text(_) => None,
+      Value::Integer(i) => Some(i as f32),
+      Value::Float(x) => Some(x),
+      #[cfg(feature = "decimal")]
+      Value::Decimal(d) => Some(average_f32(d))
+    }
+  }
+
+  /* Floats only have a partial ordering (NaN is not comparable to
+   * anything), so we fall back to treating incomparable values as
+   * equal. Comparing text against a number also makes no sense, so
+   * those are considered equal as well. */
+  pub fn compare(&self, other: &Value) -> Ordering {
+    match (*self, *other) {
+      (Value::Text(a), Value::Text(b)) => a.cmp(b),
+      (Value::Integer(a), Value::Integer(b)) => a.cmp(&b),
+      (Value::Float(a), Value::Float(b)) =>
+        a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+      (Value::Integer(a), Value::Float(b)) =>
+        (a as f32).partial_cmp(&b).unwrap_or(Ordering::Equal),
+      (Value::Float(a), Value::Integer(b)) =>
+        a.partial_cmp(&(b as f32)).unwrap_or(Ordering::Equal),
+      #[cfg(feature = "decimal")]
+      (Value::Decimal(a), Value::Decimal(b)) => a.cmp(&b),
+      #[cfg(feature = "decimal")]
+      (Value::Decimal(_), _) | (_, Value::Decimal(_)) => match (self.as_f32(), other.as_f32()) {
+        (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+        _ => Ordering::Equal
+      },
+      _ => Ordering::Equal
+    }
+  }
+}
+
+impl<'a> fmt::Display for Value<'a> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      Value::Text(s) => write!(f, "{}", s),
+      Value::Integer(i) => write!(f, "{}", i),
+      Value::Float(x) => write!(f, "{}", x),
+      #[cfg(feature = "decimal")]
+      Value::Decimal(d) => write!(f, "{}", d)
+    }
+  }
+}
+
+/* Record is implemented by every kind of row we read in. It lets the
+ * filtering, sorting and output code ask for a field by name instead
+ * of being written once per struct.
+ *
+ * Notice that only field_names and field have to be implemented, the
+ * rest of the trait has default implementations written in terms of
+ * those two. */
+pub trait Record {
+  /* The names of every field, in the order they should be written out */
+  fn field_names(&self) -> &'static [&'static str];
+
+  /* Look up a field by name, returning None if there is no such field */
+  fn field(&self, name: &str) -> Option<Value<'_>>;
+
+  /* Compare two records on a single field. Records without the field
+   * sort before records that have it. */
+  fn cmp_by(&self, other: &Self, name: &str) -> Ordering where Self: Sized {
+    match (self.field(name), other.field(name)) {
+      (Some(a), Some(b)) => a.compare(&b),
+      (None, Some(_)) => Ordering::Less,
+      (Some(_), None) => Ordering::Greater,
+      (None, None) => Ordering::Equal
+    }
+  }
+
+  /* Serialization hook: every field rendered as a string, in the same
+   * order as field_names. Writers can override this per type if the
+   * default rendering is not what they want. */
+  fn to_row(&self) -> Vec<String> {
+    self.field_names().iter().map(|name| {
+      match self.field(name) {
+        Some(v) => v.to_string(),
+        None => String::new()
+      }
+    }).collect()
+  }
+}
+
+/* Pulls one numeric field out of every record, ready to hand to the
+ * stats module. If any record is missing the field, or it is text,
+ * there is no column of numbers to be had. */
+pub fn numbers<R: Record>(records: &[R], name: &str) -> Option<Vec<f32>> {
+  records.iter().map(|r| r.field(name).and_then(|v| v.as_f32())).collect()
+}
@@ -0,0 +1,70 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/* A small pseudo-random number generator (SplitMix64). It is nowhere
+ * near good enough for cryptography, but it is fast, has no
+ * dependencies and, most importantly, the same seed always produces the
+ * same sequence of numbers on every machine. That is what makes
+ * --seed runs reproducible. */
+#[derive(Debug, Clone)]
+pub struct Rng {
+  state: u64
+}
+
+impl Rng {
+  pub fn new(seed: u64) -> Rng {
+    Rng { state: seed }
+  }
+
+  /* Seeds from the clock, for when the caller does not care about
+   * getting the same answer twice */
+  pub fn from_time() -> Rng {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    Rng::new(now.as_secs() ^ (now.subsec_nanos() as u64).rotate_left(32))
+  }
+
+  pub fn next_u64(&mut self) -> u64 {
+    self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = self.state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+  }
+
+  /* A number in [0, 1) */
+  pub fn next_f64(&mut self) -> f64 {
+    (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+  }
+
+  /* A number in [0, n). Multiplying up into 128 bits and keeping the
+   * top half avoids the bias that % n would have. */
+  pub fn below(&mut self, n: usize) -> usize {
+    ((self.next_u64() as u128 * n as u128) >> 64) as usize
+  }
+
+  /* Fisher-Yates: walk backwards through the slice, swapping each
+   * element with a random one at or before it */
+  pub fn shuffle<T>(&mut self, xs: &mut [T]) {
+    for i in (1..xs.len()).rev() {
+      let j = self.below(i + 1);
+      xs.swap(i, j);
+    }
+  }
+
+  /* Picks n elements at random without replacement. The picked elements
+   * keep the order they had in xs, so sampling an already sorted list
+   * gives a sorted sample. */
+  pub fn sample<T: Clone>(&mut self, xs: &[T], n: usize) -> Vec<T> {
+    let mut indices = (0..xs.len()).collect::<Vec<usize>>();
+    let n = n.min(xs.len());
+
+    /* Only the first n steps of a shuffle are needed to choose n */
+    for i in 0..n {
+      let j = i + self.below(xs.len() - i);
+      indices.swap(i, j);
+    }
+
+    let mut chosen = indices[..n].to_vec();
+    chosen.sort();
+    chosen.into_iter().map(|i| xs[i].clone()).collect()
+  }
+}
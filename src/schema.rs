@@ -0,0 +1,130 @@
+use std::fmt;
+
+use output::json_string;
+
+/* The kinds of column we can tell apart just by looking at the text.
+ * They are ordered from most to least specific: every Integer is also
+ * a valid Number, and everything is valid Text. */
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub enum ColumnType {
+  Integer,
+  Number,
+  Text
+}
+
+impl ColumnType {
+  fn of(cell: &str) -> ColumnType {
+    if cell.parse::<i64>().is_ok() {
+      ColumnType::Integer
+    } else if cell.parse::<f64>().is_ok() {
+      ColumnType::Number
+    } else {
+      ColumnType::Text
+    }
+  }
+
+  /* The name JSON Schema uses for this type */
+  pub fn json_type(&self) -> &'static str {
+    match *self {
+      ColumnType::Integer => "integer",
+      ColumnType::Number => "number",
+      ColumnType::Text => "string"
+    }
+  }
+}
+
+impl fmt::Display for ColumnType {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.json_type())
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+  pub name: String,
+  pub kind: ColumnType,
+  /* Whether any sampled row left this column empty or missing */
+  pub nullable: bool
+}
+
+fn cells(l: &str) -> Vec<&str> {
+  l.split(',').map(|x| x.trim()).collect()
+}
+
+/* Looks at up to `sample` lines and works out what each comma separated
+ * column holds. Each column starts out with no type at all and gets
+ * widened just enough to fit every value we see, so a column of whole
+ * numbers with one 46.33 in it becomes a Number.
+ *
+ * If the first line has text where every other line has numbers, it is
+ * taken to be a header and the column names come from it. Otherwise the
+ * columns are just numbered. */
+pub fn infer<'a, I>(lines: I, sample: usize) -> Vec<Column>
+  where I: Iterator<Item = &'a str>
+{
+  let rows = lines.filter(|l| !l.trim().is_empty())
+    .take(sample)
+    .map(cells)
+    .collect::<Vec<Vec<&str>>>();
+
+  let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+
+  let widen = |rows: &[Vec<&str>], i: usize| {
+    rows.iter().fold(None, |kind: Option<ColumnType>, r| {
+      match r.get(i) {
+        Some(cell) if !cell.is_empty() => {
+          let k = ColumnType::of(cell);
+          Some(match kind {
+            Some(current) if current > k => current,
+            _ => k
+          })
+        },
+        _ => kind
+      }
+    })
+  };
+
+  let has_header = rows.len() > 1 && (0..width).any(|i| {
+    let first = rows[0].get(i).map(|c| ColumnType::of(c));
+    first == Some(ColumnType::Text) && widen(&rows[1..], i).is_some_and(|k| k < ColumnType::Text)
+  });
+
+  let (names, body) = if has_header {
+    (Some(&rows[0]), &rows[1..])
+  } else {
+    (None, &rows[..])
+  };
+
+  (0..width).map(|i| {
+    Column {
+      name: match names.and_then(|n| n.get(i)) {
+        Some(n) if !n.is_empty() => n.to_string(),
+        _ => format!("column{}", i + 1)
+      },
+      kind: widen(body, i).unwrap_or(ColumnType::Text),
+      nullable: body.iter().any(|r| r.get(i).is_none_or(|c| c.is_empty()))
+    }
+  }).collect()
+}
+
+/* Describes one row as a JSON Schema object. Nullable columns accept
+ * null as well as their own type, and every other column is required. */
+pub fn json_schema(columns: &[Column]) -> String {
+  let properties = columns.iter().map(|c| {
+    let kind = if c.nullable {
+      format!("[{}, \"null\"]", json_string(c.kind.json_type()))
+    } else {
+      json_string(c.kind.json_type())
+    };
+    format!("    {}: {{\"type\": {}}}", json_string(&c.name), kind)
+  }).collect::<Vec<String>>();
+
+  let required = columns.iter()
+    .filter(|c| !c.nullable)
+    .map(|c| json_string(&c.name))
+    .collect::<Vec<String>>();
+
+  format!("{{\n  \"$schema\": \"http://json-schema.org/draft-07/schema#\",\n  \"type\": \"object\",\n  \"properties\": {{\n{}\n  }},\n  \"required\": [{}]\n}}\n",
+          properties.join(",\n"),
+          required.join(", "))
+}
@@ -0,0 +1,159 @@
+use std::str::FromStr;
+
+use batsman::Batsman;
+use error::ParseError;
+use line;
+use numeric::average_of;
+use record::{Record, Value};
+
+/* How an innings ended */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Dismissal {
+  NotOut,
+  Bowled,
+  Caught,
+  Lbw,
+  Stumped,
+  RunOut,
+  HitWicket,
+  /* Retiring hurt does not count as a dismissal */
+  RetiredHurt
+}
+
+impl Dismissal {
+  pub fn name(&self) -> &'static str {
+    match *self {
+      Dismissal::NotOut => "not out",
+      Dismissal::Bowled => "bowled",
+      Dismissal::Caught => "caught",
+      Dismissal::Lbw => "lbw",
+      Dismissal::Stumped => "stumped",
+      Dismissal::RunOut => "run out",
+      Dismissal::HitWicket => "hit wicket",
+      Dismissal::RetiredHurt => "retired hurt"
+    }
+  }
+
+  /* Whether the innings counts towards the batsman's average */
+  pub fn is_out(&self) -> bool {
+    !matches!(*self, Dismissal::NotOut | Dismissal::RetiredHurt)
+  }
+}
+
+impl FromStr for Dismissal {
+  type Err = ParseError;
+
+  fn from_str(s: &str) -> Result<Dismissal, ParseError> {
+    match s.to_lowercase().as_str() {
+      "not out" => Ok(Dismissal::NotOut),
+      "bowled" | "b" => Ok(Dismissal::Bowled),
+      "caught" | "c" => Ok(Dismissal::Caught),
+      "lbw" => Ok(Dismissal::Lbw),
+      "stumped" | "st" => Ok(Dismissal::Stumped),
+      "run out" => Ok(Dismissal::RunOut),
+      "hit wicket" => Ok(Dismissal::HitWicket),
+      "retired hurt" => Ok(Dismissal::RetiredHurt),
+      _ => Err(ParseError::Malformed(format!("Unknown dismissal {:?}", s)))
+    }
+  }
+}
+
+/* One line of a match scorecard, like "AN Cook, 21, caught, MA Starc"
+ * (batsman, runs, how they were out and the bowler). The bowler can be
+ * left off, or left empty, for innings with no bowler to credit like
+ * "not out" or "run out".
+ *
+ * A fifth item can give the team's score when the batsman was out,
+ * as in "AN Cook, 21, caught, MA Starc, 35", which is what
+ * partnerships are worked out from. */
+#[derive(Debug, Clone)]
+pub struct ScorecardEntry<'a> {
+  pub initials: &'a str,
+  pub surname: &'a str,
+  pub runs: u32,
+  pub dismissal: Dismissal,
+  pub bowler: Option<&'a str>,
+  pub fall: Option<u32>
+}
+
+impl<'a> ScorecardEntry<'a> {
+  pub fn parse(l: &'a str) -> Result<ScorecardEntry<'a>, ParseError> {
+    let v = line::split(l);
+    let (initials, surname) = line::name(v[0])?;
+
+    Ok(ScorecardEntry {
+      initials,
+      surname,
+      runs: line::number(&v, 1, "runs", "u32")?,
+      dismissal: line::item(&v, 2, "dismissal")?.parse()?,
+      bowler: v.get(3).cloned().filter(|b| !b.is_empty()),
+      fall: match v.get(4) {
+        Some(f) if !f.is_empty() => Some(line::number(&v, 4, "fall", "u32")?),
+        _ => None
+      }
+    })
+  }
+}
+
+const SCORECARD_FIELDS: &[&str] = &["initials", "surname", "runs", "dismissal", "bowler", "fall"];
+
+impl<'a> Record for ScorecardEntry<'a> {
+  fn field_names(&self) -> &'static [&'static str] {
+    SCORECARD_FIELDS
+  }
+
+  fn field(&self, name: &str) -> Option<Value<'_>> {
+    match name {
+      "initials" => Some(Value::Text(self.initials)),
+      "surname" => Some(Value::Text(self.surname)),
+      "runs" => Some(Value::Integer(self.runs)),
+      "dismissal" => Some(Value::Text(self.dismissal.name())),
+      "bowler" => self.bowler.map(Value::Text),
+      "fall" => self.fall.map(Value::Integer),
+      _ => None
+    }
+  }
+}
+
+/* A player's figures over every innings in a set of scorecards. The
+ * batsman is the same kind every other command works with, so these
+ * can go anywhere a batting file could. */
+#[derive(Debug, Clone)]
+pub struct Totals<'a> {
+  pub batsman: Batsman<'a>,
+  pub innings: u32,
+  pub not_outs: u32
+}
+
+/* Adds up each player's innings, keeping players in the order they
+ * first batted.
+ *
+ * An average is runs per dismissal, which leaves it undefined for a
+ * player who was never out. Such players are counted as out once, so
+ * their average is their runs: that keeps it a finite number, and one
+ * that validate agrees with. */
+pub fn totals<'a>(entries: &[ScorecardEntry<'a>]) -> Vec<Totals<'a>> {
+  let mut players: Vec<(ScorecardEntry<'a>, u32, u32)> = Vec::new();
+  for e in entries {
+    let out = if e.dismissal.is_out() { 1 } else { 0 };
+    match players.iter_mut().find(|p| p.0.initials == e.initials && p.0.surname == e.surname) {
+      Some(p) => {
+        p.0.runs += e.runs;
+        p.1 += 1;
+        p.2 += out;
+      },
+      None => players.push((e.clone(), 1, out))
+    }
+  }
+
+  players.into_iter().map(|(e, innings, outs)| Totals {
+    batsman: Batsman {
+      initials: e.initials,
+      surname: e.surname,
+      runs: e.runs,
+      average: average_of(e.runs, outs.max(1))
+    },
+    innings,
+    not_outs: innings - outs
+  }).collect()
+}
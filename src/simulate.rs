@@ -0,0 +1,108 @@
+use std::str::FromStr;
+
+use rng::Rng;
+use stats::{mean, quantile};
+
+/* How a batsman's scores are spread out around their average, which
+ * is what decides how likely the big scores and the ducks are */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Distribution {
+  /* Every ball is equally likely to get a batsman out whatever they
+   * have scored so far, which fits real scores surprisingly well */
+  Geometric,
+  /* The same idea without rounding to whole runs first */
+  Exponential,
+  /* A bell curve with the average as its standard deviation as well.
+   * Anything below zero counts as a duck, which lifts the mean score a
+   * little above the average. */
+  Normal
+}
+
+impl FromStr for Distribution {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Distribution, String> {
+    match s {
+      "geometric" => Ok(Distribution::Geometric),
+      "exponential" => Ok(Distribution::Exponential),
+      "normal" => Ok(Distribution::Normal),
+      other => Err(format!("Unknown distribution {:?}, expected geometric, exponential or normal", other))
+    }
+  }
+}
+
+/* 1 - next_f64(), which is in (0, 1] and so safe to take the log of */
+fn unit(rng: &mut Rng) -> f64 {
+  1.0 - rng.next_f64()
+}
+
+impl Distribution {
+  /* One score for a batsman with this average */
+  pub fn score(&self, average: f32, rng: &mut Rng) -> u32 {
+    let average = average.max(0.0) as f64;
+    if average == 0.0 {
+      return 0;
+    }
+    let runs = match *self {
+      Distribution::Geometric => {
+        /* Inverting the geometric distribution's CDF, with the chance
+         * of getting out chosen to make the mean come out right */
+        let p = 1.0 / (average + 1.0);
+        (unit(rng).ln() / (1.0 - p).ln()).floor()
+      },
+      Distribution::Exponential => (-average * unit(rng).ln()).round(),
+      Distribution::Normal => {
+        /* The Box-Muller transform turns two uniform numbers into a
+         * normally distributed one */
+        let z = (-2.0 * unit(rng).ln()).sqrt() * (2.0 * std::f64::consts::PI * rng.next_f64()).cos();
+        (average + average * z).round().max(0.0)
+      }
+    };
+    runs as u32
+  }
+}
+
+/* The result of playing the same side many times over */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Simulation {
+  /* Each batsman's mean score, in the order they were given */
+  pub expected: Vec<f32>,
+  /* The team total from every innings played */
+  pub totals: Vec<f32>
+}
+
+impl Simulation {
+  pub fn mean_total(&self) -> Option<f32> {
+    mean(&self.totals)
+  }
+
+  /* The range the middle confidence (0.95 for 95%) of team totals fell
+   * in */
+  pub fn interval(&self, confidence: f32) -> Option<(f32, f32)> {
+    let tail = (1.0 - confidence) / 2.0;
+    Some((quantile(&self.totals, tail)?, quantile(&self.totals, 1.0 - tail)?))
+  }
+}
+
+/* Plays innings innings for a side with these batting averages, every
+ * batsman getting one score each time. Extras and the way a real
+ * innings ends once ten wickets have gone are left out, so totals are
+ * the sum of the batsmen's scores and nothing more. */
+pub fn simulate(averages: &[f32], innings: usize, distribution: Distribution, rng: &mut Rng) -> Simulation {
+  let mut sums = vec![0.0f64; averages.len()];
+  let mut totals = Vec::with_capacity(innings);
+  for _ in 0..innings {
+    let mut total = 0;
+    for (sum, &average) in sums.iter_mut().zip(averages) {
+      let runs = distribution.score(average, rng);
+      *sum += runs as f64;
+      total += runs;
+    }
+    totals.push(total as f32);
+  }
+
+  Simulation {
+    expected: sums.iter().map(|&s| if innings == 0 { 0.0 } else { (s / innings as f64) as f32 }).collect(),
+    totals
+  }
+}
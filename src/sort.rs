@@ -0,0 +1,363 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "parallel")]
+use rayon::slice::ParallelSliceMut;
+
+use invariants::check_sorted;
+use record::Record;
+
+/* Rust's sort_by mutates the vector, we want to return a sorted one
+ * instead. We were given the vector, so it is ours to sort: no copy is
+ * made. The sort is stable, so equal elements keep their order. */
+pub fn sorted<T, F>(x: Vec<T>, mut cmp: F) -> Vec<T>
+  where F: FnMut(&T, &T) -> Ordering
+{
+  let mut y = x;
+  y.sort_by(&mut cmp);
+  debug_assert_eq!(check_sorted(&y, cmp), Ok(()));
+  y
+}
+
+/* Like sorted, but for when equal elements can come out in any order.
+ * sort_unstable_by needs no extra memory, where the stable sort
+ * allocates a buffer of half the length, and it is usually a little
+ * faster too. */
+pub fn sorted_unstable<T, F>(x: Vec<T>, mut cmp: F) -> Vec<T>
+  where F: FnMut(&T, &T) -> Ordering
+{
+  let mut y = x;
+  y.sort_unstable_by(&mut cmp);
+  debug_assert_eq!(check_sorted(&y, cmp), Ok(()));
+  y
+}
+
+/* Sorts a slice where it is, for callers who want to keep their
+ * vector (or only have a slice of one). unstable picks between the
+ * two sorts above. */
+pub fn sort_in_place<T, F>(x: &mut [T], unstable: bool, mut cmp: F)
+  where F: FnMut(&T, &T) -> Ordering
+{
+  if unstable {
+    x.sort_unstable_by(&mut cmp);
+  } else {
+    x.sort_by(&mut cmp);
+  }
+  debug_assert_eq!(check_sorted(x, cmp), Ok(()));
+}
+
+/* Below this many elements par_sorted sorts on one thread, since
+ * handing the work out to others costs more than it saves. The sort
+ * benchmark's "parallel" group shows where the two cross over. */
+#[cfg(feature = "parallel")]
+pub const PARALLEL_THRESHOLD: usize = 20_000;
+
+/* Like sorted (and just as stable), but with --features parallel the
+ * vector is split up and sorted on all of rayon's threads at once,
+ * then merged back together. The comparison is shared between the
+ * threads, so it has to be Sync and the elements Send. With only one
+ * thread to go round there is nothing to gain, so sorted is used. */
+#[cfg(feature = "parallel")]
+pub fn par_sorted<T, F>(x: Vec<T>, cmp: F) -> Vec<T>
+  where T: Send,
+        F: Fn(&T, &T) -> Ordering + Sync
+{
+  if x.len() < PARALLEL_THRESHOLD || rayon::current_num_threads() == 1 {
+    return sorted(x, cmp);
+  }
+  let mut y = x;
+  y.par_sort_by(&cmp);
+  debug_assert_eq!(check_sorted(&y, cmp), Ok(()));
+  y
+}
+
+/* An iterator over a slice in sorted order, which does the sorting as
+ * it goes. Building it puts the positions of the elements in a heap,
+ * which takes time proportional to the length, and each next() takes
+ * the smallest one off in time proportional to its logarithm. Taking
+ * the top ten of a million batsmen this way is much cheaper than
+ * sorting all of them, and the slice itself is never moved or copied.
+ *
+ * Draining the whole iterator is several times slower than sorting
+ * though, since hopping around the heap is hard on the cache, and
+ * unlike sorted it is not stable. It is for when only the first few
+ * are wanted. */
+pub struct SortedIter<'a, T: 'a, F> {
+  items: &'a [T],
+  heap: Vec<usize>,
+  cmp: F
+}
+
+pub fn sorted_iter<T, F>(x: &[T], cmp: F) -> SortedIter<'_, T, F>
+  where F: FnMut(&T, &T) -> Ordering
+{
+  let mut iter = SortedIter { items: x, heap: (0..x.len()).collect(), cmp };
+  for i in (0..x.len() / 2).rev() {
+    iter.sift_down(i);
+  }
+  iter
+}
+
+impl<'a, T, F> SortedIter<'a, T, F>
+  where F: FnMut(&T, &T) -> Ordering
+{
+  fn less(&mut self, a: usize, b: usize) -> bool {
+    (self.cmp)(&self.items[self.heap[a]], &self.items[self.heap[b]]) == Ordering::Less
+  }
+
+  /* Moves the element at i down until neither child is smaller */
+  fn sift_down(&mut self, mut i: usize) {
+    loop {
+      let (left, right) = (2 * i + 1, 2 * i + 2);
+      let mut smallest = i;
+      if left < self.heap.len() && self.less(left, smallest) {
+        smallest = left;
+      }
+      if right < self.heap.len() && self.less(right, smallest) {
+        smallest = right;
+      }
+      if smallest == i {
+        return;
+      }
+      self.heap.swap(i, smallest);
+      i = smallest;
+    }
+  }
+}
+
+impl<'a, T, F> Iterator for SortedIter<'a, T, F>
+  where F: FnMut(&T, &T) -> Ordering
+{
+  type Item = &'a T;
+
+  fn next(&mut self) -> Option<&'a T> {
+    if self.heap.is_empty() {
+      return None;
+    }
+    let top = self.heap.swap_remove(0);
+    self.sift_down(0);
+    Some(&self.items[top])
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.heap.len(), Some(self.heap.len()))
+  }
+}
+
+/* The same thing, but for any Record sorted on a named field. This
+ * way the sorting code does not need to know which struct it has. */
+pub fn sorted_by_field<T: Record>(x: Vec<T>, name: &str) -> Vec<T> {
+  sorted(x, |lhs, rhs| lhs.cmp_by(rhs, name))
+}
+
+/* Compares two records on a field, smallest first or largest first,
+ * and then breaks ties the same way SortSpec does: surname, then
+ * initials, then whichever comes first in the input (i and j are
+ * their positions in it). That makes the functions below give the same
+ * answer whichever order the records were in and whatever pivots
+ * quickselect picks. */
+fn by_field<T: Record>(l: (usize, &T), r: (usize, &T), name: &str, descending: bool) -> Ordering {
+  let o = l.1.cmp_by(r.1, name);
+  (if descending { o.reverse() } else { o })
+    .then_with(|| l.1.cmp_by(r.1, Field::Surname.name()))
+    .then_with(|| l.1.cmp_by(r.1, Field::Initials.name()))
+    .then(l.0.cmp(&r.0))
+}
+
+/* The record with the smallest or largest value of a field, found in
+ * one pass without sorting anything */
+pub fn min_by_field<'a, T: Record>(x: &'a [T], name: &str) -> Option<&'a T> {
+  x.iter().enumerate().min_by(|&l, &r| by_field(l, r, name, false)).map(|(_, r)| r)
+}
+
+pub fn max_by_field<'a, T: Record>(x: &'a [T], name: &str) -> Option<&'a T> {
+  x.iter().enumerate().min_by(|&l, &r| by_field(l, r, name, true)).map(|(_, r)| r)
+}
+
+/* The record that would be at position n (counting from 0) if x was
+ * sorted on a field, smallest first. It uses quickselect, which only
+ * sorts as much as it has to, so it takes time in proportion to the
+ * length rather than the length times its logarithm. Positions are
+ * shuffled around rather than the records, so x is left alone. */
+pub fn nth_by_field<'a, T: Record>(x: &'a [T], n: usize, name: &str) -> Option<&'a T> {
+  if n >= x.len() {
+    return None;
+  }
+  let mut positions = (0..x.len()).collect::<Vec<usize>>();
+  let (_, &mut nth, _) = positions.select_nth_unstable_by(n, |&l, &r| by_field((l, &x[l]), (r, &x[r]), name, false));
+  Some(&x[nth])
+}
+
+/* The first n records in sorted order, found the same way: quickselect
+ * splits off the n smallest, and only those get sorted. descending
+ * makes it the n largest, largest first. */
+pub fn top_by_field<'a, T: Record>(x: &'a [T], n: usize, name: &str, descending: bool) -> Vec<&'a T> {
+  let cmp = |&l: &(usize, &T), &r: &(usize, &T)| by_field(l, r, name, descending);
+  let mut top = x.iter().enumerate().collect::<Vec<(usize, &T)>>();
+  if n < top.len() {
+    top.select_nth_unstable_by(n, cmp);
+    top.truncate(n);
+  }
+  sorted(top, cmp).into_iter().map(|(_, r)| r).collect()
+}
+
+/* Every field that one of our records has, so that sort orders can be
+ * written down without stringly typed names */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Field {
+  Initials,
+  Surname,
+  Runs,
+  Average,
+  Wickets,
+  Date,
+  /* A score worked out with --score, see expr::Scored */
+  Score
+}
+
+impl Field {
+  /* The name Record::field knows this field by */
+  pub fn name(&self) -> &'static str {
+    match *self {
+      Field::Initials => "initials",
+      Field::Surname => "surname",
+      Field::Runs => "runs",
+      Field::Average => "average",
+      Field::Wickets => "wickets",
+      Field::Date => "date",
+      Field::Score => "score"
+    }
+  }
+}
+
+impl FromStr for Field {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Field, String> {
+    match s {
+      "initials" => Ok(Field::Initials),
+      "surname" => Ok(Field::Surname),
+      "runs" => Ok(Field::Runs),
+      "average" => Ok(Field::Average),
+      "wickets" => Ok(Field::Wickets),
+      "date" => Ok(Field::Date),
+      "score" => Ok(Field::Score),
+      other => Err(format!("Unknown field {:?}", other))
+    }
+  }
+}
+
+/* A sort order, built up one field at a time:
+ *
+ *   SortSpec::by(Field::Runs).desc().then(Field::Surname)
+ *
+ * sorts by runs, most first, and then alphabetically by surname for
+ * anyone on the same number of runs. asc and desc apply to whichever
+ * field was added last, and fields are ascending unless told
+ * otherwise. This saves writing comparison closures by hand, where it
+ * is easy to miss that |lhs, rhs| rhs.cmp(lhs) is the descending one.
+ *
+ * Records which are equal on every field in the spec are then put in
+ * order of surname and then initials (both ascending), so that the
+ * order never depends on how the input happened to be arranged or on
+ * which sort was used. Only records with the same name as well come
+ * out in the order they went in. */
+#[derive(Debug, Clone, PartialEq)]
+pub struct SortSpec {
+  keys: Vec<(Field, bool)>
+}
+
+impl SortSpec {
+  pub fn by(field: Field) -> SortSpec {
+    SortSpec { keys: vec![(field, false)] }
+  }
+
+  pub fn then(mut self, field: Field) -> SortSpec {
+    self.keys.push((field, false));
+    self
+  }
+
+  pub fn asc(self) -> SortSpec {
+    self.direction(false)
+  }
+
+  pub fn desc(self) -> SortSpec {
+    self.direction(true)
+  }
+
+  fn direction(mut self, descending: bool) -> SortSpec {
+    if let Some(last) = self.keys.last_mut() {
+      last.1 = descending;
+    }
+    self
+  }
+
+  /* The fields in the order they are compared */
+  pub fn fields(&self) -> Vec<Field> {
+    self.keys.iter().map(|&(f, _)| f).collect()
+  }
+
+  /* Compares two records field by field, moving on to the next field
+   * only when the ones so far are equal, and then by name */
+  pub fn compare<R: Record>(&self, lhs: &R, rhs: &R) -> Ordering {
+    self.compare_keys(lhs, rhs)
+      .then_with(|| lhs.cmp_by(rhs, Field::Surname.name()))
+      .then_with(|| lhs.cmp_by(rhs, Field::Initials.name()))
+  }
+
+  /* The same, but on the fields in the spec alone, without the names
+   * to break ties. This is what says whether two batsmen are tied for
+   * a rank. */
+  pub fn compare_keys<R: Record>(&self, lhs: &R, rhs: &R) -> Ordering {
+    self.keys.iter().fold(Ordering::Equal, |order, &(field, descending)| {
+      order.then_with(|| {
+        let o = lhs.cmp_by(rhs, field.name());
+        if descending { o.reverse() } else { o }
+      })
+    })
+  }
+
+  /* The comparison as a closure, ready to hand to sorted */
+  pub fn comparator<'s, R: Record>(&'s self) -> impl Fn(&R, &R) -> Ordering + 's {
+    move |lhs, rhs| self.compare(lhs, rhs)
+  }
+
+  /* compare_keys as a closure, ready to hand to rank */
+  pub fn key_comparator<'s, R: Record>(&'s self) -> impl Fn(&R, &R) -> Ordering + 's {
+    move |lhs, rhs| self.compare_keys(lhs, rhs)
+  }
+}
+
+/* Writes a sort order the way --sort-by takes it, so it can be
+ * parsed back in */
+impl fmt::Display for SortSpec {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let keys = self.keys.iter().map(|&(field, descending)| {
+      format!("{}:{}", field.name(), if descending { "desc" } else { "asc" })
+    }).collect::<Vec<String>>();
+    write!(f, "{}", keys.join(","))
+  }
+}
+
+/* Parses sort orders written like "runs:desc,surname" (each field
+ * with an optional :asc or :desc, separated by commas), which is what
+ * --sort-by takes */
+impl FromStr for SortSpec {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<SortSpec, String> {
+    let mut keys = Vec::new();
+    for part in s.split(',').map(|p| p.trim()) {
+      let mut pieces = part.splitn(2, ':');
+      let field = pieces.next().unwrap_or("").parse::<Field>()?;
+      let descending = match pieces.next() {
+        None | Some("asc") => false,
+        Some("desc") => true,
+        Some(other) => return Err(format!("Unknown direction {:?}, expected asc or desc", other))
+      };
+      keys.push((field, descending));
+    }
+    Ok(SortSpec { keys })
+  }
+}
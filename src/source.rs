@@ -0,0 +1,64 @@
+/* Where each line of some text came from, for text put together out
+ * of several files (each entry of a zip archive, say) which is then
+ * parsed as one, or turned into lines from another format. Records are
+ * parsed a line each, so the record at position i of what was parsed
+ * came from line i of the text:
+ *
+ *   let sources = Sources::single("2019.csv", 120);
+ *   let batsmen = parse_batsmen(&contents)?;
+ *   sources.line(0)      // Some(("2019.csv", 1)), where batsmen[0] was
+ *
+ * so anything which sorts or filters records and wants to say where
+ * they came from keeps hold of their positions as it goes. */
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Sources {
+  /* Each source, and the position of its first line in the text */
+  parts: Vec<(String, usize)>,
+  /* The number each line of the text had in its source, from 1 */
+  numbers: Vec<usize>
+}
+
+impl Sources {
+  /* The sources of text, given as the name of each one and the number
+   * of each of its lines there, in order. For a file which was read as
+   * it is they go 1, 2, 3 and so on, but a file turned into lines on
+   * the way in, like JSON, can have its records lines apart. */
+  pub fn new(parts: Vec<(String, Vec<usize>)>) -> Sources {
+    let mut sources = Sources::default();
+    for (name, numbers) in parts {
+      sources.parts.push((name, sources.numbers.len()));
+      sources.numbers.extend(numbers);
+    }
+    sources
+  }
+
+  /* The same for text with only one source, read as it is, of so many
+   * lines */
+  pub fn single(name: &str, lines: usize) -> Sources {
+    Sources::new(vec![(name.into(), (1..=lines).collect())])
+  }
+
+  /* Adds the sources of more text, which comes after this text */
+  pub fn append(&mut self, other: Sources) {
+    let first = self.numbers.len();
+    self.parts.extend(other.parts.into_iter().map(|(name, at)| (name, first + at)));
+    self.numbers.extend(other.numbers);
+  }
+
+  /* How many sources there are */
+  pub fn len(&self) -> usize {
+    self.parts.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.parts.is_empty()
+  }
+
+  /* The source of line i of the text (from 0), and which of its lines
+   * that is (from 1) */
+  pub fn line(&self, i: usize) -> Option<(&str, usize)> {
+    let number = *self.numbers.get(i)?;
+    let part = self.parts.iter().rposition(|&(_, first)| first <= i)?;
+    Some((&self.parts[part].0, number))
+  }
+}
@@ -0,0 +1,132 @@
+/* Summary statistics over plain slices of numbers. Callers pull the
+ * numbers they care about out of their records first, which keeps
+ * everything in here independent of Batsman and friends. */
+
+use std::cmp::Ordering;
+
+/* The arithmetic mean, or None for an empty slice since there is no
+ * sensible answer to "what is the average of nothing" */
+pub fn mean(xs: &[f32]) -> Option<f32> {
+  if xs.is_empty() {
+    None
+  } else {
+    Some(xs.iter().sum::<f32>() / xs.len() as f32)
+  }
+}
+
+/* The mean of every run of `window` consecutive values. The first
+ * window - 1 positions do not have enough history yet, so they are
+ * None, which keeps the output lined up with the input.
+ *
+ * .windows() hands us overlapping sub-slices without copying
+ * anything, so all we need to do is average each of them. */
+pub fn rolling_mean(xs: &[f32], window: usize) -> Vec<Option<f32>> {
+  if window == 0 {
+    return xs.iter().map(|_| None).collect();
+  }
+
+  let warmup = window.saturating_sub(1).min(xs.len());
+  (0..warmup).map(|_| None)
+    .chain(xs.windows(window).map(mean))
+    .collect()
+}
+
+/* The sample standard deviation (dividing by n - 1), which needs at
+ * least two values to mean anything */
+pub fn std_dev(xs: &[f32]) -> Option<f32> {
+  if xs.len() < 2 {
+    return None;
+  }
+
+  let m = mean(xs)?;
+  let squares = xs.iter().map(|x| (x - m) * (x - m)).sum::<f32>();
+  Some((squares / (xs.len() - 1) as f32).sqrt())
+}
+
+/* The value a fraction q of the way through the data, interpolating
+ * between the two nearest values when q falls between them. quantile
+ * of 0.5 is the median, 0.25 and 0.75 are the quartiles. */
+pub fn quantile(xs: &[f32], q: f32) -> Option<f32> {
+  if xs.is_empty() {
+    return None;
+  }
+
+  let mut sorted = xs.to_vec();
+  sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+  let position = q.clamp(0.0, 1.0) * (sorted.len() - 1) as f32;
+  let below = position.floor() as usize;
+  let above = position.ceil() as usize;
+  let fraction = position - below as f32;
+  Some(sorted[below] + (sorted[above] - sorted[below]) * fraction)
+}
+
+pub fn median(xs: &[f32]) -> Option<f32> {
+  quantile(xs, 0.5)
+}
+
+/* Pearson's correlation coefficient: +1 when ys goes up in a perfectly
+ * straight line with xs, -1 when it goes down in one, and around 0
+ * when there is no linear relationship at all. */
+pub fn pearson(xs: &[f32], ys: &[f32]) -> Option<f32> {
+  if xs.len() != ys.len() || xs.len() < 2 {
+    return None;
+  }
+
+  let mx = mean(xs)?;
+  let my = mean(ys)?;
+  let covariance = xs.iter().zip(ys).map(|(x, y)| (x - mx) * (y - my)).sum::<f32>();
+  let vx = xs.iter().map(|x| (x - mx) * (x - mx)).sum::<f32>();
+  let vy = ys.iter().map(|y| (y - my) * (y - my)).sum::<f32>();
+
+  if vx == 0.0 || vy == 0.0 {
+    None
+  } else {
+    Some(covariance / (vx.sqrt() * vy.sqrt()))
+  }
+}
+
+/* The least-squares straight line through the points, returned as
+ * (slope, intercept) so that y = slope * x + intercept */
+pub fn linear_fit(xs: &[f32], ys: &[f32]) -> Option<(f32, f32)> {
+  if xs.len() != ys.len() || xs.len() < 2 {
+    return None;
+  }
+
+  let mx = mean(xs)?;
+  let my = mean(ys)?;
+  let covariance = xs.iter().zip(ys).map(|(x, y)| (x - mx) * (y - my)).sum::<f32>();
+  let vx = xs.iter().map(|x| (x - mx) * (x - mx)).sum::<f32>();
+
+  if vx == 0.0 {
+    None
+  } else {
+    let slope = covariance / vx;
+    Some((slope, my - slope * mx))
+  }
+}
+
+/* For each value, the mean of every value whose span of years overlaps
+ * its own (its own included). With careers as the spans, that is the
+ * average of the era each player played in. */
+pub fn overlap_means(xs: &[f32], spans: &[(u32, u32)]) -> Vec<f32> {
+  spans.iter().map(|&(from, to)| {
+    let era = xs.iter().zip(spans)
+      .filter(|&(_, &(f, t))| f <= to && from <= t)
+      .map(|(&x, _)| x)
+      .collect::<Vec<f32>>();
+    mean(&era).unwrap_or(0.0)
+  }).collect()
+}
+
+/* Rescales each value by how its baseline compares with the mean of
+ * every value, so a value twice its baseline comes out as twice the
+ * overall mean. A baseline of zero leaves its value alone, since
+ * there is nothing to scale by. */
+pub fn normalize(xs: &[f32], baselines: &[f32]) -> Vec<f32> {
+  let overall = match mean(xs) {
+    Some(m) => m,
+    None => return Vec::new()
+  };
+  xs.iter().zip(baselines).map(|(&x, &b)| if b > 0.0 { x * overall / b } else { x }).collect()
+}
@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+
+use batsman::Batsman;
+use join::normalize_name;
+use numeric::average_f32;
+
+/* One problem with one line of the input. Lines are numbered from 1,
+ * the way an editor would show them. */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+  pub line: usize,
+  pub code: &'static str,
+  pub message: String
+}
+
+/* A batting average is runs divided by the number of times out, so
+ * there has to be some whole number of dismissals which gets us from
+ * the runs to the average. Averages are quoted to two decimal places,
+ * sometimes rounded and sometimes truncated, so we allow the true
+ * average to be anywhere within a hundredth of the quoted one and look
+ * for a whole number between the fewest and most dismissals that
+ * allows. */
+pub fn average_is_consistent(b: &Batsman) -> bool {
+  let average = average_f32(b.average);
+  if b.runs == 0 {
+    return average == 0.0;
+  }
+  if !average.is_finite() || average <= 0.0 {
+    return false;
+  }
+
+  let runs = b.runs as f64;
+  let average = average as f64;
+  let fewest = (runs / (average + 0.01)).ceil().max(1.0);
+  let most = if average > 0.01 { (runs / (average - 0.01)).floor() } else { runs };
+
+  fewest <= most
+}
+
+/* Checks every line of a batting file, collecting everything that is
+ * wrong rather than stopping at the first problem:
+ *
+ * - the line has to parse (names present, numbers are numbers)
+ * - the average has to be consistent with the runs
+ * - each player may only appear once
+ */
+pub fn validate(contents: &str) -> Vec<Violation> {
+  validate_with(contents, |i| format!("line {}", i + 1))
+}
+
+/* The same, with place saying where the line at index i (from 0) came
+ * from, for messages which point at another line. Text put together
+ * from several files, or converted from JSON, has other line numbers
+ * than the ones it was read with. */
+pub fn validate_with<F>(contents: &str, place: F) -> Vec<Violation>
+  where F: Fn(usize) -> String
+{
+  let mut violations = Vec::new();
+  let mut seen = HashMap::new();
+
+  for (i, l) in contents.lines().enumerate() {
+    let line = i + 1;
+
+    let b = match Batsman::parse(l) {
+      Ok(b) => b,
+      Err(e) => {
+        violations.push(Violation { line, code: "parse", message: e.to_string() });
+        continue;
+      }
+    };
+
+    if !average_is_consistent(&b) {
+      violations.push(Violation {
+        line,
+        code: "inconsistent-average",
+        message: format!("An average of {} is not possible with {} runs", b.average, b.runs)
+      });
+    }
+
+    let key = normalize_name(&format!("{} {}", b.initials, b.surname));
+    match seen.get(&key) {
+      Some(first) => violations.push(Violation {
+        line,
+        code: "duplicate-player",
+        message: format!("{} {} already appeared on {}", b.initials, b.surname, place(*first))
+      }),
+      None => {
+        seen.insert(key, i);
+      }
+    }
+  }
+
+  violations
+}
+
+/* A player who appears on two lines with different figures. Lines are
+ * numbered from 1, as with Violation. */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+  pub name: String,
+  pub first: usize,
+  pub second: usize
+}
+
+/* The first player listed twice with different runs or averages, if
+ * there is one. Unlike validate, a line repeated exactly does not
+ * count, since it cannot disagree with itself; and lines which do not
+ * parse are left for the parser to complain about. */
+pub fn conflicting_duplicate(contents: &str) -> Option<Conflict> {
+  let mut seen: HashMap<String, (usize, Batsman)> = HashMap::new();
+
+  for (i, l) in contents.lines().enumerate() {
+    let b = match Batsman::parse(l) {
+      Ok(b) => b,
+      Err(_) => continue
+    };
+
+    let key = normalize_name(&format!("{} {}", b.initials, b.surname));
+    match seen.get(&key) {
+      Some(&(first, ref f)) => if f.runs != b.runs || f.average != b.average {
+        return Some(Conflict {
+          name: format!("{} {}", f.initials, f.surname),
+          first,
+          second: i + 1
+        });
+      },
+      None => {
+        seen.insert(key, (i + 1, b));
+      }
+    }
+  }
+
+  None
+}
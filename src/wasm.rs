@@ -0,0 +1,46 @@
+use js_sys::JSON;
+use wasm_bindgen::prelude::*;
+
+use batsman::Batsman;
+use output::{Format, Style, Table};
+use record::Record;
+use sort::sorted;
+
+/* Parses a whole batting file, with the line number of the first bad
+ * line in the error */
+fn parse_all(text: &str) -> Result<Vec<Batsman<'_>>, JsValue> {
+  text.lines().enumerate().map(|(i, l)| {
+    Batsman::parse(l).map_err(|e| JsValue::from_str(&format!("Line {}: {}", i + 1, e)))
+  }).collect()
+}
+
+/* Hands records over to JavaScript as an array of plain objects. The
+ * JSON renderer already produces exactly that shape, so we let the
+ * browser parse its output rather than building the objects by hand. */
+fn to_js<R: Record>(records: &[R]) -> Result<JsValue, JsValue> {
+  JSON::parse(&Table::from_records(records).render(Format::Json, &Style::plain()))
+}
+
+/* parse_csv(text) in JavaScript: every batsman in a file, in file
+ * order, as {initials, surname, runs, average} objects. Throws if a
+ * line cannot be parsed. */
+#[wasm_bindgen]
+pub fn parse_csv(text: &str) -> Result<JsValue, JsValue> {
+  to_js(&parse_all(text)?)
+}
+
+/* leaderboard(text, field, limit) in JavaScript: the batsmen sorted
+ * best first on a field, cut down to limit of them (0 for all) */
+#[wasm_bindgen]
+pub fn leaderboard(text: &str, field: &str, limit: usize) -> Result<JsValue, JsValue> {
+  let batsmen = parse_all(text)?;
+  if batsmen.first().is_some_and(|b| b.field(field).is_none()) {
+    return Err(JsValue::from_str(&format!("Unknown field {:?}", field)));
+  }
+
+  let mut best = sorted(batsmen, |lhs, rhs| rhs.cmp_by(lhs, field));
+  if limit > 0 {
+    best.truncate(limit);
+  }
+  to_js(&best)
+}
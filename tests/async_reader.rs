@@ -0,0 +1,58 @@
+/* AsyncBatsmenReader should read the same batsmen as parsing the whole
+ * file does, however it is chunked. A byte slice is always ready, so
+ * these poll the stream by hand instead of starting a runtime. */
+extern crate futures_core;
+extern crate getting_started;
+
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+
+use futures_core::Stream;
+
+use getting_started::async_reader::{AsyncBatsmenReader, StreamError};
+use getting_started::dataset::ParsedDataset;
+
+fn collect(text: &[u8], chunk: usize) -> Vec<Result<ParsedDataset, StreamError>> {
+  let mut reader = AsyncBatsmenReader::new(text).chunk_size(chunk);
+  let mut cx = Context::from_waker(Waker::noop());
+  let mut chunks = Vec::new();
+  loop {
+    match Pin::new(&mut reader).poll_next(&mut cx) {
+      Poll::Ready(Some(chunk)) => chunks.push(chunk),
+      Poll::Ready(None) => return chunks,
+      Poll::Pending => panic!("a byte slice should never be pending")
+    }
+  }
+}
+
+fn text(chunks: &[Result<ParsedDataset, StreamError>]) -> String {
+  chunks.iter().flat_map(|c| c.as_ref().unwrap().iter().map(|b| b.to_string() + "\n").collect::<Vec<String>>()).collect()
+}
+
+const FILE: &str = "AN Cook, 11629, 46.33\nGA Gooch, 8900, 42.58\nDI Gower, 8231, 44.25\n";
+
+#[test]
+fn chunks_hold_every_batsman_in_order() {
+  for &chunk in &[1, 2, 3, 10] {
+    let chunks = collect(FILE.as_bytes(), chunk);
+    assert_eq!(chunks.len(), 3usize.div_ceil(chunk));
+    assert_eq!(text(&chunks), FILE);
+  }
+}
+
+#[test]
+fn bom_crlf_and_missing_last_newline_are_handled() {
+  let chunks = collect(b"\xef\xbb\xbfAN Cook, 11629, 46.33\r\nGA Gooch, 8900, 42.58", 10);
+  assert_eq!(text(&chunks), "AN Cook, 11629, 46.33\nGA Gooch, 8900, 42.58\n");
+}
+
+#[test]
+fn bad_lines_are_numbered_from_the_start_of_the_stream() {
+  let chunks = collect(b"AN Cook, 11629, 46.33\nGA Gooch, 8900, 42.58\nDI Gower, lots, 44.25\n", 2);
+  assert_eq!(chunks.len(), 2);
+  assert!(chunks[0].is_ok());
+  match chunks[1] {
+    Err(StreamError::Parse { line, .. }) => assert_eq!(line, 3),
+    ref other => panic!("expected a parse error, got {:?}", other.as_ref().map(|c| c.len()))
+  }
+}
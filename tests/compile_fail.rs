@@ -0,0 +1,13 @@
+/* The borrowck lessons show what the borrow checker allows. What it
+ * forbids is in tests/compile_fail: each file there has to fail to
+ * compile, with the error in the .stderr file next to it, so a
+ * toolchain which starts accepting one of them (or words the error
+ * differently) is noticed. After checking a change in wording is
+ * right, TRYBUILD=overwrite cargo test --test compile_fail updates the
+ * .stderr files. */
+extern crate trybuild;
+
+#[test]
+fn borrowck_examples_do_not_compile() {
+  trybuild::TestCases::new().compile_fail("tests/compile_fail/*.rs");
+}
@@ -0,0 +1,14 @@
+/* The lifetimes lesson, with cook kept after the line it borrows its
+ * names from has gone */
+extern crate getting_started;
+
+use getting_started::batsman::Batsman;
+
+fn main() {
+  let cook;
+  {
+    let line = String::from("AN Cook, 11629, 46.33");
+    cook = Batsman::parse(&line).unwrap();
+  }
+  println!("{}", cook.surname);
+}
@@ -0,0 +1,9 @@
+/* Handing back a reference to a String which is dropped on return */
+fn surname() -> &'static str {
+  let line = String::from("AN Cook, 11629, 46.33");
+  &line[3..7]
+}
+
+fn main() {
+  println!("{}", surname());
+}
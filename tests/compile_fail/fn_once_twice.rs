@@ -0,0 +1,7 @@
+/* The iterators lesson's hand_over, called twice */
+fn main() {
+  let surnames = vec![String::from("Cook"), String::from("Gooch")];
+  let hand_over = move || surnames;
+  println!("{}", hand_over().join(", "));
+  println!("{}", hand_over().join(", "));
+}
@@ -0,0 +1,39 @@
+/* The futures lesson's shared total, with std's Mutex in place of
+ * tokio's. An async block which holds a guard across an .await turns
+ * into a future which keeps the guard from one poll to the next, like
+ * AddRuns. (This crate is in the 2015 edition, where there are no
+ * async blocks, so the future is written out by hand.) */
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Mutex, MutexGuard};
+use std::task::{Context, Poll};
+
+struct AddRuns<'a> {
+  total: MutexGuard<'a, u32>,
+  waited: bool
+}
+
+impl<'a> Future for AddRuns<'a> {
+  type Output = ();
+
+  fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+    /* The .await, where the task might move to another thread */
+    if !self.waited {
+      self.waited = true;
+      cx.waker().wake_by_ref();
+      return Poll::Pending;
+    }
+    *self.total += 11629;
+    Poll::Ready(())
+  }
+}
+
+/* What tokio::spawn asks of a future */
+fn spawn<F: Future + Send + 'static>(_future: F) {
+}
+
+static TOTAL: Mutex<u32> = Mutex::new(0);
+
+fn main() {
+  spawn(AddRuns { total: TOTAL.lock().unwrap(), waited: false });
+}
@@ -0,0 +1,9 @@
+/* The lifetimes lesson's longer, without saying which argument the
+ * answer borrows from */
+fn longer(a: &str, b: &str) -> &str {
+  if a.len() >= b.len() { a } else { b }
+}
+
+fn main() {
+  println!("{}", longer("Cook", "Gooch"));
+}
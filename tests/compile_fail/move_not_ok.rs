@@ -0,0 +1,11 @@
+/* The moving lesson, using v after it has been moved into v2 */
+fn move_not_ok() {
+  let v = vec![1, 2, 3];
+  let v2 = v;
+
+  println!("{:?}", v);
+}
+
+fn main() {
+  move_not_ok();
+}
@@ -0,0 +1,16 @@
+/* The patterns lesson's match on Dismissal, missing the arm for NotOut */
+enum Dismissal {
+  Bowled,
+  Caught { fielder: &'static str },
+  NotOut
+}
+
+fn main() {
+  for out in [Dismissal::Bowled, Dismissal::Caught { fielder: "Knott" }, Dismissal::NotOut] {
+    let said = match out {
+      Dismissal::Bowled => "bowled".to_string(),
+      Dismissal::Caught { fielder } => format!("caught {}", fielder)
+    };
+    println!("{}", said);
+  }
+}
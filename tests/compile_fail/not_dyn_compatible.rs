@@ -0,0 +1,20 @@
+/* The traits lesson's Formatter, with a generic method which keeps it
+ * from being used as a trait object */
+use std::fmt::Display;
+
+trait Formatter {
+  fn format<T: Display>(&self, value: T) -> String;
+}
+
+struct Plain;
+
+impl Formatter for Plain {
+  fn format<T: Display>(&self, value: T) -> String {
+    value.to_string()
+  }
+}
+
+fn main() {
+  let formatters: Vec<Box<dyn Formatter>> = vec![Box::new(Plain)];
+  println!("{}", formatters.len());
+}
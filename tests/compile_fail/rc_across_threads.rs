@@ -0,0 +1,11 @@
+/* The concurrency lesson's shared total, with Rc in place of Arc */
+use std::rc::Rc;
+use std::sync::Mutex;
+use std::thread;
+
+fn main() {
+  let total = Rc::new(Mutex::new(0));
+  let shared = Rc::clone(&total);
+  thread::spawn(move || *shared.lock().unwrap() += 11629).join().unwrap();
+  println!("{}", total.lock().unwrap());
+}
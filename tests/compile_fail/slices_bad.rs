@@ -0,0 +1,21 @@
+/* The slices lesson, changing the String while slices of it are
+ * still in use */
+fn slice_fn(s: &str) {
+  println!("{:?}", s);
+}
+
+fn slices_bad() {
+  let mut s = String::from("hello world");
+
+  let hello = &s[0..5];
+  let world = &s[6..11];
+
+  s.clear();
+
+  slice_fn(hello);
+  slice_fn(world);
+}
+
+fn main() {
+  slices_bad();
+}
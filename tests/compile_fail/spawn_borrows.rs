@@ -0,0 +1,13 @@
+/* The concurrency lesson, with the thread borrowing line instead of
+ * taking it */
+extern crate getting_started;
+
+use std::thread;
+
+use getting_started::batsman::Batsman;
+
+fn main() {
+  let line = String::from("AN Cook, 11629, 46.33");
+  let handle = thread::spawn(|| Batsman::parse(&line).unwrap().runs);
+  println!("{}", handle.join().unwrap());
+}
@@ -0,0 +1,12 @@
+/* The unsafety lesson's split, written with two mutable borrows of
+ * the same slice instead of raw pointers */
+fn split_at_mut<T>(values: &mut [T], mid: usize) -> (&mut [T], &mut [T]) {
+  (&mut values[..mid], &mut values[mid..])
+}
+
+fn main() {
+  let mut runs = [11629, 8900, 8231, 8181];
+  let (england, rest) = split_at_mut(&mut runs, 2);
+  england[0] += 1;
+  rest[0] += 1;
+}
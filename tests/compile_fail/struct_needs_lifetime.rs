@@ -0,0 +1,10 @@
+/* Batsman without its lifetime parameter */
+pub struct Batsman {
+  pub initials: &str,
+  pub surname: &str,
+  pub runs: u32,
+  pub average: f32
+}
+
+fn main() {
+}
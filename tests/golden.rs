@@ -0,0 +1,130 @@
+/* Runs the batsmen command against the cases in tests/fixtures and
+ * compares what it prints with what it printed when the case was
+ * written. Each case is a directory holding:
+ *
+ * command  getting-started or borrowck to run that instead (optional,
+ *          otherwise batsmen)
+ * args     the arguments, one per line
+ * stdin    what to feed it on stdin (optional, otherwise nothing)
+ * stdout   what it should print
+ * stderr   what it should log, with the timestamps taken off
+ * status   the exit code it should give
+ * unless   features, one per line, which change the output (optional),
+ *          so the case is skipped when the tests are built with them
+ * needs    features, one per line, which the case cannot run without
+ *          (optional), so it is skipped unless the tests have them
+ *
+ * The command runs in tests/fixtures, so the arguments can name the
+ * input files kept there. When output changes on purpose, run
+ *
+ *   UPDATE_GOLDEN=1 cargo test --test golden
+ *
+ * to write the new output over the old, and check the diff before
+ * committing it. */
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+fn fixtures() -> PathBuf {
+  Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures")
+}
+
+/* Log lines start with a timestamp like "2016-01-01T12:00:00.123456Z",
+ * which would never match, so it is dropped */
+fn without_timestamps(stderr: &str) -> String {
+  stderr.lines().map(|l| {
+    match l.split_once(' ') {
+      Some((first, rest)) if first.len() > 20 && first.ends_with('Z') && first.as_bytes()[10] == b'T' => rest.trim_start(),
+      _ => l
+    }
+  }).map(|l| l.to_string() + "\n").collect()
+}
+
+/* Whether the tests were built with a feature, for the unless and
+ * needs files. Decimal averages keep the digits they were read with
+ * (42.00 rather than 42) and have no .0 in the Debug listing, for one,
+ * --script only works with scripting, and --copy only fails without
+ * clipboard. */
+fn enabled(feature: &str) -> bool {
+  match feature {
+    "decimal" => cfg!(feature = "decimal"),
+    "scripting" => cfg!(feature = "scripting"),
+    "clipboard" => cfg!(feature = "clipboard"),
+    _ => false
+  }
+}
+
+/* The features listed in one of a case's files */
+fn features(case: &Path, file: &str) -> Vec<String> {
+  fs::read_to_string(case.join(file)).unwrap_or_default().lines().map(|f| f.trim().to_string()).filter(|f| !f.is_empty()).collect()
+}
+
+/* The output of one case, in the same form as its golden files */
+fn run(case: &Path) -> Vec<(&'static str, String)> {
+  let args = fs::read_to_string(case.join("args")).expect("every case has an args file");
+  let stdin = fs::read(case.join("stdin")).unwrap_or_default();
+
+  let command = match fs::read_to_string(case.join("command")).unwrap_or_default().trim() {
+    "getting-started" => env!("CARGO_BIN_EXE_getting-started"),
+    "borrowck" => env!("CARGO_BIN_EXE_borrowck"),
+    _ => env!("CARGO_BIN_EXE_batsmen")
+  };
+
+  let mut child = Command::new(command)
+    .args(args.lines())
+    .current_dir(fixtures())
+    .env_remove("RUST_LOG")
+    .env_remove("BATSMEN_PLUGINS")
+    .env_remove("LC_ALL")
+    .env_remove("LC_MESSAGES")
+    .env_remove("LANG")
+    .stdin(Stdio::piped())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()
+    .expect("batsmen runs");
+  child.stdin.take().expect("stdin is piped").write_all(&stdin).expect("stdin can be written");
+  let output = child.wait_with_output().expect("batsmen finishes");
+
+  vec![
+    ("stdout", String::from_utf8_lossy(&output.stdout).into_owned()),
+    ("stderr", without_timestamps(&String::from_utf8_lossy(&output.stderr))),
+    ("status", format!("{}\n", output.status.code().unwrap_or(-1)))
+  ]
+}
+
+#[test]
+fn golden() {
+  let update = env::var_os("UPDATE_GOLDEN").is_some_and(|v| v == "1");
+
+  let mut cases = fs::read_dir(fixtures()).expect("tests/fixtures exists")
+    .filter_map(|e| e.ok().map(|e| e.path()))
+    .filter(|p| p.join("args").is_file())
+    .filter(|p| !features(p, "unless").iter().any(|f| enabled(f)))
+    .filter(|p| features(p, "needs").iter().all(|f| enabled(f)))
+    .collect::<Vec<PathBuf>>();
+  cases.sort();
+  assert!(!cases.is_empty(), "no cases in tests/fixtures");
+
+  let mut failures = Vec::new();
+  for case in &cases {
+    let name = case.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    for (file, actual) in run(case) {
+      let path = case.join(file);
+      if update {
+        fs::write(&path, &actual).expect("golden files can be written");
+        continue;
+      }
+      let expected = fs::read_to_string(&path).unwrap_or_default();
+      if actual != expected {
+        failures.push(format!("{}/{} differs\n--- expected\n{}--- actual\n{}", name, file, expected, actual));
+      }
+    }
+  }
+
+  assert!(failures.is_empty(),
+          "{} golden file(s) differ (UPDATE_GOLDEN=1 rewrites them):\n\n{}",
+          failures.len(), failures.join("\n"));
+}
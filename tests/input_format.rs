@@ -0,0 +1,64 @@
+extern crate getting_started;
+
+use getting_started::input::InputFormat;
+
+#[test]
+fn detects_csv() {
+  assert_eq!(InputFormat::detect(b"AN Cook, 11629, 46.33, 1\nGA Gooch, 8900, 42.58, 2\n"),
+             InputFormat::Csv);
+}
+
+#[test]
+fn detects_tsv() {
+  assert_eq!(InputFormat::detect(b"AN Cook\t11629\t46.33\t1\nGA Gooch\t8900\t42.58\t2\n"),
+             InputFormat::Tsv);
+}
+
+#[test]
+fn tabs_and_commas_go_to_whichever_is_more_common() {
+  assert_eq!(InputFormat::detect(b"Cook, AN\t11629\t46.33\t1\n"), InputFormat::Tsv);
+  assert_eq!(InputFormat::detect(b"AN Cook, 11629, 46.33\tnote\n"), InputFormat::Csv);
+}
+
+#[test]
+fn detects_json_array() {
+  assert_eq!(InputFormat::detect(b"  \n[\n  {\"surname\": \"Cook\"}\n]\n"), InputFormat::Json);
+}
+
+#[test]
+fn detects_ndjson() {
+  assert_eq!(InputFormat::detect(b"{\"surname\": \"Cook\"}\n\n{\"surname\": \"Gooch\"}\n"),
+             InputFormat::Ndjson);
+}
+
+#[test]
+fn multi_line_object_is_json() {
+  assert_eq!(InputFormat::detect(b"{\n  \"players\": []\n}\n"), InputFormat::Json);
+}
+
+#[test]
+fn skips_byte_order_mark() {
+  assert_eq!(InputFormat::detect(b"\xef\xbb\xbf[]"), InputFormat::Json);
+}
+
+#[test]
+fn empty_input_is_csv() {
+  assert_eq!(InputFormat::detect(b""), InputFormat::Csv);
+  assert_eq!(InputFormat::detect(b" \n \n"), InputFormat::Csv);
+}
+
+#[test]
+fn from_extension() {
+  assert_eq!(InputFormat::from_extension("data/batsmen.CSV"), Some(InputFormat::Csv));
+  assert_eq!(InputFormat::from_extension("batsmen.tsv"), Some(InputFormat::Tsv));
+  assert_eq!(InputFormat::from_extension("batsmen.json"), Some(InputFormat::Json));
+  assert_eq!(InputFormat::from_extension("batsmen.jsonl"), Some(InputFormat::Ndjson));
+  assert_eq!(InputFormat::from_extension("batsmen-data.txt"), None);
+  assert_eq!(InputFormat::from_extension("batsmen"), None);
+}
+
+#[test]
+fn from_str() {
+  assert_eq!("ndjson".parse::<InputFormat>(), Ok(InputFormat::Ndjson));
+  assert!("xml".parse::<InputFormat>().is_err());
+}
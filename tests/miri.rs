@@ -0,0 +1,39 @@
+/* Undefined behaviour does not have to crash, so the unsafe code in
+ * the borrowck lessons is run under Miri, which checks every pointer
+ * it follows:
+ *
+ *   cargo +nightly miri test --test miri
+ *
+ * Outside Miri these just run the code. The ignored test is unsound
+ * on purpose, to show Miri catching it with -- --ignored. */
+extern crate getting_started;
+
+#[allow(dead_code)]
+#[path = "../src/bin/getting-started/learn/lessons.rs"]
+mod lessons;
+#[allow(dead_code)]
+#[path = "../src/bin/getting-started/learn/measure.rs"]
+mod measure;
+
+#[test]
+fn unsafety_lesson_is_sound() {
+  (lessons::find("unsafety").unwrap().run)();
+}
+
+/* Both halves start at the beginning, so they overlap, and writing
+ * through one while the other is alive breaks &mut's promise */
+#[test]
+#[ignore]
+fn overlapping_split_is_undefined_behaviour() {
+  fn split_overlapping(values: &mut [u32]) -> (&mut [u32], &mut [u32]) {
+    let start = values.as_mut_ptr();
+    let len = values.len();
+    unsafe { (std::slice::from_raw_parts_mut(start, len), std::slice::from_raw_parts_mut(start, len)) }
+  }
+
+  let mut runs = [11629, 8900];
+  let (a, b) = split_overlapping(&mut runs);
+  a[0] += 1;
+  b[0] += 1;
+  assert_eq!(a[0], 11631);
+}
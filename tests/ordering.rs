@@ -0,0 +1,89 @@
+/* The order batsmen come out in should depend only on the batsmen,
+ * never on the order they went in or on which sort did the work.
+ * These shuffle small files full of ties and check that every
+ * shuffle gives exactly the same text. */
+extern crate proptest;
+extern crate getting_started;
+#[cfg(feature = "parallel")]
+extern crate rayon;
+
+use proptest::prelude::*;
+
+use getting_started::batsman::Batsman;
+use getting_started::sort::{Field, SortSpec, max_by_field, min_by_field, sorted, sorted_unstable, top_by_field};
+#[cfg(feature = "parallel")]
+use getting_started::sort::{PARALLEL_THRESHOLD, par_sorted};
+
+const NAMES: &[&str] = &[
+  "AN Cook", "AB Cook", "MC Cowdrey", "GA Gooch", "DI Gower", "G Boycott", "L Hutton", "H Sutcliffe"
+];
+
+/* A file and a shuffled copy of it. Nobody appears twice, but runs
+ * only come in three values, so most batsmen tie with someone. */
+fn shuffled_file() -> impl Strategy<Value = (Vec<String>, Vec<String>)> {
+  prop::sample::subsequence(NAMES.to_vec(), 1..NAMES.len())
+    .prop_flat_map(|names| {
+      let n = names.len();
+      (Just(names), prop::collection::vec(prop::sample::select(vec![100u32, 200, 300]), n))
+    })
+    .prop_map(|(names, runs)| {
+      names.iter().zip(runs).map(|(name, r)| format!("{}, {}, 40.00", name, r)).collect::<Vec<String>>()
+    })
+    .prop_flat_map(|lines| (Just(lines.clone()), Just(lines).prop_shuffle()))
+}
+
+fn parse(lines: &[String]) -> Vec<Batsman<'_>> {
+  lines.iter().map(|l| Batsman::parse(l).unwrap()).collect()
+}
+
+fn text<'a, I: IntoIterator<Item = &'a Batsman<'a>>>(batsmen: I) -> String {
+  batsmen.into_iter().map(|b| b.to_string() + "\n").collect()
+}
+
+proptest! {
+  #[test]
+  fn sorting_ignores_input_order((original, shuffled) in shuffled_file()) {
+    let spec = SortSpec::by(Field::Runs).desc();
+    let expected = sorted(parse(&original), spec.comparator());
+
+    prop_assert_eq!(text(&sorted(parse(&shuffled), spec.comparator())), text(&expected));
+    prop_assert_eq!(text(&sorted_unstable(parse(&shuffled), spec.comparator())), text(&expected));
+  }
+
+  #[test]
+  fn selection_ignores_input_order((original, shuffled) in shuffled_file()) {
+    let (a, b) = (parse(&original), parse(&shuffled));
+
+    prop_assert_eq!(text(top_by_field(&a, 3, "runs", true)), text(top_by_field(&b, 3, "runs", true)));
+    prop_assert_eq!(text(top_by_field(&a, 3, "runs", false)), text(top_by_field(&b, 3, "runs", false)));
+    prop_assert_eq!(text(max_by_field(&a, "runs")), text(max_by_field(&b, "runs")));
+    prop_assert_eq!(text(min_by_field(&a, "runs")), text(min_by_field(&b, "runs")));
+  }
+}
+
+#[test]
+fn ties_are_broken_by_surname_then_initials() {
+  let lines = ["MC Cowdrey, 100, 40.00", "AN Cook, 100, 40.00", "AB Cook, 100, 40.00", "GA Gooch, 200, 40.00"]
+    .iter().map(|l| l.to_string()).collect::<Vec<String>>();
+  let spec = SortSpec::by(Field::Runs).desc();
+
+  assert_eq!(text(&sorted(parse(&lines), spec.comparator())),
+             "GA Gooch, 200, 40.00\nAB Cook, 100, 40.00\nAN Cook, 100, 40.00\nMC Cowdrey, 100, 40.00\n");
+}
+
+/* Big enough for par_sorted to split the work up, with runs in few
+ * enough values that ties have to be broken the same way. It runs on
+ * a pool of its own so that there is more than one thread even on a
+ * single core. */
+#[cfg(feature = "parallel")]
+#[test]
+fn parallel_sort_matches_sorted() {
+  let lines = (0..PARALLEL_THRESHOLD * 3).map(|i| format!("AB Player{}, {}, 40.00", i, i * 7919 % 300))
+    .collect::<Vec<String>>();
+  let spec = SortSpec::by(Field::Runs).desc();
+
+  let pool = rayon::ThreadPoolBuilder::new().num_threads(4).build().unwrap();
+
+  assert_eq!(text(&pool.install(|| par_sorted(parse(&lines), spec.comparator()))),
+             text(&sorted(parse(&lines), spec.comparator())));
+}
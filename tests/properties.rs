@@ -0,0 +1,171 @@
+/* Properties of the parsers which should hold for any input, checked
+ * against a few hundred generated inputs each by proptest. When one
+ * fails, proptest shrinks the input down to the smallest one it can
+ * find that still fails, and prints it. */
+extern crate proptest;
+extern crate getting_started;
+
+use proptest::prelude::*;
+
+use getting_started::batsman::{Batsman, career_span};
+use getting_started::bowler::Bowler;
+use getting_started::coerce::Coercions;
+use getting_started::dataset::ParsedDataset;
+use getting_started::expr::Expr;
+use getting_started::generate::Generator;
+use getting_started::innings::Innings;
+use getting_started::input::InputFormat;
+use getting_started::invariants::{check_ranks, check_sorted};
+use getting_started::numeric::average_from_f32;
+use getting_started::scorecard::ScorecardEntry;
+use getting_started::source::Sources;
+use getting_started::rank::{RankMethod, rank};
+use getting_started::rng::Rng;
+use getting_started::sort::{Field, SortSpec};
+
+/* A batsman that can be written out. Averages are whole hundredths,
+ * since that is all the text form keeps. Some have no initials, which
+ * Display writes as just a surname. */
+fn batsman_parts() -> impl Strategy<Value = (String, String, u32, u32)> {
+  ("[A-Z]{0,3}", "[A-Z][a-z']{1,12}", any::<u32>(), 0..100_000u32)
+}
+
+fn field() -> impl Strategy<Value = Field> {
+  prop::sample::select(vec![
+    Field::Initials, Field::Surname, Field::Runs, Field::Average,
+    Field::Wickets, Field::Date, Field::Score
+  ])
+}
+
+proptest! {
+  #[test]
+  fn batsman_round_trips((initials, surname, runs, hundredths) in batsman_parts()) {
+    let average = hundredths as f32 / 100.0;
+    let builder = if initials.is_empty() { Batsman::builder() } else { Batsman::builder().initials(&initials) };
+    let b = builder.surname(&surname).runs(runs).average(average_from_f32(average).unwrap()).build().unwrap();
+
+    let line = b.to_string();
+    let parsed = Batsman::parse(&line).unwrap();
+    prop_assert_eq!(parsed.initials, b.initials);
+    prop_assert_eq!(parsed.surname, b.surname);
+    prop_assert_eq!(parsed.runs, b.runs);
+    prop_assert_eq!(format!("{:.2}", parsed.average), format!("{:.2}", b.average));
+  }
+
+  #[test]
+  fn parsers_never_panic(line in "\\PC*") {
+    let _ = Batsman::parse(&line);
+    let _ = Bowler::parse(&line);
+    let _ = Innings::parse(&line);
+    let _ = ScorecardEntry::parse(&line);
+  }
+
+  #[test]
+  fn comma_heavy_lines_never_panic(line in "[A-Za-z0-9 ,.\\-]{0,40}") {
+    let _ = Batsman::parse(&line);
+    let _ = Bowler::parse(&line);
+    let _ = Innings::parse(&line);
+    let _ = ScorecardEntry::parse(&line);
+  }
+
+  #[test]
+  fn dataset_agrees_with_parsing_each_line(parts in prop::collection::vec(batsman_parts(), 0..20)) {
+    let lines = parts.iter().map(|&(ref i, ref s, r, h)| format!("{} {}, {}, {:.2}", i, s, r, h as f32 / 100.0)).collect::<Vec<String>>();
+    let dataset = ParsedDataset::parse(lines.join("\n")).unwrap();
+
+    prop_assert_eq!(dataset.len(), lines.len());
+    for (b, l) in dataset.iter().zip(&lines) {
+      let expected = Batsman::parse(l).unwrap();
+      prop_assert_eq!((b.initials, b.surname, b.runs), (expected.initials, expected.surname, expected.runs));
+    }
+  }
+
+  #[test]
+  fn detect_never_panics(bytes in prop::collection::vec(any::<u8>(), 0..256)) {
+    let _ = InputFormat::detect(&bytes);
+  }
+
+  #[test]
+  fn expressions_never_panic(s in "[0-9a-z_+*/(). -]{0,40}") {
+    let _ = s.parse::<Expr>();
+  }
+
+  #[test]
+  fn deep_expressions_are_errors_not_overflows(depth in 0..5000usize) {
+    let s = format!("{}1{}", "(".repeat(depth), ")".repeat(depth));
+    prop_assert_eq!(s.parse::<Expr>().is_ok(), depth <= 64);
+  }
+
+  #[test]
+  fn sort_spec_round_trips(keys in prop::collection::vec((field(), any::<bool>()), 1..5)) {
+    let mut spec = SortSpec::by(keys[0].0);
+    spec = if keys[0].1 { spec.desc() } else { spec.asc() };
+    for &(f, descending) in &keys[1..] {
+      spec = spec.then(f);
+      spec = if descending { spec.desc() } else { spec.asc() };
+    }
+    prop_assert_eq!(spec.to_string().parse::<SortSpec>(), Ok(spec));
+  }
+
+  #[test]
+  fn generated_lines_parse(seed in any::<u64>()) {
+    for line in Generator::new(Rng::new(seed)).take(50) {
+      prop_assert!(Batsman::parse(&line).is_ok(), "{:?} does not parse", line);
+      let (debut, last) = career_span(&line).unwrap().unwrap();
+      prop_assert!(debut <= last);
+    }
+  }
+
+  #[test]
+  fn corrupted_lines_do_not(seed in any::<u64>()) {
+    for line in Generator::new(Rng::new(seed)).corrupt(1.0).take(50) {
+      prop_assert!(Batsman::parse(&line).is_err(), "{:?} parses", line);
+    }
+  }
+
+  #[test]
+  fn coercing_mends_separators_and_nothing_else(seed in any::<u64>()) {
+    let coercions = "runs: strip_commas; average: strip_percent".parse::<Coercions>().unwrap();
+    for line in Generator::new(Rng::new(seed)).take(50) {
+      prop_assert_eq!(coercions.apply(&line), line);
+    }
+    for line in Generator::new(Rng::new(seed)).corrupt(1.0).take(50).filter(|l| l.contains('"')) {
+      prop_assert!(Batsman::parse(&coercions.apply(&line)).is_ok(), "{:?} does not parse", coercions.apply(&line));
+    }
+  }
+
+  #[test]
+  fn batsmen_are_traced_back_to_their_file(sizes in prop::collection::vec(0..20usize, 1..5), seed in any::<u64>()) {
+    let mut lines = Generator::new(Rng::new(seed));
+    let files = sizes.iter().map(|&n| lines.by_ref().take(n).map(|l| l + "\n").collect::<String>()).collect::<Vec<String>>();
+    let contents = files.concat();
+    let sources = Sources::new(sizes.iter().enumerate().map(|(f, &n)| (format!("{}.csv", f), (1..=n).collect())).collect());
+
+    let batsmen = contents.lines().map(|l| Batsman::parse(l).unwrap()).collect::<Vec<Batsman>>();
+    for (i, b) in batsmen.iter().enumerate() {
+      let (file, line) = sources.line(i).unwrap();
+      let f = file.trim_end_matches(".csv").parse::<usize>().unwrap();
+      prop_assert_eq!(&Batsman::parse(files[f].lines().nth(line - 1).unwrap()).unwrap(), b);
+    }
+    prop_assert_eq!(sources.line(batsmen.len()), None);
+  }
+
+  #[test]
+  fn ranks_keep_their_invariants(mut runs in prop::collection::vec(0..20u32, 0..50)) {
+    runs.sort();
+    for &method in &[RankMethod::Standard, RankMethod::Dense, RankMethod::Ordinal] {
+      prop_assert_eq!(check_ranks(&rank(&runs, method, |a, b| a.cmp(b)), method), Ok(()));
+    }
+  }
+
+  #[test]
+  fn unsorted_and_gaps_are_caught(mut runs in prop::collection::vec(any::<u32>(), 2..50)) {
+    runs.sort();
+    runs.dedup();
+    prop_assume!(runs.len() > 1);
+    runs.reverse();
+    prop_assert_eq!(check_sorted(&runs, |a, b| a.cmp(b)).unwrap_err().index, 1);
+    let ranks = (1..=runs.len() as u32).map(|r| r * 2).collect::<Vec<u32>>();
+    prop_assert!(check_ranks(&ranks, RankMethod::Dense).is_err());
+  }
+}